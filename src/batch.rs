@@ -0,0 +1,408 @@
+//! Look up lists of titles or DOIs in bulk, e.g. to get citation counts of a publication list.
+
+use std::io::BufRead;
+
+use id::{parse_doi, ClusterId};
+use matcher::TitleMatch;
+use paper::Paper;
+use errors::*;
+
+/// An entry of a list to look up, e.g. a line of a publication list.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LookupInput {
+    /// Free-text title, matched against titles of search results.
+    Title(String),
+    /// DOI, e.g. "10.1098/rspa.1984.0023", searched as is.
+    Doi(String),
+}
+
+impl LookupInput {
+    /// Take `entry` as a DOI if it is one with an optional `doi:` prefix, or a DOI URL,
+    /// and otherwise as a title.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use scholar::batch::LookupInput;
+    ///
+    /// assert_eq!(
+    ///     LookupInput::parse("https://doi.org/10.1098/rspa.1984.0023"),
+    ///     LookupInput::Doi(String::from("10.1098/rspa.1984.0023"))
+    /// );
+    /// assert_eq!(
+    ///     LookupInput::parse(" Quantal phase factors accompanying adiabatic changes "),
+    ///     LookupInput::Title(String::from("Quantal phase factors accompanying adiabatic changes"))
+    /// );
+    /// ```
+    pub fn parse(entry: &str) -> Self {
+        let entry = entry.trim();
+        let doi = match entry.get(..4) {
+            Some(prefix) if prefix.eq_ignore_ascii_case("doi:") => entry[4..].trim_start(),
+            _ => entry,
+        };
+        // Titles have spaces, while DOIs and their URLs do not
+        match parse_doi(doi) {
+            Some(parsed) if !doi.contains(char::is_whitespace) => LookupInput::Doi(parsed),
+            _ => LookupInput::Title(entry.to_owned()),
+        }
+    }
+
+    /// The title or the DOI.
+    pub fn as_str(&self) -> &str {
+        match *self {
+            LookupInput::Title(ref title) => title,
+            LookupInput::Doi(ref doi) => doi,
+        }
+    }
+}
+
+/// Read entries to look up from the first column of `csv`, one row per line,
+/// parsing each with `LookupInput::parse()`.
+///
+/// A plain list of one title or DOI per line is read as well.
+/// Empty rows are skipped, and so is the first row if it is a header
+/// of `title`, `doi`, or `entry`. Quoted fields may contain commas and doubled quotes,
+/// but not line breaks.
+///
+/// # Example
+///
+/// ```
+/// use scholar::batch::{read_inputs, LookupInput};
+///
+/// let csv = "title,year\n\
+///            \"Space, time, and gravity\",1984\n\
+///            10.1098/rspa.1984.0023,1984\n";
+/// assert_eq!(
+///     read_inputs(csv.as_bytes()).unwrap(),
+///     vec![
+///         LookupInput::Title(String::from("Space, time, and gravity")),
+///         LookupInput::Doi(String::from("10.1098/rspa.1984.0023")),
+///     ]
+/// );
+/// ```
+pub fn read_inputs<R: BufRead>(csv: R) -> Result<Vec<LookupInput>> {
+    const HEADERS: &[&str] = &["title", "doi", "entry"];
+
+    let mut inputs = Vec::new();
+    for (i, line) in csv.lines().enumerate() {
+        let field = first_csv_field(&line?);
+        let field = field.trim();
+        if field.is_empty() || (i == 0 && HEADERS.iter().any(|h| field.eq_ignore_ascii_case(h))) {
+            continue;
+        }
+        inputs.push(LookupInput::parse(field));
+    }
+    Ok(inputs)
+}
+
+/// The first field of a CSV row, unquoted.
+fn first_csv_field(row: &str) -> String {
+    let row = row.trim_start();
+    if !row.starts_with('"') {
+        return row.split(',').nth(0).unwrap_or("").to_owned();
+    }
+
+    let mut field = String::new();
+    let mut chars = row[1..].chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => break,
+            c => field.push(c),
+        }
+    }
+    field
+}
+
+/// The paper of `doi` among `papers` found by searching it.
+///
+/// A paper whose DOI is `doi`, compared case-insensitively, is the match.
+/// Otherwise, the sole paper found is taken, since Google Scholar lists only the paper
+/// for a DOI it knows, though without its DOI unless linked from the publisher's page.
+/// Matches have `confidence` of 1. Returns `None` if nothing is found,
+/// or if several papers are found and none of them has `doi`.
+pub fn match_doi(doi: &str, papers: &[Paper]) -> Option<TitleMatch> {
+    let paper = papers
+        .iter()
+        .find(|p| p.doi.as_ref().map_or(false, |d| d.eq_ignore_ascii_case(doi)))
+        .or_else(|| if papers.len() == 1 { papers.first() } else { None })?;
+    Some(TitleMatch {
+        paper: paper.clone(),
+        confidence: 1.0,
+    })
+}
+
+/// How an entry was resolved.
+#[derive(Debug)]
+pub enum LookupOutcome {
+    /// The paper matching the entry.
+    Matched(TitleMatch),
+    /// No paper matches the entry confidently enough, with the best one found if any.
+    Unresolved(Option<TitleMatch>),
+    /// Looking up the entry failed.
+    Failed(Error),
+}
+
+/// An entry looked up, reported by `lookup_batch()`.
+#[derive(Debug)]
+pub struct BatchEntry {
+    /// Position of the entry in the inputs, counted from 0.
+    pub index: usize,
+    pub input: LookupInput,
+    pub outcome: LookupOutcome,
+}
+
+impl BatchEntry {
+    /// The matched paper, if resolved.
+    pub fn matched_paper(&self) -> Option<&Paper> {
+        match self.outcome {
+            LookupOutcome::Matched(ref m) => Some(&m.paper),
+            _ => None,
+        }
+    }
+
+    /// Cluster ID of the matched paper, if resolved and known.
+    pub fn cluster_id(&self) -> Option<ClusterId> {
+        self.matched_paper().and_then(|paper| paper.cluster_id)
+    }
+
+    /// Citation count of the matched paper, if resolved and shown.
+    pub fn citation_count(&self) -> Option<u32> {
+        self.matched_paper()
+            .and_then(|paper| paper.citation_count)
+            .map(|c| c.value)
+    }
+}
+
+/// Iterator over entries looked up, in the order of inputs. See `lookup_batch()`.
+pub struct BatchLookup<I, F> {
+    inputs: I,
+    min_confidence: f64,
+    resolve: F,
+    index: usize,
+    blocked: bool,
+}
+
+/// Look up each of `inputs` with `resolve`, one at a time, yielding entries as they resolve.
+///
+/// `resolve` takes an entry and returns the best paper found for it if any,
+/// e.g. by `Client::lookup()`, which waits as the rate limiter of the client decides.
+/// Matches scored below `min_confidence` are reported as `LookupOutcome::Unresolved`.
+/// An error of `resolve` is reported for its entry only, and the others are still looked up,
+/// except that `ErrorKind::Blocked` ends the iteration, since the following requests
+/// would be blocked as well. Resume later from the last `BatchEntry::index`, which failed.
+///
+/// # Example
+///
+/// ```
+/// use scholar::batch::{lookup_batch, BatchReport, LookupInput};
+/// use scholar::matcher::TitleMatch;
+/// use scholar::paper::Paper;
+///
+/// let inputs = vec![
+///     LookupInput::parse("Space, time, and gravity"),
+///     LookupInput::parse("Unknown manuscript"),
+/// ];
+/// let report = lookup_batch(inputs, 0.8, |input| {
+///     Ok(match input.as_str() {
+///         "Space, time, and gravity" => Some(TitleMatch {
+///             paper: Paper::new("Space, time, and gravity", 42),
+///             confidence: 1.0,
+///         }),
+///         _ => None,
+///     })
+/// }).collect::<BatchReport>();
+///
+/// assert_eq!(report.matched().count(), 1);
+/// assert_eq!(report.entries[0].cluster_id().unwrap().as_u64(), 42);
+/// assert_eq!(report.unresolved().count(), 1);
+/// ```
+pub fn lookup_batch<I, F>(inputs: I, min_confidence: f64, resolve: F) -> BatchLookup<I::IntoIter, F>
+where
+    I: IntoIterator<Item = LookupInput>,
+    F: FnMut(&LookupInput) -> Result<Option<TitleMatch>>,
+{
+    BatchLookup {
+        inputs: inputs.into_iter(),
+        min_confidence,
+        resolve,
+        index: 0,
+        blocked: false,
+    }
+}
+
+impl<I, F> Iterator for BatchLookup<I, F>
+where
+    I: Iterator<Item = LookupInput>,
+    F: FnMut(&LookupInput) -> Result<Option<TitleMatch>>,
+{
+    type Item = BatchEntry;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.blocked {
+            return None;
+        }
+        let input = self.inputs.next()?;
+
+        let outcome = match (self.resolve)(&input) {
+            Ok(Some(m)) => {
+                if m.confidence >= self.min_confidence {
+                    LookupOutcome::Matched(m)
+                } else {
+                    LookupOutcome::Unresolved(Some(m))
+                }
+            }
+            Ok(None) => LookupOutcome::Unresolved(None),
+            Err(e) => {
+                if let ErrorKind::Blocked(_) = *e.kind() {
+                    warn!("Blocked at entry {}; stopping the batch", self.index);
+                    self.blocked = true;
+                }
+                LookupOutcome::Failed(e)
+            }
+        };
+
+        let entry = BatchEntry {
+            index: self.index,
+            input,
+            outcome,
+        };
+        self.index += 1;
+        Some(entry)
+    }
+}
+
+/// Entries looked up, collected from `lookup_batch()`.
+///
+/// Write it with `export::batch_report_to_csv()`.
+#[derive(Debug, Default)]
+pub struct BatchReport {
+    /// Entries in the order of inputs.
+    pub entries: Vec<BatchEntry>,
+}
+
+impl BatchReport {
+    /// Entries resolved to papers.
+    pub fn matched<'a>(&'a self) -> impl Iterator<Item = &'a BatchEntry> + 'a {
+        self.entries
+            .iter()
+            .filter(|entry| entry.matched_paper().is_some())
+    }
+
+    /// Entries not resolved, including ones failed to be looked up.
+    pub fn unresolved<'a>(&'a self) -> impl Iterator<Item = &'a BatchEntry> + 'a {
+        self.entries
+            .iter()
+            .filter(|entry| entry.matched_paper().is_none())
+    }
+
+    /// Sum of citation counts of the matched papers, unknown ones taken as 0.
+    pub fn total_citations(&self) -> u64 {
+        self.entries
+            .iter()
+            .map(|entry| u64::from(entry.citation_count().unwrap_or(0)))
+            .sum()
+    }
+}
+
+impl ::std::iter::FromIterator<BatchEntry> for BatchReport {
+    fn from_iter<I: IntoIterator<Item = BatchEntry>>(entries: I) -> Self {
+        Self {
+            entries: entries.into_iter().collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use scrape::BlockKind;
+
+    #[test]
+    fn lookup_input_test() {
+        let doi = |s: &str| LookupInput::Doi(s.to_owned());
+        let title = |s: &str| LookupInput::Title(s.to_owned());
+
+        assert_eq!(LookupInput::parse("10.1098/rspa.1984.0023"), doi("10.1098/rspa.1984.0023"));
+        assert_eq!(LookupInput::parse("DOI: 10.1098/rspa.1984.0023"), doi("10.1098/rspa.1984.0023"));
+        assert_eq!(
+            LookupInput::parse("https://doi.org/10.1103/PhysRevLett.13.508"),
+            doi("10.1103/PhysRevLett.13.508")
+        );
+        assert_eq!(
+            LookupInput::parse("Erratum to 10.1098/rspa.1984.0023"),
+            title("Erratum to 10.1098/rspa.1984.0023")
+        );
+        assert_eq!(LookupInput::parse("Doing it right"), title("Doing it right"));
+
+        let csv = "Title\n\
+                   \n\
+                   \"A \"\"quoted\"\" title, with a comma\",2001\n  \
+                   doi:10.1234/foo\n\
+                   \"\",empty\n";
+        assert_eq!(
+            read_inputs(csv.as_bytes()).unwrap(),
+            vec![title("A \"quoted\" title, with a comma"), doi("10.1234/foo")]
+        );
+        // Only the first row may be a header
+        assert_eq!(read_inputs("foo\ntitle\n".as_bytes()).unwrap(), vec![title("foo"), title("title")]);
+    }
+
+    #[test]
+    fn match_doi_test() {
+        let mut with_doi = Paper::new("foo", 1);
+        with_doi.doi = Some(String::from("10.1234/FOO"));
+        let papers = vec![Paper::new("bar", 2), with_doi];
+        assert_eq!(match_doi("10.1234/foo", &papers).unwrap().paper.title, "foo");
+        assert_eq!(match_doi("10.1234/baz", &papers), None);
+        assert_eq!(match_doi("10.1234/baz", &papers[..1]).unwrap().paper.title, "bar");
+        assert_eq!(match_doi("10.1234/baz", &[]), None);
+    }
+
+    #[test]
+    fn lookup_batch_test() {
+        let inputs = vec!["foo", "bar", "baz", "qux", "quux"]
+            .into_iter()
+            .map(LookupInput::parse);
+        let mut looked_up = Vec::new();
+        let report = lookup_batch(inputs, 0.8, |input| {
+            looked_up.push(input.as_str().to_owned());
+            let title_match = |id, confidence| {
+                let mut paper = Paper::new(input.as_str(), id);
+                paper.citation_count = Some((id as u32).into());
+                Some(TitleMatch { paper, confidence })
+            };
+            match input.as_str() {
+                "foo" => Ok(title_match(1, 0.9)),
+                "bar" => Ok(title_match(2, 0.5)),
+                "baz" => Err(ErrorKind::Http(String::from("500")).into()),
+                "qux" => Err(ErrorKind::Blocked(BlockKind::Captcha).into()),
+                _ => Ok(title_match(5, 1.0)),
+            }
+        }).collect::<BatchReport>();
+
+        // Entries after blocking are not looked up
+        assert_eq!(looked_up, vec!["foo", "bar", "baz", "qux"]);
+        assert_eq!(report.entries.iter().map(|e| e.index).collect::<Vec<_>>(), vec![0, 1, 2, 3]);
+
+        assert_eq!(report.matched().map(|e| e.index).collect::<Vec<_>>(), vec![0]);
+        assert_eq!(report.entries[0].cluster_id(), Some(ClusterId(1)));
+        assert_eq!(report.entries[0].citation_count(), Some(1));
+        assert_eq!(report.total_citations(), 1);
+
+        assert_eq!(report.unresolved().map(|e| e.index).collect::<Vec<_>>(), vec![1, 2, 3]);
+        match report.entries[1].outcome {
+            LookupOutcome::Unresolved(Some(ref m)) => assert_eq!(m.confidence, 0.5),
+            ref outcome => panic!("unexpected outcome: {:?}", outcome),
+        }
+        match report.entries[2].outcome {
+            LookupOutcome::Failed(Error(ErrorKind::Http(_), _)) => {}
+            ref outcome => panic!("unexpected outcome: {:?}", outcome),
+        }
+        assert_eq!(report.entries[2].cluster_id(), None);
+    }
+}
@@ -2,38 +2,22 @@ use clap::ArgMatches;
 
 #[derive(Clone)]
 pub struct Config {
-    pub max_result_count: Option<u32>,
-    pub recursive_depth: u32,
-    pub output_format: OutputFormat,
+    pub json: bool,
     pub verbose: bool,
 }
 
-#[derive(Clone)]
-pub enum OutputFormat {
-    HumanReadable,
-    Json,
-}
-
 impl Config {
     pub fn new(matches: &ArgMatches) -> Self {
-        use std::cmp;
-
-        let recursive_depth = cmp::min(
-            value_t!(matches, "recursive", u32).unwrap_or(0),
-            super::MAX_RECURSIVE_DEPTH,
-        );
-
-        let output_format = if recursive_depth > 0 || matches.is_present("json") {
-            OutputFormat::Json
-        } else {
-            OutputFormat::HumanReadable
-        };
-
         Self {
-            max_result_count: value_t!(matches, "count", u32).ok(),
-            recursive_depth,
-            output_format,
+            json: matches.is_present("json"),
             verbose: matches.is_present("verbose"),
         }
     }
+
+    /// Print `url` to be fetched in verbose mode, to stderr not to mix it with the output.
+    pub fn log_url(&self, url: &str) {
+        if self.verbose {
+            eprintln!("Fetching {}", url);
+        }
+    }
 }
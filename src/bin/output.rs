@@ -0,0 +1,149 @@
+use std::fmt::Write;
+
+use serde_json;
+
+use scholar::graph::CitationGraph;
+use scholar::paper::Paper;
+use scholar::profile::{AuthorProfile, ProfileMetric};
+
+use config::Config;
+use errors::*;
+
+pub fn print_papers(papers: &[Paper], cfg: &Config) -> Result<()> {
+    if cfg.json {
+        println!("{}", serde_json::to_string_pretty(papers)?);
+    } else {
+        print!("{}", papers_table(papers));
+    }
+    Ok(())
+}
+
+pub fn print_paper(paper: &Paper, cfg: &Config) -> Result<()> {
+    if cfg.json {
+        println!("{}", serde_json::to_string_pretty(paper)?);
+    } else {
+        println!("{}", paper);
+    }
+    Ok(())
+}
+
+pub fn print_paper_with_citers(paper: &Paper, cfg: &Config) -> Result<()> {
+    if cfg.json {
+        return print_paper(paper, cfg);
+    }
+
+    println!("{}\n", paper);
+    println!("... is cited by:\n");
+    print!("{}", papers_table(paper.citers.as_ref().map_or(&[], |c| &c[..])));
+    Ok(())
+}
+
+pub fn print_graph(graph: &CitationGraph, cfg: &Config) -> Result<()> {
+    let mut papers = graph.papers.values().cloned().collect::<Vec<_>>();
+    papers.sort_by_key(|p| p.cluster_id);
+
+    if cfg.json {
+        let edges = graph
+            .edges
+            .iter()
+            .map(|&(citing, cited)| json!([citing.as_u64(), cited.as_u64()]))
+            .collect::<Vec<_>>();
+        let graph = json!({
+            "papers": papers,
+            "edges": edges,
+            "truncated": graph.truncated,
+        });
+        println!("{}", serde_json::to_string_pretty(&graph)?);
+        return Ok(());
+    }
+
+    print!("{}", papers_table(&papers));
+    println!("\nCitations (citing -> cited):\n");
+    for &(citing, cited) in &graph.edges {
+        println!("{} -> {}", citing, cited);
+    }
+    if graph.truncated {
+        println!("\n(stopped at the maximum number of requests)");
+    }
+    Ok(())
+}
+
+pub fn print_profile(profile: &AuthorProfile, articles: &[Paper], cfg: &Config) -> Result<()> {
+    if cfg.json {
+        let profile = json!({
+            "profile": profile,
+            "articles": articles,
+        });
+        println!("{}", serde_json::to_string_pretty(&profile)?);
+        return Ok(());
+    }
+
+    println!("{}", profile.name);
+    if let Some(ref affiliation) = profile.affiliation {
+        println!("{}", affiliation);
+    }
+    if !profile.interests.is_empty() {
+        println!("Interests: {}", profile.interests.join(", "));
+    }
+
+    let since = profile
+        .recent_since
+        .map_or_else(|| String::from("Recent"), |y| format!("Since {}", y));
+    println!("\n{:<12} {:>10} {:>10}", "", "All", since);
+    for &(label, metric) in &[
+        ("Citations", profile.citations),
+        ("h-index", profile.h_index),
+        ("i10-index", profile.i10_index),
+    ] {
+        if let Some(ProfileMetric { all, recent }) = metric {
+            println!("{:<12} {:>10} {:>10}", label, all, recent);
+        }
+    }
+
+    println!();
+    print!("{}", papers_table(articles));
+    Ok(())
+}
+
+/// Rows of cluster ID, published year, citation count, and title, with a header.
+fn papers_table(papers: &[Paper]) -> String {
+    fn cell<T: ToString>(v: &Option<T>) -> String {
+        v.as_ref().map_or_else(|| String::from("-"), ToString::to_string)
+    }
+
+    let mut table = format!("{:<20} {:>4} {:>8}  {}\n", "CLUSTER ID", "YEAR", "CITED", "TITLE");
+    for paper in papers {
+        let citations = paper.citation_count.map(|c| c.value);
+        writeln!(
+            table,
+            "{:<20} {:>4} {:>8}  {}",
+            cell(&paper.cluster_id),
+            cell(&paper.year),
+            cell(&citations),
+            paper.title
+        ).unwrap();
+    }
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn papers_table_test() {
+        let mut berry = Paper::new("Quantal phase factors accompanying adiabatic changes", 42);
+        berry.year = Some(1984);
+        berry.citation_count = Some(12_345.into());
+        let mut unknown = Paper::new("Unknown", 0);
+        unknown.set_cluster_id(None);
+
+        assert_eq!(
+            papers_table(&[berry, unknown]),
+            "CLUSTER ID           YEAR    CITED  TITLE\n\
+             42                   1984    12345  \
+             Quantal phase factors accompanying adiabatic changes\n\
+             -                       -        -  Unknown\n"
+        );
+    }
+}
@@ -2,220 +2,362 @@
 extern crate clap;
 #[macro_use]
 extern crate error_chain;
+#[macro_use]
 extern crate serde_json;
 
 extern crate scholar;
 
 use std::fs;
 
-use clap::{App, Arg, ArgGroup, ArgMatches};
+use clap::{App, AppSettings, Arg, ArgGroup, ArgMatches, SubCommand};
 
-use scholar::MAX_RESULT_COUNT;
-use scholar::request::{send_request, ClusterQuery, SearchQuery};
-use scholar::scrape::{CitationDocument, ClusterDocument, SearchDocument};
+use scholar::client::Client;
+use scholar::graph::CrawlOptions;
+use scholar::id::ClusterId;
+use scholar::request::{profile_url, ScholarQuery, SortOrder};
+use scholar::scrape::{CitationDocument, ClusterDocument, PapersDocument, ProfileDocument,
+                      SearchDocument};
 
 mod config;
 mod errors;
-mod scrape;
+mod output;
 
 use config::Config;
 use errors::*;
 
-const MAX_RECURSIVE_DEPTH: u32 = 5;
+const DEFAULT_COUNT: usize = 5;
+const MAX_COUNT: usize = 100;
+const MAX_DEPTH: u32 = 5;
 
 quick_main!(run);
 
 fn run() -> Result<()> {
     let matches = app().get_matches();
+    let client = Client::new();
+
+    match matches.subcommand() {
+        ("search", Some(m)) => search(&client, m, &Config::new(m)),
+        ("cites", Some(m)) => cites(&client, m, &Config::new(m)),
+        ("cluster", Some(m)) => cluster(&client, m, &Config::new(m)),
+        ("profile", Some(m)) => profile(&client, m, &Config::new(m)),
+        _ => unreachable!(), // SubcommandRequiredElseHelp
+    }
+}
 
-    if !query_exists(&matches) {
-        use clap::{Error, ErrorKind};
+fn search(client: &Client, matches: &ArgMatches, cfg: &Config) -> Result<()> {
+    let count = value_t!(matches, "count", usize).unwrap_or(DEFAULT_COUNT); // validated in app()
 
-        app().print_help()?;
-        println!("\n");
-        Error::with_description("Missing query", ErrorKind::MissingRequiredArgument).exit();
-    }
+    let papers = if let Some(file) = matches.value_of("html") {
+        let doc = checked(SearchDocument::from_read(fs::File::open(file)?)?)?;
+        let mut papers = doc.scrape_papers()?;
+        papers.truncate(count);
+        papers
+    } else {
+        let sort_order = if matches.is_present("sort-by-date") {
+            SortOrder::Date
+        } else {
+            SortOrder::Relevance
+        };
+        let query = ScholarQuery::new()
+            .query(&search_words(matches))
+            .year_range(
+                value_t!(matches, "since", u32).ok(),
+                value_t!(matches, "until", u32).ok(),
+            )
+            .sort_by(sort_order);
+        cfg.log_url(&query.to_url());
+        client
+            .search_iter(&query, count)
+            .collect::<scholar::errors::Result<Vec<_>>>()?
+    };
 
-    let cfg = Config::new(&matches);
+    output::print_papers(&papers, cfg)
+}
 
-    if matches.is_present("cluster-id") {
-        let cluster_id = value_t!(matches, "cluster-id", u64).unwrap(); // validated in app()
-        let query = ClusterQuery::new(cluster_id);
-        let body = send_request(&query, cfg.verbose)?;
-        let doc = ClusterDocument::from(&*body);
-        scrape::scrape_cluster_doc(&doc, &cfg)?;
+fn cites(client: &Client, matches: &ArgMatches, cfg: &Config) -> Result<()> {
+    let depth = value_t!(matches, "depth", u32).unwrap_or(1); // validated in app()
 
-        return Ok(());
+    if let Some(file) = matches.value_of("html") {
+        let doc = checked(CitationDocument::from_read(fs::File::open(file)?)?)?;
+        return output::print_paper_with_citers(&doc.scrape_target_paper_with_citers()?, cfg);
     }
 
-    if let Some(cite_file) = matches.value_of("cite-html") {
-        let file = fs::File::open(cite_file)?;
-        let doc = CitationDocument::from_read(file)?;
-        scrape::scrape_citaiton_doc(&doc, &cfg)?;
-
-        return Ok(());
+    let cluster_id = value_t!(matches, "cluster-id", u64).unwrap(); // validated in app()
+    cfg.log_url(&ScholarQuery::new().cites(cluster_id).to_url());
+
+    if depth > 1 {
+        let options = CrawlOptions {
+            depth,
+            max_requests: value_t!(matches, "max-requests", u32).unwrap_or(10),
+        };
+        let jobs = value_t!(matches, "jobs", usize).unwrap_or(1); // validated in app()
+        let graph = if jobs > 1 {
+            client.crawl_citations_parallel(ClusterId(cluster_id), &options, jobs)?
+        } else {
+            client.crawl_citations(ClusterId(cluster_id), &options)?
+        };
+        output::print_graph(&graph, cfg)
+    } else {
+        let doc = checked(client.citations(cluster_id)?)?;
+        output::print_paper_with_citers(&doc.scrape_target_paper_with_citers()?, cfg)
     }
+}
 
-    let search_doc = if let Some(search_file) = matches.value_of("search-html") {
-        let file = fs::File::open(search_file)?;
-        SearchDocument::from_read(file)?
+fn cluster(client: &Client, matches: &ArgMatches, cfg: &Config) -> Result<()> {
+    let doc = if let Some(file) = matches.value_of("html") {
+        ClusterDocument::from_read(fs::File::open(file)?)?
     } else {
-        let mut query = SearchQuery::default();
-
-        if let Some(count) = cfg.max_result_count {
-            query.set_count(count);
-        }
-        if let Some(words) = matches.value_of("words") {
-            query.set_words(words);
-        }
-        if let Some(phrase) = matches.value_of("phrase") {
-            query.set_phrase(phrase);
-        }
-        if let Some(authors) = matches.value_of("authors") {
-            query.set_authors(authors);
-        }
-        if matches.is_present("title-only") {
-            query.set_title_only(true);
-        }
-
-        let body = send_request(&query, cfg.verbose)?;
-        SearchDocument::from(&*body)
+        let cluster_id = value_t!(matches, "cluster-id", u64).unwrap(); // validated in app()
+        cfg.log_url(&ScholarQuery::new().cluster(cluster_id).to_url());
+        client.cluster(cluster_id)?
+    };
+
+    let doc = checked(doc)?;
+    output::print_paper(&doc.scrape_target_paper()?, cfg)
+}
+
+fn profile(client: &Client, matches: &ArgMatches, cfg: &Config) -> Result<()> {
+    let doc = if let Some(file) = matches.value_of("html") {
+        ProfileDocument::from_read(fs::File::open(file)?)?
+    } else {
+        let user_id = matches.value_of("user-id").unwrap(); // required in app()
+        cfg.log_url(&profile_url(user_id, 0, 100));
+        client.profile(user_id)?
     };
 
-    scrape::scrape_search_doc(&search_doc, &cfg)?;
+    let doc = checked(doc)?;
+    output::print_profile(&doc.scrape_profile()?, &doc.scrape_articles()?, cfg)
+}
+
+fn checked<D: PapersDocument>(doc: D) -> Result<D> {
+    if let Some(kind) = doc.block_kind() {
+        bail!(ErrorKind::Scholar(scholar::errors::ErrorKind::Blocked(kind)));
+    }
+    Ok(doc)
+}
+
+/// Words to search for, with the phrase and the authors written in Google Scholar's operators.
+fn search_words(matches: &ArgMatches) -> String {
+    let mut words: Vec<String> = matches
+        .values_of("words")
+        .map(|w| w.map(ToOwned::to_owned).collect())
+        .unwrap_or_default();
+    if let Some(phrase) = matches.value_of("phrase") {
+        words.push(format!(r#""{}""#, phrase));
+    }
+    if let Some(authors) = matches.value_of("authors") {
+        words.push(format!(r#"author:"{}""#, authors));
+    }
 
-    Ok(())
+    let words = words.join(" ");
+    if matches.is_present("title-only") {
+        format!("allintitle: {}", words)
+    } else {
+        words
+    }
 }
 
 fn app() -> App<'static, 'static> {
     App::new(env!("CARGO_PKG_NAME"))
         .version(crate_version!())
+        .setting(AppSettings::SubcommandRequiredElseHelp)
+        .setting(AppSettings::VersionlessSubcommands)
         .arg(
-            Arg::with_name("count")
-                .short("c")
-                .long("count")
-                .help("Maximum number of search results (default = 5)")
-                .takes_value(true)
-                .validator(|v| match v.parse::<u32>() {
-                    Ok(v) if v > MAX_RESULT_COUNT => Err(format!(
-                        "The value is too large; exceeding {}",
-                        MAX_RESULT_COUNT
-                    )),
-                    Ok(v) if v > 0 => Ok(()),
-                    _ => Err(String::from("The value is not a positive integer")),
-                })
-                .display_order(0),
-        )
-        .arg(
-            Arg::with_name("words")
-                .short("w")
-                .long("words")
-                .help("Search papers with these words")
-                .takes_value(true)
-                .display_order(1),
-        )
-        .arg(
-            Arg::with_name("phrase")
-                .short("p")
-                .long("phrase")
-                .help("Search papers with this exact phrase")
-                .takes_value(true)
-                .display_order(2),
+            Arg::with_name("json")
+                .long("json")
+                .help("Output in JSON format instead of a table")
+                .global(true),
         )
         .arg(
-            Arg::with_name("authors")
-                .short("a")
-                .long("authors")
-                .help("Search papers with these authors")
-                .takes_value(true)
-                .display_order(3),
+            Arg::with_name("verbose")
+                .short("v")
+                .long("verbose")
+                .help("Verbose mode")
+                .global(true),
         )
-        .arg(
-            Arg::with_name("title-only")
-                .short("t")
-                .long("title-only")
-                .help(
-                    "Search only papers which contain specified words in their title \
-                     (default = false)",
+        .subcommand(
+            SubCommand::with_name("search")
+                .about("Search papers")
+                .arg(
+                    Arg::with_name("words")
+                        .help("Search papers with these words")
+                        .multiple(true),
                 )
-                .display_order(4),
-        )
-        .group(
-            ArgGroup::with_name("search-query")
-                .args(&["words", "phrase", "authors"])
-                .multiple(true)
-                .conflicts_with_all(&["cluster-id", "html"]),
-        )
-        .arg(
-            Arg::with_name("cluster-id")
-                .long("cluster-id")
-                .help("Search a paper with this cluster ID")
-                .takes_value(true)
-                .validator(|v| match v.parse::<u64>() {
-                    Ok(_) => Ok(()),
-                    _ => Err(String::from("The value is not an integer")),
-                })
-                .conflicts_with("html")
-                .display_order(10),
-        )
-        .arg(
-            Arg::with_name("search-html")
-                .long("search-html")
-                .help(
+                .arg(
+                    Arg::with_name("count")
+                        .short("c")
+                        .long("count")
+                        .help("Maximum number of search results (default = 5)")
+                        .takes_value(true)
+                        .validator(|v| match v.parse::<usize>() {
+                            Ok(v) if v > MAX_COUNT => Err(format!(
+                                "The value is too large; exceeding {}",
+                                MAX_COUNT
+                            )),
+                            Ok(v) if v > 0 => Ok(()),
+                            _ => Err(String::from("The value is not a positive integer")),
+                        })
+                        .display_order(0),
+                )
+                .arg(
+                    Arg::with_name("phrase")
+                        .short("p")
+                        .long("phrase")
+                        .help("Search papers with this exact phrase")
+                        .takes_value(true)
+                        .display_order(1),
+                )
+                .arg(
+                    Arg::with_name("authors")
+                        .short("a")
+                        .long("authors")
+                        .help("Search papers with these authors")
+                        .takes_value(true)
+                        .display_order(2),
+                )
+                .arg(
+                    Arg::with_name("title-only")
+                        .short("t")
+                        .long("title-only")
+                        .help(
+                            "Search only papers which contain specified words in their title \
+                             (default = false)",
+                        )
+                        .display_order(3),
+                )
+                .arg(year_arg("since", "Search papers published in or after this year"))
+                .arg(year_arg("until", "Search papers published in or before this year"))
+                .arg(
+                    Arg::with_name("sort-by-date")
+                        .long("sort-by-date")
+                        .help("List papers added recently first, instead of relevant ones")
+                        .conflicts_with("html"),
+                )
+                .group(
+                    ArgGroup::with_name("search-query")
+                        .args(&["words", "phrase", "authors"])
+                        .multiple(true)
+                        .conflicts_with("html"),
+                )
+                .arg(html_arg(
                     "Scrape this HTML file as a search results page \
                      (possibly useful only when debugging)",
-                )
-                .value_name("file")
-                .display_order(90),
+                ))
+                .group(
+                    ArgGroup::with_name("source")
+                        .args(&["words", "phrase", "authors", "html"])
+                        .multiple(true)
+                        .required(true),
+                ),
         )
-        .arg(
-            Arg::with_name("cite-html")
-                .long("cite-html")
-                .help(
+        .subcommand(
+            SubCommand::with_name("cites")
+                .about("List papers citing a paper")
+                .arg(cluster_id_arg("List papers citing a paper of this cluster ID"))
+                .arg(
+                    Arg::with_name("depth")
+                        .short("d")
+                        .long("depth")
+                        .help("Follow citations recursively up to this depth (default = 1)")
+                        .takes_value(true)
+                        .validator(|v| match v.parse::<u32>() {
+                            Ok(v) if v > MAX_DEPTH => Err(format!(
+                                "The value is too large; exceeding {}",
+                                MAX_DEPTH
+                            )),
+                            Ok(v) if v > 0 => Ok(()),
+                            _ => Err(String::from("The value is not a positive integer")),
+                        })
+                        .conflicts_with("html")
+                        .display_order(0),
+                )
+                .arg(
+                    Arg::with_name("max-requests")
+                        .long("max-requests")
+                        .help("Fetch at most this number of pages when following citations")
+                        .takes_value(true)
+                        .validator(|v| match v.parse::<u32>() {
+                            Ok(v) if v > 0 => Ok(()),
+                            _ => Err(String::from("The value is not a positive integer")),
+                        })
+                        .requires("depth")
+                        .display_order(1),
+                )
+                .arg(
+                    Arg::with_name("jobs")
+                        .short("j")
+                        .long("jobs")
+                        .help(
+                            "Fetch up to this number of pages in parallel \
+                             when following citations (default = 1)",
+                        )
+                        .takes_value(true)
+                        .validator(|v| match v.parse::<usize>() {
+                            Ok(v) if v > 0 => Ok(()),
+                            _ => Err(String::from("The value is not a positive integer")),
+                        })
+                        .requires("depth")
+                        .display_order(2),
+                )
+                .arg(html_arg(
                     "Scrape this HTML file as a citers list page \
                      (possibly useful only when debugging)",
-                )
-                .value_name("file")
-                .display_order(91),
+                )),
         )
-        .group(ArgGroup::with_name("html").args(&["search-html", "cite-html"]))
-        .arg(
-            Arg::with_name("json")
-                .long("json")
-                .help("Output in JSON format")
-                .display_order(20),
+        .subcommand(
+            SubCommand::with_name("cluster")
+                .about("Show a paper of a cluster ID")
+                .arg(cluster_id_arg("Show a paper of this cluster ID"))
+                .arg(html_arg(
+                    "Scrape this HTML file as a versions list page \
+                     (possibly useful only when debugging)",
+                )),
         )
-        .arg(
-            Arg::with_name("recursive")
-                .short("r")
-                .long("recursive")
-                .help(
-                    "Search papers recursively with this depth (0-based). \
-                     JSON output is enabled automatically.",
+        .subcommand(
+            SubCommand::with_name("profile")
+                .about("Show an author profile and its articles")
+                .arg(
+                    Arg::with_name("user-id")
+                        .help("Show the profile of this user ID, e.g. qc6CJjYAAAAJ")
+                        .required_unless("html")
+                        .conflicts_with("html"),
                 )
-                .takes_value(true)
-                .validator(|v| match v.parse::<u32>() {
-                    Ok(v) if v > MAX_RECURSIVE_DEPTH => Err(format!(
-                        "The value is too large; exceeding {}",
-                        MAX_RECURSIVE_DEPTH
-                    )),
-                    Ok(_) => Ok(()),
-                    _ => Err(String::from("The value is not a zero or positive integer")),
-                })
-                .display_order(21),
-        )
-        .arg(
-            Arg::with_name("verbose")
-                .short("v")
-                .long("verbose")
-                .help("Verbose mode")
-                .display_order(22),
+                .arg(html_arg(
+                    "Scrape this HTML file as an author profile page \
+                     (possibly useful only when debugging)",
+                )),
         )
 }
 
-fn query_exists(matches: &ArgMatches) -> bool {
-    matches.is_present("search-query") || matches.is_present("html")
-        || matches.is_present("cluster-id")
+fn cluster_id_arg(help: &'static str) -> Arg<'static, 'static> {
+    Arg::with_name("cluster-id")
+        .help(help)
+        .required_unless("html")
+        .conflicts_with("html")
+        .validator(|v| match v.parse::<u64>() {
+            Ok(_) => Ok(()),
+            _ => Err(String::from("The value is not an integer")),
+        })
+}
+
+fn year_arg(name: &'static str, help: &'static str) -> Arg<'static, 'static> {
+    Arg::with_name(name)
+        .long(name)
+        .help(help)
+        .value_name("year")
+        .validator(|v| match v.parse::<u32>() {
+            Ok(_) => Ok(()),
+            _ => Err(String::from("The value is not a year")),
+        })
+        .display_order(4)
+}
+
+fn html_arg(help: &'static str) -> Arg<'static, 'static> {
+    Arg::with_name("html")
+        .long("html")
+        .help(help)
+        .value_name("file")
+        .display_order(90)
 }
 
 #[cfg(test)]
@@ -223,98 +365,49 @@ mod tests {
     use super::*;
 
     #[test]
-    fn app_arg_conflict_test() {
-        use clap::ErrorKind::ArgumentConflict;
+    fn app_args_test() {
+        use clap::ErrorKind::{ArgumentConflict, MissingRequiredArgument};
 
-        assert_eq!(
-            app()
-                .get_matches_from_safe(&["prog", "--words", "foo", "--cluster-id", "0"])
-                .unwrap_err()
-                .kind,
-            ArgumentConflict
-        );
+        let kind = |args: &[&str]| app().get_matches_from_safe(args).unwrap_err().kind;
 
+        assert_eq!(kind(&["prog", "search"]), MissingRequiredArgument);
         assert_eq!(
-            app()
-                .get_matches_from_safe(&[
-                    "prog",
-                    "--phrase",
-                    r#""foo bar""#,
-                    "--search-html",
-                    "foo.html"
-                ])
-                .unwrap_err()
-                .kind,
+            kind(&["prog", "search", "foo", "--html", "foo.html"]),
             ArgumentConflict
         );
-
+        assert_eq!(kind(&["prog", "cites"]), MissingRequiredArgument);
         assert_eq!(
-            app()
-                .get_matches_from_safe(&["prog", "--authors", "foo", "--cite-html", "foo.html"])
-                .unwrap_err()
-                .kind,
+            kind(&["prog", "cites", "0", "--html", "foo.html"]),
             ArgumentConflict
         );
+        assert_eq!(kind(&["prog", "profile"]), MissingRequiredArgument);
+
+        let matches = app()
+            .get_matches_from_safe(&["prog", "cites", "42", "--json", "-d", "2"])
+            .unwrap();
+        let (name, cites) = matches.subcommand();
+        assert_eq!(name, "cites");
+        let cites = cites.unwrap();
+        assert_eq!(value_t!(cites, "cluster-id", u64).unwrap(), 42);
+        assert_eq!(value_t!(cites, "depth", u32).unwrap(), 2);
+        assert!(Config::new(cites).json);
+
+        assert!(app()
+            .get_matches_from_safe(&["prog", "profile", "--html", "foo.html"])
+            .is_ok());
+    }
 
-        assert_eq!(
-            app()
-                .get_matches_from_safe(&["prog", "--cluster-id", "0", "--search-html", "foo.html"])
-                .unwrap_err()
-                .kind,
-            ArgumentConflict
-        );
+    #[test]
+    fn search_words_test() {
+        let words = |args: &[&str]| {
+            let matches = app().get_matches_from(args);
+            search_words(matches.subcommand_matches("search").unwrap())
+        };
 
+        assert_eq!(words(&["prog", "search", "quantum", "theory"]), "quantum theory");
         assert_eq!(
-            app()
-                .get_matches_from_safe(&[
-                    "prog",
-                    "--search-html",
-                    "foo.html",
-                    "--cite-html",
-                    "foo.html"
-                ])
-                .unwrap_err()
-                .kind,
-            ArgumentConflict
+            words(&["prog", "search", "-p", "geometric phase", "-a", "m berry", "-t"]),
+            r#"allintitle: "geometric phase" author:"m berry""#
         );
     }
-
-    #[test]
-    fn query_exists_test() {
-        assert!(query_exists(&app().get_matches_from(&["prog", "--words", "foo"])));
-
-        assert!(query_exists(&app().get_matches_from(&[
-            "prog",
-            "--phrase",
-            r#""foo bar""#
-        ])));
-
-        assert!(query_exists(&app().get_matches_from(&[
-            "prog",
-            "--authors",
-            "foo"
-        ])));
-
-        assert!(query_exists(&app().get_matches_from(&[
-            "prog",
-            "--cluster-id",
-            "0"
-        ])));
-
-        assert!(query_exists(&app().get_matches_from(&[
-            "prog",
-            "--search-html",
-            "foo.html"
-        ])));
-
-        assert!(query_exists(&app().get_matches_from(&[
-            "prog",
-            "--cite-html",
-            "foo.html"
-        ])));
-
-        assert!(!query_exists(&app().get_matches_from(&["prog"])));
-
-        assert!(!query_exists(&app().get_matches_from(&["prog", "--count", "1"])));
-    }
 }
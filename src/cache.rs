@@ -0,0 +1,242 @@
+//! Cache of fetched pages on disk.
+
+use std::fs::{self, File};
+use std::io::{self, BufRead, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, SystemTime};
+
+use errors::*;
+
+/// Counter making names of temporary files unique within a process.
+static NEXT_TMP: AtomicUsize = AtomicUsize::new(0);
+
+/// Raw pages keyed by URL, stored in files of a directory and expiring after a TTL,
+/// so that crawling the same pages again does not hit Google Scholar.
+///
+/// Freshness is judged by modification times of the files,
+/// so the cache survives across runs and can be shared by processes.
+/// `Client::set_page_cache()` makes a client look up pages here before fetching them.
+///
+/// # Example
+///
+/// ```no_run
+/// use std::time::Duration;
+/// use scholar::cache::PageCache;
+///
+/// let mut cache = PageCache::open("scholar-cache", Duration::from_secs(24 * 60 * 60)).unwrap();
+///
+/// let url = "https://scholar.google.com/scholar?q=quantum+theory";
+/// if cache.get(url).unwrap().is_none() {
+///     cache.put(url, b"<html>...</html>").unwrap();
+/// }
+/// println!("{} hits, {} misses", cache.get_stats().hits, cache.get_stats().misses);
+/// ```
+#[derive(Clone, Debug)]
+pub struct PageCache {
+    dir: PathBuf,
+    ttl: Duration,
+    stats: CacheStats,
+}
+
+/// Numbers of lookups of a `PageCache`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    /// Lookups finding a fresh page.
+    pub hits: u64,
+    /// Lookups finding no page or an expired one.
+    pub misses: u64,
+}
+
+impl PageCache {
+    /// Open a cache in `dir`, creating the directory if it does not exist.
+    /// Pages older than `ttl` are treated as missing.
+    pub fn open<P: AsRef<Path>>(dir: P, ttl: Duration) -> Result<Self> {
+        fs::create_dir_all(dir.as_ref())?;
+        Ok(Self {
+            dir: dir.as_ref().to_owned(),
+            ttl,
+            stats: CacheStats::default(),
+        })
+    }
+
+    pub fn get_dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn set_ttl(&mut self, ttl: Duration) {
+        self.ttl = ttl;
+    }
+
+    pub fn get_ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    pub fn get_stats(&self) -> CacheStats {
+        self.stats
+    }
+
+    /// Look up a fresh page of `url`, counting a hit or a miss.
+    pub fn get(&mut self, url: &str) -> Result<Option<Vec<u8>>> {
+        let page = self.read(url)?;
+        if page.is_some() {
+            self.stats.hits += 1;
+        } else {
+            self.stats.misses += 1;
+        }
+        Ok(page)
+    }
+
+    /// Store `page` of `url`, replacing the old one.
+    pub fn put(&mut self, url: &str, page: &[u8]) -> Result<()> {
+        let path = self.path(url);
+        // Write to another file first so that readers never see a half-written page.
+        // The name is unique to this write, so that concurrent writers do not share it
+        let tmp = path.with_extension(format!(
+            "{}.{}.tmp",
+            process::id(),
+            NEXT_TMP.fetch_add(1, Ordering::Relaxed)
+        ));
+        let written = File::create(&tmp).and_then(|mut file| {
+            writeln!(file, "{}", url)?;
+            file.write_all(page)
+        });
+        if let Err(e) = written.and_then(|()| fs::rename(&tmp, &path)) {
+            let _ = fs::remove_file(&tmp);
+            return Err(e.into());
+        }
+        Ok(())
+    }
+
+    /// Remove a page of `url`, if any.
+    pub fn remove(&mut self, url: &str) -> Result<()> {
+        match fs::remove_file(self.path(url)) {
+            Ok(()) => Ok(()),
+            Err(ref e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Remove expired pages, returning how many are removed.
+    pub fn purge_expired(&mut self) -> Result<usize> {
+        let mut count = 0;
+        for entry in fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            if path.extension().map_or(false, |ext| ext == "html") && !self.is_fresh(&path)? {
+                fs::remove_file(&path)?;
+                count += 1;
+            }
+        }
+        Ok(count)
+    }
+
+    fn read(&self, url: &str) -> Result<Option<Vec<u8>>> {
+        let path = self.path(url);
+        let file = match File::open(&path) {
+            Ok(file) => file,
+            Err(ref e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        if !self.is_fresh(&path)? {
+            return Ok(None);
+        }
+
+        let mut file = io::BufReader::new(file);
+        let mut cached_url = String::new();
+        file.read_line(&mut cached_url)?;
+        // Another URL of the same hash
+        if cached_url.trim() != url {
+            return Ok(None);
+        }
+
+        let mut page = Vec::new();
+        file.read_to_end(&mut page)?;
+        Ok(Some(page))
+    }
+
+    fn is_fresh(&self, path: &Path) -> Result<bool> {
+        let modified = fs::metadata(path)?.modified()?;
+        Ok(match SystemTime::now().duration_since(modified) {
+            Ok(age) => age < self.ttl,
+            // Modified in the future, by a clock skewed
+            Err(_) => true,
+        })
+    }
+
+    fn path(&self, url: &str) -> PathBuf {
+        self.dir.join(format!("{:016x}.html", fnv1a(url.as_bytes())))
+    }
+}
+
+/// 64-bit FNV-1a hash, which is stable across builds unlike `DefaultHasher`.
+pub(crate) fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &b| {
+        (hash ^ u64::from(b)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::env;
+    use std::process;
+
+    #[test]
+    fn page_cache_test() {
+        let dir = env::temp_dir().join(format!("scholar-page-cache-test-{}", process::id()));
+        let mut cache = PageCache::open(&dir, Duration::from_secs(60)).unwrap();
+
+        assert_eq!(cache.get("/scholar?q=foo").unwrap(), None);
+        cache.put("/scholar?q=foo", b"<html>foo</html>").unwrap();
+        cache.put("/scholar?q=bar", b"<html>bar</html>").unwrap();
+        assert_eq!(
+            cache.get("/scholar?q=foo").unwrap(),
+            Some(b"<html>foo</html>".to_vec())
+        );
+        assert_eq!(cache.get_stats(), CacheStats { hits: 1, misses: 1 });
+
+        cache.remove("/scholar?q=foo").unwrap();
+        cache.remove("/scholar?q=foo").unwrap();
+        assert_eq!(cache.get("/scholar?q=foo").unwrap(), None);
+
+        cache.set_ttl(Duration::from_secs(0));
+        assert_eq!(cache.get("/scholar?q=bar").unwrap(), None);
+        assert_eq!(cache.get_stats(), CacheStats { hits: 1, misses: 3 });
+        assert_eq!(cache.purge_expired().unwrap(), 1);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn page_cache_concurrent_put_test() {
+        use std::thread;
+
+        let dir = env::temp_dir().join(format!("scholar-page-cache-put-test-{}", process::id()));
+        let cache = PageCache::open(&dir, Duration::from_secs(60)).unwrap();
+
+        let threads = (0..8)
+            .map(|i| {
+                let mut cache = cache.clone();
+                thread::spawn(move || {
+                    for _ in 0..10 {
+                        cache.put("/scholar?q=foo", format!("page {}", i).as_bytes()).unwrap();
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+        for t in threads {
+            t.join().unwrap();
+        }
+
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn fnv1a_test() {
+        assert_eq!(fnv1a(b""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(fnv1a(b"a"), 0xaf63_dc4c_8601_ec8c);
+    }
+}
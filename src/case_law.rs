@@ -0,0 +1,60 @@
+//! `CaseLaw` struct.
+
+use std::fmt;
+
+use paper::{option_na, CitationCount};
+
+/// A legal document (case law) listed in case law search results.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-derive", derive(Serialize))]
+pub struct CaseLaw {
+    /// Name of the case, e.g. "Brown v. Board of Education".
+    pub name: String,
+    /// Link to the case text on Google Scholar.
+    pub link: Option<String>,
+    /// Case ID found in `link`.
+    pub case_id: Option<u64>,
+    /// Legal citation, e.g. "347 US 483, 74 S. Ct. 686, 98 L. Ed. 873".
+    pub citation: Option<String>,
+    /// Court which decided the case.
+    pub court: Option<String>,
+    /// Decided year.
+    pub year: Option<u32>,
+    pub citation_count: Option<CitationCount>,
+}
+
+impl fmt::Display for CaseLaw {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            r#""{}"
+  Link to case: {}
+      Citation: {}
+         Court: {}
+  Decided year: {}
+Citation count: {}"#,
+            self.name,
+            option_na(&self.link),
+            option_na(&self.citation),
+            option_na(&self.court),
+            option_na(&self.year),
+            option_na(&self.citation_count),
+        )
+    }
+}
+
+impl CaseLaw {
+    /// Create new `CaseLaw` with specified `name`.
+    /// Other fields are left `None`.
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_owned(),
+            link: None,
+            case_id: None,
+            citation: None,
+            court: None,
+            year: None,
+            citation_count: None,
+        }
+    }
+}
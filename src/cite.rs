@@ -0,0 +1,21 @@
+//! Structs scraped from the "Cite" popup of a paper.
+
+/// Link to export a paper for a bibliography manager, e.g. "BibTeX" or "EndNote".
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-derive", derive(Serialize))]
+pub struct ExportLink {
+    /// Name of the format as labeled, e.g. "BibTeX".
+    pub format: String,
+    /// Absolute URL of the exported file.
+    pub url: String,
+}
+
+/// Citation formatted in a style, e.g. "MLA" or "APA".
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-derive", derive(Serialize))]
+pub struct FormattedCitation {
+    /// Name of the style as labeled, e.g. "MLA".
+    pub style: String,
+    /// Formatted citation, with emphasis such as the journal name in italics stripped.
+    pub text: String,
+}
@@ -0,0 +1,1936 @@
+//! Fetch pages from Google Scholar and parse them into documents.
+//!
+//! Requires the `client` feature.
+
+use std::cmp;
+use std::collections::VecDeque;
+use std::fmt;
+use std::io::Read;
+use std::slice;
+use std::sync::{mpsc, Arc, Mutex, MutexGuard};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::vec;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use reqwest::{self, Proxy, RedirectPolicy, StatusCode, Url};
+use reqwest::header::{Cookie, Headers, SetCookie, UserAgent};
+
+use super::GOOGLESCHOLAR_URL_BASE;
+use request::{cite_popup_url, citation_detail_url, library_url, profile_url, top_venues_url,
+              ScholarQuery, USER_AGENT};
+use batch::{self, BatchLookup, LookupInput};
+use cache::{CacheStats, PageCache};
+use cookie::CookieJar;
+use graph::{self, CitationGraph, CiterPage, CrawlOptions};
+use id::ClusterId;
+use matcher::{self, TitleMatch};
+use observer::Observer;
+use paper::Paper;
+use robots::RobotsTxt;
+use snapshot::{SnapshotMode, SnapshotStore};
+use source::PaperSource;
+use cite::ExportLink;
+use scrape::{CitationDetailDocument, CitationDocument, CitePopupDocument, ClusterDocument,
+             LibraryDocument, MetricsDocument, PapersDocument, ProfileDocument, SearchDocument};
+use watch::{self, CitationUpdate, WatchedPaper};
+use errors::*;
+
+/// Maximum number of redirects followed per request.
+const MAX_REDIRECTS: usize = 10;
+
+/// Minimum delay between requests of `Client::lookup_batch()` on a client without rate limiter.
+pub const BATCH_MIN_DELAY: Duration = Duration::from_secs(10);
+
+/// Client to fetch pages from Google Scholar with its own configuration.
+///
+/// # Example
+///
+/// ```no_run
+/// use scholar::client::Client;
+/// use scholar::request::ScholarQuery;
+/// use scholar::scrape::PapersDocument;
+///
+/// let mut client = Client::new();
+/// client.set_user_agent("my-crawler/0.1");
+///
+/// let doc = client.search(&ScholarQuery::new().query("quantum theory")).unwrap();
+/// let papers = doc.scrape_papers().unwrap();
+/// ```
+///
+/// Clones of a client share its rate limiter, rotation of proxies and user agents, connections,
+/// cookie jar, page cache, snapshots, and request budget.
+#[derive(Clone, Debug)]
+pub struct Client {
+    user_agent: String,
+    user_agents: Vec<String>,
+    next_user_agent: Arc<AtomicUsize>,
+    headers: Vec<(String, String)>,
+    rate_limiter: Option<Arc<Mutex<RateLimiter>>>,
+    retry_policy: RetryPolicy,
+    proxies: Vec<String>,
+    next_proxy: Arc<AtomicUsize>,
+    http_clients: HttpClients,
+    cookie_jar: Option<Arc<Mutex<CookieJar>>>,
+    page_cache: Option<Arc<Mutex<PageCache>>>,
+    snapshots: Option<(Arc<SnapshotStore>, SnapshotMode)>,
+    request_budget: Option<Arc<Mutex<RequestBudget>>>,
+    /// Rules of robots.txt if respected, fetched on the first request unless set
+    robots_txt: Option<Arc<Mutex<Option<RobotsTxt>>>>,
+    observer: Option<SharedObserver>,
+}
+
+/// Observer shared by clones of a client, which is not `Debug` by itself.
+#[derive(Clone)]
+struct SharedObserver(Arc<dyn Observer>);
+
+impl fmt::Debug for SharedObserver {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("SharedObserver")
+    }
+}
+
+/// `reqwest` clients shared by clones of a client, so that requests reuse their connections.
+#[derive(Clone, Default)]
+struct HttpClients {
+    /// One for each of `Client::proxies`, in the same order
+    proxied: Arc<Vec<reqwest::Client>>,
+    /// Client sending requests directly, built on the first request without proxies
+    direct: Arc<Mutex<Option<reqwest::Client>>>,
+}
+
+impl fmt::Debug for HttpClients {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("HttpClients")
+    }
+}
+
+impl Default for Client {
+    /// Create a client with the default user agent and the default retry policy,
+    /// without rate limiting.
+    fn default() -> Self {
+        Self {
+            user_agent: USER_AGENT.to_owned(),
+            user_agents: Vec::new(),
+            next_user_agent: Arc::new(AtomicUsize::new(0)),
+            headers: Vec::new(),
+            rate_limiter: None,
+            retry_policy: RetryPolicy::default(),
+            proxies: Vec::new(),
+            next_proxy: Arc::new(AtomicUsize::new(0)),
+            http_clients: HttpClients::default(),
+            cookie_jar: None,
+            page_cache: None,
+            snapshots: None,
+            request_budget: None,
+            robots_txt: None,
+            observer: None,
+        }
+    }
+}
+
+impl Client {
+    /// Create a client with the default configuration.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the `User-Agent` header sent with requests, unless rotated by `set_user_agents()`.
+    pub fn set_user_agent(&mut self, user_agent: &str) {
+        self.user_agent = user_agent.to_owned();
+    }
+
+    pub fn get_user_agent(&self) -> &str {
+        &self.user_agent
+    }
+
+    /// Send `User-Agent` headers of `user_agents` in turn, switching to the next one for each
+    /// request, including retries. An empty list sends `get_user_agent()` with every request.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use scholar::client::Client;
+    ///
+    /// let mut client = Client::new();
+    /// client.set_user_agents(&[
+    ///     "Mozilla/5.0 (X11; Linux x86_64; rv:60.0) Gecko/20100101 Firefox/60.0",
+    ///     "Mozilla/5.0 (Windows NT 10.0; Win64; x64; rv:60.0) Gecko/20100101 Firefox/60.0",
+    /// ]);
+    /// client.set_header("Accept-Language", "en-US,en;q=0.5");
+    /// ```
+    pub fn set_user_agents(&mut self, user_agents: &[&str]) {
+        self.user_agents = user_agents.iter().map(|&ua| ua.to_owned()).collect();
+    }
+
+    pub fn get_user_agents(&self) -> &[String] {
+        &self.user_agents
+    }
+
+    /// Send header `name: value` with every request, e.g. `Accept-Language` or `Referer`,
+    /// replacing the value set before for `name` if any. Header names are case-insensitive.
+    ///
+    /// `User-Agent` and `Cookie` set here are overridden by `set_user_agent()`
+    /// and `set_cookie_jar()`, respectively.
+    pub fn set_header(&mut self, name: &str, value: &str) {
+        match self.headers
+            .iter_mut()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+        {
+            Some(header) => header.1 = value.to_owned(),
+            None => self.headers.push((name.to_owned(), value.to_owned())),
+        }
+    }
+
+    /// Stop sending header `name` set by `set_header()`.
+    pub fn remove_header(&mut self, name: &str) {
+        self.headers.retain(|(n, _)| !n.eq_ignore_ascii_case(name));
+    }
+
+    /// Headers set by `set_header()`, in the order first set.
+    pub fn get_headers(&self) -> &[(String, String)] {
+        &self.headers
+    }
+
+    /// Wait before each request as `rate_limiter` decides.
+    ///
+    /// The limiter backs off when Google Scholar responds with 429 Too Many Requests
+    /// or 503 Service Unavailable, or shows a page blocking the request,
+    /// and recovers once a page is fetched successfully.
+    pub fn set_rate_limiter(&mut self, rate_limiter: RateLimiter) {
+        self.rate_limiter = Some(Arc::new(Mutex::new(rate_limiter)));
+    }
+
+    /// Retry requests failing transiently as `retry_policy` decides.
+    pub fn set_retry_policy(&mut self, retry_policy: RetryPolicy) {
+        self.retry_policy = retry_policy;
+    }
+
+    pub fn get_retry_policy(&self) -> &RetryPolicy {
+        &self.retry_policy
+    }
+
+    /// Send every request through a proxy of `proxy` URL, e.g. `http://127.0.0.1:8118`.
+    ///
+    /// See `set_proxies()`.
+    pub fn set_proxy(&mut self, proxy: &str) -> Result<()> {
+        self.set_proxies(&[proxy])
+    }
+
+    /// Send requests through `proxies` in turn, switching to the next one for each request,
+    /// including retries. An empty list sends requests directly.
+    /// Connections to each proxy are kept and reused by clones of this client.
+    ///
+    /// Only HTTP and HTTPS proxies are supported, and other URLs are reported as
+    /// `ErrorKind::InvalidProxy`. To crawl over Tor, point this to an HTTP proxy in front of it,
+    /// e.g. `HTTPTunnelPort` of Tor or Privoxy, instead of its SOCKS port.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use scholar::client::Client;
+    ///
+    /// let mut client = Client::new();
+    /// client.set_proxies(&["http://10.0.0.1:3128", "http://10.0.0.2:3128"]).unwrap();
+    /// assert!(client.set_proxy("socks5://127.0.0.1:9050").is_err());
+    /// ```
+    pub fn set_proxies(&mut self, proxies: &[&str]) -> Result<()> {
+        let mut clients = Vec::with_capacity(proxies.len());
+        for &proxy in proxies {
+            let invalid_proxy = || ErrorKind::InvalidProxy(proxy.to_owned());
+            match Url::parse(proxy) {
+                Ok(ref url) if url.scheme() == "http" || url.scheme() == "https" => {}
+                _ => bail!(invalid_proxy()),
+            }
+            let proxy = Proxy::all(proxy).chain_err(&invalid_proxy)?;
+            clients.push(build_http_client(Some(proxy)).chain_err(&invalid_proxy)?);
+        }
+
+        self.proxies = proxies.iter().map(|&p| p.to_owned()).collect();
+        self.http_clients.proxied = Arc::new(clients);
+        Ok(())
+    }
+
+    pub fn get_proxies(&self) -> &[String] {
+        &self.proxies
+    }
+
+    /// Send cookies of `cookie_jar` with requests, and store cookies set by responses in it.
+    ///
+    /// Cookies set by responses redirected from are not stored.
+    pub fn set_cookie_jar(&mut self, cookie_jar: CookieJar) {
+        self.cookie_jar = Some(Arc::new(Mutex::new(cookie_jar)));
+    }
+
+    /// Snapshot of the cookie jar, e.g. to save it with `CookieJar::write()`.
+    pub fn get_cookie_jar(&self) -> Option<CookieJar> {
+        self.cookie_jar.as_ref().map(|jar| lock(jar).clone())
+    }
+
+    /// Look up pages in `page_cache` before fetching them, and store fetched pages in it.
+    ///
+    /// Pages blocking the request are not stored.
+    pub fn set_page_cache(&mut self, page_cache: PageCache) {
+        self.page_cache = Some(Arc::new(Mutex::new(page_cache)));
+    }
+
+    /// Notify `observer` of pages fetched, retries, blocks, and papers scraped while crawling.
+    ///
+    /// Pass an `Arc` you keep a clone of to read the state of the observer afterwards.
+    pub fn set_observer(&mut self, observer: Arc<dyn Observer>) {
+        self.observer = Some(SharedObserver(observer));
+    }
+
+    /// Record every page fetched in `snapshots`, or serve pages recorded there
+    /// without sending requests, as `mode` decides.
+    ///
+    /// Replaying takes precedence over the page cache, and records pages blocking requests
+    /// as well, so that tests of downstream crates can reproduce how they handle blocking.
+    pub fn set_snapshots(&mut self, snapshots: SnapshotStore, mode: SnapshotMode) {
+        self.snapshots = Some((Arc::new(snapshots), mode));
+    }
+
+    pub fn get_snapshot_mode(&self) -> Option<SnapshotMode> {
+        self.snapshots.as_ref().map(|&(_, mode)| mode)
+    }
+
+    /// Refuse requests beyond `request_budget` with `ErrorKind::BudgetExceeded`.
+    pub fn set_request_budget(&mut self, request_budget: RequestBudget) {
+        self.request_budget = Some(Arc::new(Mutex::new(request_budget)));
+    }
+
+    /// Snapshot of the request budget, e.g. to see how many requests are left.
+    pub fn get_request_budget(&self) -> Option<RequestBudget> {
+        self.request_budget.as_ref().map(|budget| lock(budget).clone())
+    }
+
+    /// Opt in to respecting robots.txt of Google Scholar, refusing pages it disallows
+    /// with `ErrorKind::DisallowedByRobots`, or opt out.
+    ///
+    /// robots.txt is fetched on the first request, and pages must be allowed for every user
+    /// agent rotated, or `get_user_agent()` if not rotated.
+    /// A robots.txt not found allows every page. Note that Google Scholar disallows
+    /// crawling most of its pages, including search results, to generic user agents.
+    pub fn set_respect_robots_txt(&mut self, respect: bool) {
+        self.robots_txt = if respect {
+            Some(Arc::new(Mutex::new(None)))
+        } else {
+            None
+        };
+    }
+
+    pub fn get_respect_robots_txt(&self) -> bool {
+        self.robots_txt.is_some()
+    }
+
+    /// Respect `robots_txt` instead of fetching one, e.g. a copy saved before.
+    pub fn set_robots_txt(&mut self, robots_txt: RobotsTxt) {
+        self.robots_txt = Some(Arc::new(Mutex::new(Some(robots_txt))));
+    }
+
+    /// Hits and misses of the page cache so far.
+    pub fn get_page_cache_stats(&self) -> Option<CacheStats> {
+        self.page_cache.as_ref().map(|cache| lock(cache).get_stats())
+    }
+
+    /// Fetch the search result page of `query`.
+    ///
+    /// Transport failures and non-success status codes are reported as `ErrorKind::Http`
+    /// after retrying as the retry policy decides,
+    /// and pages not looking like Google Scholar as `ErrorKind::BadHtml`.
+    /// A fresh page in the page cache is returned without requests.
+    pub fn search(&self, query: &ScholarQuery) -> Result<SearchDocument> {
+        self.fetch_page(&query.to_url(), |page| SearchDocument::from_read(page))
+    }
+
+    /// Search for a free-text `title`, e.g. of an entry in a reference list,
+    /// and find the paper best matching it in the first page of results.
+    ///
+    /// Returns `None` if nothing is found. Check `TitleMatch::confidence` of the result,
+    /// since the best of unrelated papers is returned as well.
+    /// Errors are reported as in `search()`, and results failed to be scraped are skipped.
+    /// See `matcher::best_match()`.
+    pub fn resolve_title(&self, title: &str) -> Result<Option<TitleMatch>> {
+        let doc = self.search(&ScholarQuery::new().query(title))?;
+        let (papers, _) = doc.scrape_papers_lossy()?;
+        Ok(matcher::best_match(title, &papers))
+    }
+
+    /// Search for `input` and find the paper best matching it in the first page of results,
+    /// by `resolve_title()` for a title, or by `batch::match_doi()` for a DOI.
+    pub fn lookup(&self, input: &LookupInput) -> Result<Option<TitleMatch>> {
+        match *input {
+            LookupInput::Title(ref title) => self.resolve_title(title),
+            LookupInput::Doi(ref doi) => {
+                let doc = self.search(&ScholarQuery::new().query(doi))?;
+                let (papers, _) = doc.scrape_papers_lossy()?;
+                Ok(batch::match_doi(doi, &papers))
+            }
+        }
+    }
+
+    /// Look up each of `inputs` with `lookup()`, one at a time. See `batch::lookup_batch()`.
+    ///
+    /// Requests wait as the rate limiter of the client decides.
+    /// If the client has none, the batch is throttled by a rate limiter of its own
+    /// with `BATCH_MIN_DELAY`, since a publication list easily needs hundreds of requests.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use std::fs::File;
+    /// use std::io::BufReader;
+    /// use scholar::batch::{read_inputs, BatchReport};
+    /// use scholar::client::Client;
+    /// use scholar::export::batch_report_to_csv;
+    ///
+    /// let inputs = read_inputs(BufReader::new(File::open("publications.csv").unwrap())).unwrap();
+    /// let report = Client::new().lookup_batch(inputs, 0.8).collect::<BatchReport>();
+    /// batch_report_to_csv(&report.entries, File::create("report.csv").unwrap()).unwrap();
+    /// ```
+    pub fn lookup_batch<I>(
+        &self,
+        inputs: I,
+        min_confidence: f64,
+    ) -> BatchLookup<I::IntoIter, impl FnMut(&LookupInput) -> Result<Option<TitleMatch>>>
+    where
+        I: IntoIterator<Item = LookupInput>,
+    {
+        let mut client = self.clone();
+        if client.rate_limiter.is_none() {
+            client.set_rate_limiter(RateLimiter::new(BATCH_MIN_DELAY));
+        }
+        batch::lookup_batch(inputs, min_confidence, move |input| client.lookup(input))
+    }
+
+    /// Fetch the list of papers citing a paper of `cluster_id`.
+    ///
+    /// Errors are reported as in `search()`.
+    pub fn citations(&self, cluster_id: u64) -> Result<CitationDocument> {
+        let url = ScholarQuery::new().cites(cluster_id).to_url();
+        self.fetch_page(&url, |page| CitationDocument::from_read(page))
+    }
+
+    /// Fetch a page of the list of papers citing a paper of `cluster_id`,
+    /// listing citers from the `start`-th, counted from 0.
+    /// See `CitationDocument::next_page_start()` for `start` of the next page.
+    ///
+    /// Errors are reported as in `search()`.
+    pub fn citations_page(&self, cluster_id: u64, start: u32) -> Result<CitationDocument> {
+        let url = ScholarQuery::new().cites(cluster_id).start(start).to_url();
+        self.fetch_page(&url, |page| CitationDocument::from_read(page))
+    }
+
+    /// Fetch the list of versions of a paper of `cluster_id`.
+    ///
+    /// Errors are reported as in `search()`.
+    pub fn cluster(&self, cluster_id: u64) -> Result<ClusterDocument> {
+        let url = ScholarQuery::new().cluster(cluster_id).to_url();
+        self.fetch_page(&url, |page| ClusterDocument::from_read(page))
+    }
+
+    /// Fetch the profile page of an author of `user_id`, listing up to 100 articles.
+    ///
+    /// Errors are reported as in `search()`. A private profile is fetched successfully,
+    /// but scraping it fails with `ErrorKind::ProfilePrivate`; see `ProfileDocument::is_private()`.
+    pub fn profile(&self, user_id: &str) -> Result<ProfileDocument> {
+        let url = profile_url(user_id, 0, 100);
+        self.fetch_page(&url, |page| ProfileDocument::from_read(page))
+    }
+
+    /// Fetch the page of an article in an author profile, of `profile_article_id`
+    /// as in `Paper::profile_article_id`, showing its citations per year.
+    ///
+    /// Errors are reported as in `search()`.
+    pub fn citation_detail(&self, profile_article_id: &str) -> Result<CitationDetailDocument> {
+        let url = citation_detail_url(profile_article_id);
+        self.fetch_page(&url, |page| CitationDetailDocument::from_read(page))
+    }
+
+    /// Fetch the "Cite" popup of a paper of `info_id` as in `Paper::info_id`.
+    /// See `request::cite_popup_url()`.
+    ///
+    /// Errors are reported as in `search()`.
+    pub fn cite_popup(&self, info_id: &str) -> Result<CitePopupDocument> {
+        let url = cite_popup_url(info_id);
+        self.fetch_page(&url, |page| CitePopupDocument::from_read(page))
+    }
+
+    /// Fetch the BibTeX entry Google Scholar generates for `paper`,
+    /// following the "BibTeX" export link in the "Cite" popup of its `info_id`.
+    ///
+    /// The entry is more accurate than `Paper::to_bibtex()` for e.g. proceedings and editors.
+    /// Returns `ErrorKind::ResultNotFount` if `paper` has no `info_id`,
+    /// and `ErrorKind::ExportLinkNotFound` if the popup shows no BibTeX link,
+    /// as when Google Scholar is not set to show links to import into BibTeX.
+    /// The entry is not kept in the page cache nor in snapshots.
+    pub fn fetch_bibtex(&self, paper: &Paper) -> Result<String> {
+        let info_id = match paper.info_id {
+            Some(ref id) => id,
+            None => return Err(ErrorKind::ResultNotFount.into()),
+        };
+        let links = self.cite_popup(info_id)?.scrape_export_links()?;
+        let link = match bibtex_link(&links) {
+            Some(link) => link,
+            None => bail!(ErrorKind::ExportLinkNotFound(String::from("BibTeX"))),
+        };
+        self.fetch_text(&link.url)
+    }
+
+    /// Fetch the top publications page of Google Scholar Metrics in `category`, or overall.
+    /// See `request::top_venues_url()`.
+    ///
+    /// Errors are reported as in `search()`.
+    pub fn top_venues(&self, category: Option<&str>) -> Result<MetricsDocument> {
+        let url = top_venues_url(category);
+        self.fetch_page(&url, |page| MetricsDocument::from_read(page))
+    }
+
+    /// Fetch a page of "My library" of the signed-in user,
+    /// listing saved articles from the `start`-th. See `request::library_url()`.
+    ///
+    /// Requires cookies of the signed-in user, e.g. with `set_cookie_jar()`;
+    /// otherwise Google Scholar shows a sign-in page instead.
+    /// Errors are reported as in `search()`.
+    pub fn library(&self, start: u32) -> Result<LibraryDocument> {
+        let url = library_url(start);
+        self.fetch_page(&url, |page| LibraryDocument::from_read(page))
+    }
+
+    /// Crawl papers citing a paper of `root` recursively into a graph.
+    ///
+    /// One citation page is fetched per paper, so only the first page of citers is followed.
+    /// See `graph::crawl_citations()`.
+    pub fn crawl_citations(
+        &self,
+        root: ClusterId,
+        options: &CrawlOptions,
+    ) -> Result<CitationGraph> {
+        graph::crawl_citations(root, options, |id| self.citing_papers(id))
+    }
+
+    /// Like `crawl_citations()`, but fetches up to `concurrency` citation pages in parallel
+    /// with `citations_parallel()`. See `graph::crawl_citations_batched()`.
+    pub fn crawl_citations_parallel(
+        &self,
+        root: ClusterId,
+        options: &CrawlOptions,
+        concurrency: usize,
+    ) -> Result<CitationGraph> {
+        graph::crawl_citations_batched(root, options, concurrency, |ids| {
+            self.citations_parallel(ids, concurrency)
+        })
+    }
+
+    /// Collect up to `limit` papers citing a paper of `cluster_id`, walking every page of citers.
+    /// See `graph::collect_all_citers()`.
+    ///
+    /// Each page costs a request, so a paper cited thousands of times takes a while
+    /// and may get blocked; set `limit` and the rate limiter accordingly.
+    pub fn collect_all_citers(&self, cluster_id: ClusterId, limit: usize) -> Result<Vec<Paper>> {
+        graph::collect_all_citers(cluster_id, limit, |id, start| {
+            let doc = if start == 0 {
+                self.citations(id.as_u64())?
+            } else {
+                self.citations_page(id.as_u64(), start)?
+            };
+            let citers = doc.scrape_papers()?;
+            self.with_observer(|o| citers.iter().for_each(|c| o.on_paper_scraped(c)));
+            Ok(CiterPage {
+                citers,
+                next_start: doc.next_page_start(),
+            })
+        })
+    }
+
+    /// Fetch citation pages of `watched` papers and report citations added since the last run,
+    /// paired with cluster IDs of the papers. See `watch::check_citations()`.
+    ///
+    /// Up to `limit` citers of each paper are collected with `collect_all_citers()`
+    /// and compared with `WatchedPaper::known_citers`. Use the same `limit` as the citers
+    /// stored last time, so that known citers are not taken as new or missed.
+    pub fn check_citations(
+        &self,
+        watched: &[WatchedPaper],
+        limit: usize,
+    ) -> Vec<(ClusterId, Result<CitationUpdate>)> {
+        watch::check_citations(watched, |id| self.all_citing_papers(id, limit))
+    }
+
+    /// Fetch citation pages of `cluster_ids` on up to `concurrency` threads,
+    /// scraping each into the cited paper with `citers`, in the order of `cluster_ids`.
+    ///
+    /// The threads share the rate limiter, the cookie jar, and the page cache of this client.
+    /// Requests are still spaced out by the rate limiter, while waits for responses overlap.
+    pub fn citations_parallel(
+        &self,
+        cluster_ids: &[ClusterId],
+        concurrency: usize,
+    ) -> Vec<Result<Paper>> {
+        let ids = Arc::new(cluster_ids.to_vec());
+        let next = Arc::new(AtomicUsize::new(0));
+        let (tx, rx) = mpsc::channel();
+
+        let workers = cmp::min(cmp::max(concurrency, 1), ids.len());
+        let handles = (0..workers)
+            .map(|_| {
+                let client = self.clone();
+                let ids = Arc::clone(&ids);
+                let next = Arc::clone(&next);
+                let tx = tx.clone();
+                thread::spawn(move || loop {
+                    let i = next.fetch_add(1, Ordering::Relaxed);
+                    let id = match ids.get(i) {
+                        Some(&id) => id,
+                        None => break,
+                    };
+                    let paper = client.citing_papers(id);
+                    if tx.send((i, paper)).is_err() {
+                        break;
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+        drop(tx);
+
+        let mut results = ids.iter().map(|_| None).collect::<Vec<_>>();
+        for (i, paper) in rx {
+            results[i] = Some(paper);
+        }
+        for handle in handles {
+            // A panicked worker leaves its result missing, reported below
+            let _ = handle.join();
+        }
+        results
+            .into_iter()
+            .map(|paper| paper.unwrap_or_else(|| Err("Worker thread panicked".into())))
+            .collect()
+    }
+
+    /// Iterate over papers found by `query`, following "Next" links of result pages,
+    /// until `limit` papers are yielded or the last page is reached.
+    ///
+    /// A page is fetched only when the papers of the previous page are exhausted.
+    /// An error of fetching or scraping a page is yielded once and ends the iteration.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use scholar::client::Client;
+    /// use scholar::request::ScholarQuery;
+    ///
+    /// let client = Client::new();
+    /// let query = ScholarQuery::new().query("quantum theory");
+    /// for paper in client.search_iter(&query, 25) {
+    ///     println!("{}", paper.unwrap().title);
+    /// }
+    /// ```
+    pub fn search_iter<'a>(
+        &'a self,
+        query: &ScholarQuery,
+        limit: usize,
+    ) -> SearchPages<impl FnMut(&str) -> Result<SearchDocument> + 'a> {
+        search_pages(&query.to_url(), limit, move |url| {
+            self.fetch_page(url, |page| SearchDocument::from_read(page))
+        })
+    }
+
+    /// Fetch the citation page of `cluster_id` and scrape the cited paper with `citers`,
+    /// notifying the observer of the papers.
+    fn citing_papers(&self, cluster_id: ClusterId) -> Result<Paper> {
+        let paper = self.citations(cluster_id.as_u64())?
+            .scrape_target_paper_with_citers()?;
+        self.with_observer(|o| {
+            o.on_paper_scraped(&paper);
+            for citer in paper.citers.iter().flatten() {
+                o.on_paper_scraped(citer);
+            }
+        });
+        Ok(paper)
+    }
+
+    /// Like `citing_papers()`, but with up to `limit` citers from every citation page.
+    fn all_citing_papers(&self, cluster_id: ClusterId, limit: usize) -> Result<Paper> {
+        let mut target = None;
+        let citers = graph::collect_all_citers(cluster_id, limit, |id, start| {
+            let (doc, citers) = if start == 0 {
+                let doc = self.citations(id.as_u64())?;
+                let mut paper = doc.scrape_target_paper_with_citers()?;
+                let citers = paper.citers.take().unwrap_or_default();
+                target = Some(paper);
+                (doc, citers)
+            } else {
+                let doc = self.citations_page(id.as_u64(), start)?;
+                let citers = doc.scrape_papers()?;
+                (doc, citers)
+            };
+            self.with_observer(|o| citers.iter().for_each(|c| o.on_paper_scraped(c)));
+            Ok(CiterPage {
+                citers,
+                next_start: doc.next_page_start(),
+            })
+        })?;
+
+        let mut paper = match target {
+            Some(paper) => {
+                self.with_observer(|o| o.on_paper_scraped(&paper));
+                paper
+            }
+            // No page is fetched for `limit` of 0
+            None => self.citing_papers(cluster_id)?,
+        };
+        paper.citers = Some(citers);
+        Ok(paper)
+    }
+
+    /// Fetch `url` from the page cache, or else from Google Scholar, and parse it with `parse`.
+    ///
+    /// The rate limiter is told whether the fetched page is blocking the request,
+    /// and the observer is notified of the fetch, retries, and the block.
+    fn fetch_page<D, P>(&self, url: &str, parse: P) -> Result<D>
+    where
+        D: PapersDocument,
+        P: Fn(&[u8]) -> Result<D>,
+    {
+        let url = absolute_url(url)?;
+
+        if let Some((ref snapshots, SnapshotMode::Replay)) = self.snapshots {
+            let page = match snapshots.replay(url.as_str())? {
+                Some(page) => page,
+                None => bail!(ErrorKind::SnapshotNotFound(url.into_string())),
+            };
+            debug!("Replaying {}", url);
+            self.with_observer(|o| o.on_page_fetched(url.as_str(), true));
+            return parse_timed(url.as_str(), &page, parse);
+        }
+
+        let mut cached = None;
+        self.with_page_cache(|cache| cached = Some(cache.get(url.as_str())));
+        if let Some(page) = cached {
+            if let Some(page) = page? {
+                debug!("Found {} in the page cache", url);
+                self.with_observer(|o| o.on_page_fetched(url.as_str(), true));
+                return parse_timed(url.as_str(), &page, parse);
+            }
+        }
+
+        self.check_robots_txt(&url)?;
+        debug!("Fetching {}", url);
+
+        let mut page = Vec::new();
+        self.retry_policy
+            .run_notifying(
+                || self.fetch_url(&url),
+                thread::sleep,
+                |attempt, delay, err| {
+                    warn!(
+                        "Retrying {} in {:?} after attempt {} failed: {}",
+                        url, delay, attempt, err
+                    );
+                    self.with_observer(|o| o.on_retry(url.as_str(), attempt, delay, err))
+                },
+            )?
+            .read_to_end(&mut page)?;
+        self.with_observer(|o| o.on_page_fetched(url.as_str(), false));
+        let doc = parse_timed(url.as_str(), &page, parse)?;
+
+        let blocked = doc.is_blocked();
+        if let Some((ref snapshots, SnapshotMode::Record)) = self.snapshots {
+            snapshots.record(url.as_str(), &page, blocked)?;
+        }
+        if blocked {
+            warn!("Blocked by Google Scholar at {}", url);
+            self.with_observer(|o| o.on_blocked(url.as_str()));
+        }
+        self.with_rate_limiter(|l| if blocked { l.backoff() } else { l.reset_backoff() });
+        if !blocked {
+            let mut stored = Ok(());
+            self.with_page_cache(|cache| stored = cache.put(url.as_str(), &page));
+            stored?;
+        }
+
+        Ok(doc)
+    }
+
+    /// Fetch `url`, which is not a page of papers, as text, retrying as `fetch_page()` does.
+    fn fetch_text(&self, url: &str) -> Result<String> {
+        let url = absolute_url(url)?;
+        debug!("Fetching {}", url);
+
+        let mut text = String::new();
+        self.retry_policy
+            .run(|| self.fetch_url(&url))?
+            .read_to_string(&mut text)?;
+        self.with_observer(|o| o.on_page_fetched(url.as_str(), false));
+        Ok(text)
+    }
+
+    /// Return `ErrorKind::DisallowedByRobots` if robots.txt is respected and disallows `url`.
+    fn check_robots_txt(&self, url: &Url) -> Result<()> {
+        let robots_txt = match self.robots_txt {
+            Some(ref robots_txt) => robots_txt,
+            None => return Ok(()),
+        };
+
+        // Fetch without holding the lock, since other threads may wait for it long otherwise
+        if lock(robots_txt).is_none() {
+            let fetched = self.fetch_robots_txt(url)?;
+            let mut robots_txt = lock(robots_txt);
+            if robots_txt.is_none() {
+                *robots_txt = Some(fetched);
+            }
+        }
+
+        let path = match url.query() {
+            Some(query) => format!("{}?{}", url.path(), query),
+            None => url.path().to_owned(),
+        };
+        let allowed = lock(robots_txt)
+            .as_ref()
+            .map_or(true, |r| {
+                self.rotated_user_agents()
+                    .iter()
+                    .all(|ua| r.is_allowed(ua, &path))
+            });
+        if allowed {
+            Ok(())
+        } else {
+            warn!("Refused to fetch {} disallowed by robots.txt", url);
+            Err(ErrorKind::DisallowedByRobots(url.to_string()).into())
+        }
+    }
+
+    fn fetch_robots_txt(&self, url: &Url) -> Result<RobotsTxt> {
+        let robots_url = url.join("/robots.txt")
+            .chain_err(|| ErrorKind::Http(url.to_string()))?;
+        debug!("Fetching {}", robots_url);
+
+        let mut text = String::new();
+        match self.retry_policy.run(|| self.fetch_url(&robots_url)) {
+            Ok(mut res) => {
+                res.read_to_string(&mut text)?;
+            }
+            // No robots.txt, which allows everything
+            Err(ref e) if http_status(e).map_or(false, |s| s.is_client_error()) => {}
+            Err(e) => return Err(e),
+        }
+        Ok(RobotsTxt::parse(&text))
+    }
+
+    /// User agents to send in turn, which is `user_agent` alone if not rotated.
+    fn rotated_user_agents(&self) -> &[String] {
+        if self.user_agents.is_empty() {
+            slice::from_ref(&self.user_agent)
+        } else {
+            &self.user_agents
+        }
+    }
+
+    /// User agent to send with the next request, rotating the list.
+    fn next_user_agent(&self) -> &str {
+        let user_agents = self.rotated_user_agents();
+        let i = self.next_user_agent.fetch_add(1, Ordering::Relaxed);
+        &user_agents[i % user_agents.len()]
+    }
+
+    /// Proxy to send the next request through and the client for it, rotating the list.
+    fn next_proxy(&self) -> Option<(&str, &reqwest::Client)> {
+        if self.proxies.is_empty() {
+            return None;
+        }
+        let i = self.next_proxy.fetch_add(1, Ordering::Relaxed) % self.proxies.len();
+        Some((&self.proxies[i], &self.http_clients.proxied[i]))
+    }
+
+    /// Client to send the next request with, through the next proxy if any.
+    fn next_http_client(&self) -> reqwest::Result<reqwest::Client> {
+        if let Some((_, client)) = self.next_proxy() {
+            return Ok(client.clone());
+        }
+
+        let mut direct = lock(&self.http_clients.direct);
+        if let Some(ref client) = *direct {
+            return Ok(client.clone());
+        }
+        let client = build_http_client(None)?;
+        *direct = Some(client.clone());
+        Ok(client)
+    }
+
+    fn with_rate_limiter<F: FnOnce(&mut RateLimiter)>(&self, f: F) {
+        if let Some(ref limiter) = self.rate_limiter {
+            f(&mut lock(limiter));
+        }
+    }
+
+    fn with_page_cache<F: FnOnce(&mut PageCache)>(&self, f: F) {
+        if let Some(ref cache) = self.page_cache {
+            f(&mut lock(cache));
+        }
+    }
+
+    fn with_observer<F: FnOnce(&dyn Observer)>(&self, f: F) {
+        if let Some(ref observer) = self.observer {
+            f(&*observer.0);
+        }
+    }
+
+    fn with_cookie_jar<F: FnOnce(&mut CookieJar)>(&self, f: F) {
+        if let Some(ref jar) = self.cookie_jar {
+            f(&mut lock(jar));
+        }
+    }
+
+    fn fetch_url(&self, url: &Url) -> Result<reqwest::Response> {
+        let http_error = || ErrorKind::Http(url.to_string());
+
+        let client = self.next_http_client().chain_err(&http_error)?;
+
+        let mut headers = Headers::new();
+        for (name, value) in &self.headers {
+            headers.set_raw(name.clone(), value.clone());
+        }
+        headers.set(UserAgent::new(self.next_user_agent().to_owned()));
+        self.with_cookie_jar(|jar| {
+            let mut matching = jar.matching(url).peekable();
+            if matching.peek().is_some() {
+                let mut cookie = Cookie::new();
+                for (name, value) in matching {
+                    cookie.append(name.to_owned(), value.to_owned());
+                }
+                headers.set(cookie);
+            }
+        });
+
+        let mut req = client.get(url.clone());
+        req.headers(headers);
+
+        if let Some(ref budget) = self.request_budget {
+            lock(budget).acquire()?;
+        }
+        self.with_rate_limiter(RateLimiter::wait);
+        let res = req.send().chain_err(&http_error)?;
+
+        if let Some(set_cookies) = res.headers().get::<SetCookie>() {
+            // The response may come from another URL after redirects
+            let res_url = res.url().clone();
+            self.with_cookie_jar(|jar| {
+                for set_cookie in &set_cookies.0 {
+                    jar.store(&res_url, set_cookie);
+                }
+            });
+        }
+
+        debug!("{} {}", res.status(), url);
+        match res.status() {
+            StatusCode::TooManyRequests | StatusCode::ServiceUnavailable => {
+                self.with_rate_limiter(RateLimiter::backoff)
+            }
+            _ => {}
+        }
+        let status = res.status();
+        if status.is_client_error() || status.is_server_error() {
+            return Err(Error::with_chain(HttpStatus(status), http_error()));
+        }
+        Ok(res)
+    }
+}
+
+impl PaperSource for Client {
+    fn name(&self) -> &str {
+        "Google Scholar"
+    }
+
+    /// Search with `search_iter()`, failing on the first page or paper failed to be scraped.
+    fn search_papers(&self, query: &str, count: usize) -> Result<Vec<Paper>> {
+        self.search_iter(&ScholarQuery::new().query(query), count)
+            .collect()
+    }
+
+    /// Papers in the first citation page of `paper`, found by its cluster ID.
+    fn citing_papers(&self, paper: &Paper) -> Result<Vec<Paper>> {
+        let cluster_id = match paper.cluster_id {
+            Some(id) => id,
+            None => return Err(ErrorKind::ResultNotFount.into()),
+        };
+        Ok(self.citing_papers(cluster_id)?.citers.unwrap_or_default())
+    }
+}
+
+/// Parse `page` of `url` with `parse`, logging how long it takes.
+fn parse_timed<D, P>(url: &str, page: &[u8], parse: P) -> Result<D>
+where
+    P: Fn(&[u8]) -> Result<D>,
+{
+    let start = Instant::now();
+    let doc = parse(page);
+    trace!("Parsed {} ({} bytes) in {:?}", url, page.len(), start.elapsed());
+    doc
+}
+
+/// Resolve `url`, which is either absolute or relative to Google Scholar.
+fn absolute_url(url: &str) -> Result<Url> {
+    Url::parse(GOOGLESCHOLAR_URL_BASE)
+        .unwrap()
+        .join(url)
+        .chain_err(|| ErrorKind::Http(url.to_owned()))
+}
+
+/// The link among `links` to export in BibTeX, labeled as such in any case.
+fn bibtex_link(links: &[ExportLink]) -> Option<&ExportLink> {
+    links
+        .iter()
+        .find(|link| link.format.trim().eq_ignore_ascii_case("BibTeX"))
+}
+
+/// Build a `reqwest` client following redirects up to `MAX_REDIRECTS`, through `proxy` if any.
+fn build_http_client(proxy: Option<Proxy>) -> reqwest::Result<reqwest::Client> {
+    let mut builder = reqwest::Client::builder();
+    builder.redirect(RedirectPolicy::limited(MAX_REDIRECTS));
+    if let Some(proxy) = proxy {
+        builder.proxy(proxy);
+    }
+    builder.build()
+}
+
+/// Lock `mutex`, even if poisoned by a panicking thread, since its value is still consistent.
+fn lock<'a, T>(mutex: &'a Mutex<T>) -> MutexGuard<'a, T> {
+    match mutex.lock() {
+        Ok(guard) => guard,
+        Err(e) => e.into_inner(),
+    }
+}
+
+/// Fetch the search result page of `query` with the default client.
+///
+/// See `Client::search()`.
+pub fn fetch_search(query: &ScholarQuery) -> Result<SearchDocument> {
+    Client::default().search(query)
+}
+
+/// Fetch the list of papers citing a paper of `cluster_id` with the default client.
+///
+/// See `Client::citations()`.
+pub fn fetch_citations(cluster_id: u64) -> Result<CitationDocument> {
+    Client::default().citations(cluster_id)
+}
+
+/// Iterator over papers across result pages. See `Client::search_iter()`.
+pub struct SearchPages<F> {
+    fetch: F,
+    next_url: Option<String>,
+    remaining: usize,
+    papers: vec::IntoIter<Paper>,
+}
+
+/// Iterate over papers as in `Client::search_iter()`, starting from `url`,
+/// but fetching each page with `fetch` instead of a `Client`.
+pub fn search_pages<F>(url: &str, limit: usize, fetch: F) -> SearchPages<F>
+where
+    F: FnMut(&str) -> Result<SearchDocument>,
+{
+    SearchPages {
+        fetch,
+        next_url: Some(url.to_owned()),
+        remaining: limit,
+        papers: Vec::new().into_iter(),
+    }
+}
+
+impl<F> Iterator for SearchPages<F>
+where
+    F: FnMut(&str) -> Result<SearchDocument>,
+{
+    type Item = Result<Paper>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+
+        loop {
+            if let Some(paper) = self.papers.next() {
+                self.remaining -= 1;
+                return Some(Ok(paper));
+            }
+
+            let url = self.next_url.take()?;
+            let page = (self.fetch)(&url).and_then(|doc| {
+                let papers = doc.scrape_papers()?;
+                Ok((papers, doc.next_page_url()))
+            });
+            match page {
+                Ok((papers, next_url)) => {
+                    self.papers = papers.into_iter();
+                    self.next_url = next_url;
+                }
+                Err(e) => {
+                    self.remaining = 0;
+                    return Some(Err(e));
+                }
+            }
+        }
+    }
+}
+
+/// Limiter of the rate of requests, to crawl politely without being blocked.
+///
+/// Up to `burst` requests are sent back to back,
+/// and then requests are spaced by `min_delay` plus random jitter up to `jitter`.
+/// Each `min_delay` passed without requests allows one more request in a burst.
+/// After `backoff()`, `min_delay` doubles until `reset_backoff()` is called.
+///
+/// # Example
+///
+/// ```no_run
+/// use std::time::Duration;
+/// use scholar::client::{Client, RateLimiter};
+///
+/// let mut limiter = RateLimiter::new(Duration::from_secs(10));
+/// limiter.set_jitter(Duration::from_secs(5));
+/// limiter.set_burst(3);
+///
+/// let mut client = Client::new();
+/// client.set_rate_limiter(limiter);
+/// ```
+#[derive(Clone, Debug)]
+pub struct RateLimiter {
+    min_delay: Duration,
+    jitter: Duration,
+    burst: u32,
+    tokens: u32,
+    last: Option<Instant>,
+    backoff_exponent: u32,
+}
+
+/// `RateLimiter::backoff()` stops doubling the delay after this many times.
+const MAX_BACKOFF_EXPONENT: u32 = 6;
+
+impl RateLimiter {
+    /// Create a limiter spacing requests by `min_delay`, without jitter nor bursts.
+    pub fn new(min_delay: Duration) -> Self {
+        Self {
+            min_delay,
+            jitter: Duration::from_secs(0),
+            burst: 1,
+            tokens: 0,
+            last: None,
+            backoff_exponent: 0,
+        }
+    }
+
+    pub fn get_min_delay(&self) -> Duration {
+        self.min_delay
+    }
+
+    /// Set the maximum of random delay added to each wait.
+    pub fn set_jitter(&mut self, jitter: Duration) {
+        self.jitter = jitter;
+    }
+
+    pub fn get_jitter(&self) -> Duration {
+        self.jitter
+    }
+
+    /// Set the number of requests sent back to back. `0` is treated as `1`.
+    pub fn set_burst(&mut self, burst: u32) {
+        self.burst = cmp::max(burst, 1);
+        self.tokens = cmp::min(self.tokens, self.burst);
+    }
+
+    pub fn get_burst(&self) -> u32 {
+        self.burst
+    }
+
+    /// Double the delay, e.g. when Google Scholar blocked a request, and stop bursts.
+    pub fn backoff(&mut self) {
+        self.backoff_exponent = cmp::min(self.backoff_exponent + 1, MAX_BACKOFF_EXPONENT);
+        self.tokens = 0;
+    }
+
+    /// Restore the delay to `min_delay`.
+    pub fn reset_backoff(&mut self) {
+        self.backoff_exponent = 0;
+    }
+
+    /// Block the current thread until the next request is allowed.
+    pub fn wait(&mut self) {
+        let delay = self.reserve(Instant::now());
+        if delay > Duration::from_secs(0) {
+            thread::sleep(delay + random_duration(self.jitter));
+        }
+    }
+
+    /// Reserve a request at `now`, returning how long to wait before it, without jitter.
+    fn reserve(&mut self, now: Instant) -> Duration {
+        let interval = self.min_delay * 2u32.pow(self.backoff_exponent);
+        let interval_nanos = duration_nanos(interval);
+
+        let wait = match self.last {
+            Some(last) if now >= last => {
+                let elapsed = now - last;
+                self.tokens = match duration_nanos(elapsed).checked_div(interval_nanos) {
+                    Some(refill) => {
+                        cmp::min(u64::from(self.tokens) + refill, u64::from(self.burst)) as u32
+                    }
+                    None => self.burst,
+                };
+
+                if self.tokens > 0 || elapsed >= interval {
+                    Duration::from_secs(0)
+                } else {
+                    interval - elapsed
+                }
+            }
+            Some(last) => (last - now) + interval,
+            None => {
+                self.tokens = self.burst;
+                Duration::from_secs(0)
+            }
+        };
+
+        self.tokens = self.tokens.saturating_sub(1);
+        self.last = Some(now + wait);
+        wait
+    }
+}
+
+/// Budget of requests sent in the last hour and the last day,
+/// refusing requests beyond it with `ErrorKind::BudgetExceeded`.
+///
+/// Each attempt counts, including retries, while pages found in the page cache
+/// or replayed from snapshots do not.
+///
+/// # Example
+///
+/// ```
+/// use scholar::client::{Client, RequestBudget};
+///
+/// let mut budget = RequestBudget::new();
+/// budget.set_per_hour(Some(60));
+/// budget.set_per_day(Some(500));
+///
+/// let mut client = Client::new();
+/// client.set_request_budget(budget);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct RequestBudget {
+    per_hour: Option<u32>,
+    per_day: Option<u32>,
+    /// Times requests are sent within the last day, oldest first.
+    sent: VecDeque<Instant>,
+}
+
+const HOUR: Duration = Duration::from_secs(60 * 60);
+const DAY: Duration = Duration::from_secs(24 * 60 * 60);
+
+impl RequestBudget {
+    /// Create a budget without limits.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the maximum number of requests in any hour, or `None` for no limit.
+    pub fn set_per_hour(&mut self, per_hour: Option<u32>) {
+        self.per_hour = per_hour;
+    }
+
+    pub fn get_per_hour(&self) -> Option<u32> {
+        self.per_hour
+    }
+
+    /// Set the maximum number of requests in any day, or `None` for no limit.
+    pub fn set_per_day(&mut self, per_day: Option<u32>) {
+        self.per_day = per_day;
+    }
+
+    pub fn get_per_day(&self) -> Option<u32> {
+        self.per_day
+    }
+
+    /// Number of requests sent in the last hour.
+    pub fn used_last_hour(&self) -> u32 {
+        self.used_within(Instant::now(), HOUR)
+    }
+
+    /// Number of requests sent in the last day.
+    pub fn used_last_day(&self) -> u32 {
+        self.used_within(Instant::now(), DAY)
+    }
+
+    /// Count a request sent now, or return `ErrorKind::BudgetExceeded` without counting it
+    /// if it exceeds the budget.
+    pub fn acquire(&mut self) -> Result<()> {
+        self.acquire_at(Instant::now())
+    }
+
+    fn acquire_at(&mut self, now: Instant) -> Result<()> {
+        while self.sent
+            .front()
+            .map_or(false, |&t| now.duration_since(t) >= DAY)
+        {
+            self.sent.pop_front();
+        }
+
+        for &(limit, window, name) in &[(self.per_hour, HOUR, "hour"), (self.per_day, DAY, "day")] {
+            if let Some(limit) = limit {
+                if self.used_within(now, window) >= limit {
+                    bail!(ErrorKind::BudgetExceeded(limit, name.to_owned()));
+                }
+            }
+        }
+
+        self.sent.push_back(now);
+        Ok(())
+    }
+
+    fn used_within(&self, now: Instant, window: Duration) -> u32 {
+        self.sent
+            .iter()
+            .filter(|&&t| now < t || now.duration_since(t) < window)
+            .count() as u32
+    }
+}
+
+/// Policy of retrying requests failing transiently.
+///
+/// Transport failures, 5xx status codes and 429 Too Many Requests are retried,
+/// and other errors are returned immediately since retrying them yields the same result.
+///
+/// # Example
+///
+/// ```no_run
+/// use std::time::Duration;
+/// use scholar::client::{Backoff, Client, RetryPolicy};
+///
+/// let mut policy = RetryPolicy::new(5, Duration::from_secs(2));
+/// policy.set_backoff(Backoff::Constant);
+///
+/// let mut client = Client::new();
+/// client.set_retry_policy(policy);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    base_delay: Duration,
+    backoff: Backoff,
+}
+
+/// How the delay between attempts grows.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Backoff {
+    /// Wait `base_delay` between every attempt.
+    Constant,
+    /// Start from `base_delay` and double it for each attempt, with random jitter added
+    /// as in `with_retry()`.
+    Exponential,
+}
+
+impl Default for RetryPolicy {
+    /// Try up to 3 times, backing off exponentially from 1 second.
+    fn default() -> Self {
+        Self::new(3, Duration::from_secs(1))
+    }
+}
+
+impl RetryPolicy {
+    /// Create a policy trying up to `max_attempts` times with exponential backoff
+    /// from `base_delay`. `0` attempts is treated as `1`, i.e. no retry.
+    pub fn new(max_attempts: u32, base_delay: Duration) -> Self {
+        Self {
+            max_attempts: cmp::max(max_attempts, 1),
+            base_delay,
+            backoff: Backoff::Exponential,
+        }
+    }
+
+    pub fn get_max_attempts(&self) -> u32 {
+        self.max_attempts
+    }
+
+    pub fn get_base_delay(&self) -> Duration {
+        self.base_delay
+    }
+
+    pub fn set_backoff(&mut self, backoff: Backoff) {
+        self.backoff = backoff;
+    }
+
+    pub fn get_backoff(&self) -> Backoff {
+        self.backoff
+    }
+
+    /// Whether `err` is a transient failure worth retrying.
+    pub fn is_retryable(&self, err: &Error) -> bool {
+        match *err.kind() {
+            ErrorKind::Http(_) => is_retryable_status(http_status(err)),
+            _ => false,
+        }
+    }
+
+    /// Call `f` until it succeeds, fails with an error not retryable,
+    /// or fails `max_attempts` times, sleeping between attempts.
+    pub fn run<T, F>(&self, f: F) -> Result<T>
+    where
+        F: FnMut() -> Result<T>,
+    {
+        self.run_sleeping(f, thread::sleep)
+    }
+
+    fn run_sleeping<T, F, S>(&self, f: F, sleep: S) -> Result<T>
+    where
+        F: FnMut() -> Result<T>,
+        S: FnMut(Duration),
+    {
+        self.run_notifying(f, sleep, |_, _, _| {})
+    }
+
+    /// Like `run_sleeping()`, but calls `notify` with the attempt failed, the delay,
+    /// and the error before each retry.
+    fn run_notifying<T, F, S, N>(&self, mut f: F, mut sleep: S, mut notify: N) -> Result<T>
+    where
+        F: FnMut() -> Result<T>,
+        S: FnMut(Duration),
+        N: FnMut(u32, Duration, &Error),
+    {
+        let mut attempt = 1;
+        loop {
+            let err = match f() {
+                Ok(v) => return Ok(v),
+                Err(e) => e,
+            };
+            if attempt >= self.max_attempts || !self.is_retryable(&err) {
+                return Err(err);
+            }
+
+            let delay = self.delay(attempt);
+            notify(attempt, delay, &err);
+            sleep(delay);
+            attempt += 1;
+        }
+    }
+
+    /// Delay after the `attempt`-th attempt failed.
+    fn delay(&self, attempt: u32) -> Duration {
+        match self.backoff {
+            Backoff::Constant => self.base_delay,
+            Backoff::Exponential => backoff_delay(self.base_delay, attempt),
+        }
+    }
+}
+
+/// Whether Google Scholar limited the rate of requests, failing `err` with an `ErrorKind::Http`
+/// of 429 Too Many Requests, or 503 Service Unavailable as for its `/sorry/` page.
+///
+/// Unlike `ErrorKind::Blocked`, no page is scraped, but crawlers should back off as well.
+pub fn is_rate_limited(err: &Error) -> bool {
+    match *err.kind() {
+        ErrorKind::Http(_) => http_status(err).map_or(false, |status| {
+            status == StatusCode::TooManyRequests || status == StatusCode::ServiceUnavailable
+        }),
+        _ => false,
+    }
+}
+
+/// Unsuccessful status code of a response, chained to `ErrorKind::Http`.
+#[derive(Debug)]
+pub(crate) struct HttpStatus(pub StatusCode);
+
+impl fmt::Display for HttpStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "HTTP status {}", self.0)
+    }
+}
+
+impl ::std::error::Error for HttpStatus {}
+
+/// Status code of the response `err` was made from, if any.
+fn http_status(err: &Error) -> Option<StatusCode> {
+    err.1
+        .next_error
+        .as_ref()
+        .and_then(|e| e.downcast_ref::<HttpStatus>())
+        .map(|status| status.0)
+}
+
+/// Transport failures, without status code, are retryable as well as 5xx and 429.
+fn is_retryable_status(status: Option<StatusCode>) -> bool {
+    match status {
+        Some(StatusCode::TooManyRequests) | None => true,
+        Some(status) => status.is_server_error(),
+    }
+}
+
+/// Call `f` up to `attempts` times until it succeeds, sleeping between attempts.
+///
+/// Only `ErrorKind::Blocked` and `ErrorKind::Http` of transport failures, 5xx, or 429 are retried,
+/// and other errors, e.g. 404, are returned immediately since retrying them yields the same result.
+/// The delay starts from `base_delay` and doubles for each attempt, with random jitter added.
+/// Returns the last error if all attempts fail. `f` is called at least once.
+///
+/// # Example
+///
+/// ```no_run
+/// use std::time::Duration;
+/// use scholar::client::{fetch_citations, with_retry};
+///
+/// let doc = with_retry(3, Duration::from_secs(5), || fetch_citations(42)).unwrap();
+/// ```
+pub fn with_retry<T, F>(attempts: u32, base_delay: Duration, f: F) -> Result<T>
+where
+    F: FnMut() -> Result<T>,
+{
+    with_retry_sleeping(attempts, base_delay, f, thread::sleep)
+}
+
+/// Like `with_retry()`, but sleeps with `sleep` instead of `std::thread::sleep()`.
+pub fn with_retry_sleeping<T, F, S>(
+    attempts: u32,
+    base_delay: Duration,
+    mut f: F,
+    mut sleep: S,
+) -> Result<T>
+where
+    F: FnMut() -> Result<T>,
+    S: FnMut(Duration),
+{
+    let mut attempt = 1;
+    loop {
+        let err = match f() {
+            Ok(v) => return Ok(v),
+            Err(e) => e,
+        };
+
+        let retry = match *err.kind() {
+            ErrorKind::Http(_) => attempt < attempts && is_retryable_status(http_status(&err)),
+            ErrorKind::Blocked(_) => attempt < attempts,
+            _ => false,
+        };
+        if !retry {
+            return Err(err);
+        }
+
+        sleep(backoff_delay(base_delay, attempt));
+        attempt += 1;
+    }
+}
+
+/// `base_delay` doubled `attempt - 1` times, plus jitter up to half of `base_delay`.
+fn backoff_delay(base_delay: Duration, attempt: u32) -> Duration {
+    let delay = base_delay * 2u32.pow(cmp::min(attempt - 1, 16));
+    delay + random_duration(base_delay / 2)
+}
+
+const NANOS_PER_SEC: u64 = 1_000_000_000;
+
+/// Pseudo-random duration from 0 to `max`, seeded by the clock.
+fn random_duration(max: Duration) -> Duration {
+    let seed = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| u64::from(d.subsec_nanos()))
+        .unwrap_or(0);
+    let nanos = seed % (duration_nanos(max) + 1);
+    Duration::new(nanos / NANOS_PER_SEC, (nanos % NANOS_PER_SEC) as u32)
+}
+
+fn duration_nanos(d: Duration) -> u64 {
+    d.as_secs() * NANOS_PER_SEC + u64::from(d.subsec_nanos())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use scrape::BlockKind;
+
+    #[test]
+    fn client_user_agent_test() {
+        let mut client = Client::new();
+        assert_eq!(client.get_user_agent(), USER_AGENT);
+
+        client.set_user_agent("foo/0.1");
+        assert_eq!(client.get_user_agent(), "foo/0.1");
+    }
+
+    #[test]
+    fn client_user_agents_test() {
+        let mut client = Client::new();
+        assert_eq!(client.rotated_user_agents(), &[USER_AGENT.to_owned()]);
+
+        client.set_user_agents(&["foo/0.1", "bar/0.1"]);
+        let clone = client.clone();
+        assert_eq!(client.next_user_agent(), "foo/0.1");
+        assert_eq!(clone.next_user_agent(), "bar/0.1");
+        assert_eq!(client.next_user_agent(), "foo/0.1");
+        assert_eq!(client.get_user_agents().len(), 2);
+
+        client.set_user_agents(&[]);
+        client.set_user_agent("baz/0.1");
+        assert_eq!(client.next_user_agent(), "baz/0.1");
+    }
+
+    #[test]
+    fn client_headers_test() {
+        let mut client = Client::new();
+        client.set_header("Accept-Language", "en-US");
+        client.set_header("Referer", "https://scholar.google.com/");
+        client.set_header("accept-language", "ja");
+        assert_eq!(
+            client.get_headers(),
+            &[
+                ("Accept-Language".to_owned(), "ja".to_owned()),
+                ("Referer".to_owned(), "https://scholar.google.com/".to_owned()),
+            ]
+        );
+
+        client.remove_header("ACCEPT-LANGUAGE");
+        assert_eq!(client.get_headers().len(), 1);
+    }
+
+    #[test]
+    fn client_proxies_test() {
+        let mut client = Client::new();
+        let next_proxy = |client: &Client| client.next_proxy().map(|(proxy, _)| proxy.to_owned());
+        assert_eq!(next_proxy(&client), None);
+
+        client.set_proxies(&["http://foo:3128", "https://bar:3128"]).unwrap();
+        let clone = client.clone();
+        assert_eq!(next_proxy(&client).unwrap(), "http://foo:3128");
+        assert_eq!(next_proxy(&clone).unwrap(), "https://bar:3128");
+        assert_eq!(next_proxy(&client).unwrap(), "http://foo:3128");
+        assert_eq!(client.http_clients.proxied.len(), 2);
+
+        match *client.set_proxy("socks5://baz:9050").unwrap_err().kind() {
+            ErrorKind::InvalidProxy(ref url) => assert_eq!(url, "socks5://baz:9050"),
+            ref e => panic!("unexpected error: {:?}", e),
+        }
+        assert!(client.set_proxy("not a url").is_err());
+        assert_eq!(client.get_proxies().len(), 2);
+
+        client.set_proxies(&[]).unwrap();
+        assert_eq!(next_proxy(&client), None);
+    }
+
+    #[test]
+    fn client_replay_snapshots_test() {
+        use std::{env, fs, process};
+
+        let dir = env::temp_dir().join(format!("scholar-client-replay-test-{}", process::id()));
+        let snapshots = SnapshotStore::open(&dir).unwrap();
+        let query = ScholarQuery::new().query("quantum theory");
+        let url = absolute_url(&query.to_url()).unwrap();
+        let page = fs::read("src/test_html/search/quantum_theory.html").unwrap();
+        snapshots.record(url.as_str(), &page, false).unwrap();
+
+        let mut client = Client::new();
+        assert_eq!(client.get_snapshot_mode(), None);
+        client.set_snapshots(snapshots, SnapshotMode::Replay);
+        assert_eq!(client.get_snapshot_mode(), Some(SnapshotMode::Replay));
+
+        let papers = client.search(&query).unwrap().scrape_papers().unwrap();
+        assert_eq!(papers.len(), 10);
+
+        match client.cluster(42) {
+            Err(Error(ErrorKind::SnapshotNotFound(ref url), _)) => {
+                assert!(url.contains("cluster=42"))
+            }
+            Err(e) => panic!("unexpected error: {}", e),
+            Ok(_) => panic!("replayed a page not recorded"),
+        }
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn search_pages_test() {
+        use std::fs;
+
+        let fetch_fixture = |urls: &mut Vec<String>, url: &str| -> Result<SearchDocument> {
+            urls.push(url.to_owned());
+            let path = if url.contains("start=20") {
+                bail!(ErrorKind::Http(url.to_owned()));
+            } else if url.contains("start=10") {
+                "src/test_html/search/quantum_theory_page2.html"
+            } else {
+                "src/test_html/search/quantum_theory.html"
+            };
+            SearchDocument::from_read(fs::File::open(path).unwrap())
+        };
+
+        let mut urls = Vec::new();
+        let papers = search_pages("/scholar?q=quantum+theory", 5, |url| {
+            fetch_fixture(&mut urls, url)
+        }).collect::<Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(papers.len(), 5);
+        assert_eq!(urls.len(), 1);
+
+        let mut urls = Vec::new();
+        let papers = search_pages("/scholar?q=quantum+theory", 11, |url| {
+            fetch_fixture(&mut urls, url)
+        }).collect::<Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(papers.len(), 11);
+        assert_eq!(papers[10].title, "The quantum theory of the electron");
+        assert_eq!(urls.len(), 2);
+
+        let mut urls = Vec::new();
+        let results = search_pages("/scholar?q=quantum+theory", 100, |url| {
+            fetch_fixture(&mut urls, url)
+        }).collect::<Vec<_>>();
+        assert_eq!(results.len(), 13);
+        assert!(results[..12].iter().all(|r| r.is_ok()));
+        match *results[12].as_ref().unwrap_err().kind() {
+            ErrorKind::Http(ref url) => assert!(url.contains("start=20")),
+            ref e => panic!("unexpected error: {:?}", e),
+        }
+        assert_eq!(urls.len(), 3);
+    }
+
+    #[test]
+    fn bibtex_link_test() {
+        let link = |format: &str| ExportLink {
+            format: format.to_owned(),
+            url: format!("https://scholar.googleusercontent.com/{}", format),
+        };
+
+        let links = vec![link("EndNote"), link("BibTeX"), link("RefMan")];
+        assert_eq!(bibtex_link(&links), Some(&links[1]));
+        assert_eq!(bibtex_link(&[link("bibtex ")]), Some(&link("bibtex ")));
+        assert_eq!(bibtex_link(&[link("EndNote")]), None);
+        assert_eq!(bibtex_link(&[]), None);
+    }
+
+    #[test]
+    fn with_retry_test() {
+        let mut calls = 0;
+        let mut sleeps = Vec::new();
+        let result = with_retry_sleeping(
+            3,
+            Duration::from_secs(0),
+            || -> Result<()> {
+                calls += 1;
+                Err(ErrorKind::Blocked(BlockKind::Captcha).into())
+            },
+            |d| sleeps.push(d),
+        );
+        assert!(result.is_err());
+        assert_eq!(calls, 3);
+        assert_eq!(sleeps, vec![Duration::from_secs(0); 2]);
+
+        let mut calls = 0;
+        let result = with_retry_sleeping(
+            3,
+            Duration::from_secs(0),
+            || {
+                calls += 1;
+                if calls < 2 {
+                    Err(ErrorKind::Http(String::from("foo")).into())
+                } else {
+                    Ok(calls)
+                }
+            },
+            |_| (),
+        );
+        assert_eq!(result.unwrap(), 2);
+
+        let mut calls = 0;
+        let result = with_retry_sleeping(
+            3,
+            Duration::from_secs(0),
+            || -> Result<()> {
+                calls += 1;
+                Err(ErrorKind::bad_html("title", "").into())
+            },
+            |_| panic!("should not sleep"),
+        );
+        assert!(result.is_err());
+        assert_eq!(calls, 1);
+
+        let mut calls = 0;
+        let result = with_retry_sleeping(
+            3,
+            Duration::from_secs(0),
+            || -> Result<()> {
+                calls += 1;
+                let status = HttpStatus(StatusCode::NotFound);
+                Err(Error::with_chain(status, ErrorKind::Http(String::from("foo"))))
+            },
+            |_| panic!("should not sleep"),
+        );
+        assert!(result.is_err());
+        assert_eq!(calls, 1);
+    }
+
+    #[test]
+    fn retry_policy_test() {
+        let mut policy = RetryPolicy::new(3, Duration::from_secs(1));
+        policy.set_backoff(Backoff::Constant);
+
+        let mut calls = 0;
+        let mut sleeps = Vec::new();
+        let result = policy.run_sleeping(
+            || -> Result<()> {
+                calls += 1;
+                Err(ErrorKind::Http(String::from("foo")).into())
+            },
+            |d| sleeps.push(d),
+        );
+        assert!(result.is_err());
+        assert_eq!(calls, 3);
+        assert_eq!(sleeps, vec![Duration::from_secs(1); 2]);
+
+        let mut notified = Vec::new();
+        let result = policy.run_notifying(
+            || -> Result<()> { Err(ErrorKind::Http(String::from("foo")).into()) },
+            |_| {},
+            |attempt, delay, err| notified.push((attempt, delay, err.to_string())),
+        );
+        assert!(result.is_err());
+        assert_eq!(
+            notified,
+            vec![
+                (1, Duration::from_secs(1), String::from("HTTP request to foo failed")),
+                (2, Duration::from_secs(1), String::from("HTTP request to foo failed")),
+            ]
+        );
+
+        let mut calls = 0;
+        let result = policy.run_sleeping(
+            || -> Result<()> {
+                calls += 1;
+                Err(ErrorKind::Blocked(BlockKind::Captcha).into())
+            },
+            |_| panic!("should not sleep"),
+        );
+        assert!(result.is_err());
+        assert_eq!(calls, 1);
+
+        assert_eq!(RetryPolicy::new(0, Duration::from_secs(1)).get_max_attempts(), 1);
+    }
+
+    #[test]
+    fn is_rate_limited_test() {
+        let status_error =
+            |status| Error::with_chain(HttpStatus(status), ErrorKind::Http(String::from("foo")));
+        assert!(is_rate_limited(&status_error(StatusCode::TooManyRequests)));
+        assert!(is_rate_limited(&status_error(StatusCode::ServiceUnavailable)));
+        assert!(!is_rate_limited(&status_error(StatusCode::NotFound)));
+        assert!(!is_rate_limited(&ErrorKind::Http(String::from("foo")).into()));
+        assert!(!is_rate_limited(&ErrorKind::Blocked(BlockKind::Captcha).into()));
+
+        let policy = RetryPolicy::default();
+        assert!(policy.is_retryable(&status_error(StatusCode::TooManyRequests)));
+        assert!(!policy.is_retryable(&status_error(StatusCode::NotFound)));
+    }
+
+    #[test]
+    fn is_retryable_status_test() {
+        assert!(is_retryable_status(None));
+        assert!(is_retryable_status(Some(StatusCode::TooManyRequests)));
+        assert!(is_retryable_status(Some(StatusCode::InternalServerError)));
+        assert!(is_retryable_status(Some(StatusCode::ServiceUnavailable)));
+        assert!(!is_retryable_status(Some(StatusCode::NotFound)));
+        assert!(!is_retryable_status(Some(StatusCode::Forbidden)));
+    }
+
+    #[test]
+    fn rate_limiter_test() {
+        let secs = Duration::from_secs;
+        let start = Instant::now();
+
+        let mut limiter = RateLimiter::new(secs(10));
+        limiter.set_burst(2);
+        assert_eq!(limiter.reserve(start), secs(0));
+        assert_eq!(limiter.reserve(start), secs(0));
+        assert_eq!(limiter.reserve(start), secs(10));
+        // Reserved at start + 10s, so the next one waits until start + 20s
+        assert_eq!(limiter.reserve(start + secs(4)), secs(16));
+
+        // 20s passed since the last request, refilling the burst
+        assert_eq!(limiter.reserve(start + secs(40)), secs(0));
+        assert_eq!(limiter.reserve(start + secs(40)), secs(0));
+        assert_eq!(limiter.reserve(start + secs(43)), secs(7));
+
+        limiter.backoff();
+        limiter.backoff();
+        assert_eq!(limiter.reserve(start + secs(100)), secs(0));
+        assert_eq!(limiter.reserve(start + secs(110)), secs(30));
+
+        limiter.reset_backoff();
+        assert_eq!(limiter.reserve(start + secs(145)), secs(5));
+    }
+
+    #[test]
+    fn request_budget_test() {
+        let secs = Duration::from_secs;
+        let start = Instant::now();
+
+        let mut budget = RequestBudget::new();
+        for _ in 0..5 {
+            budget.acquire_at(start).unwrap();
+        }
+        assert_eq!(budget.used_within(start, HOUR), 5);
+
+        budget.set_per_hour(Some(2));
+        budget.set_per_day(Some(3));
+        match budget.acquire_at(start) {
+            Err(Error(ErrorKind::BudgetExceeded(2, ref window), _)) => assert_eq!(window, "hour"),
+            result => panic!("unexpected result: {:?}", result),
+        }
+
+        let mut budget = RequestBudget::new();
+        budget.set_per_hour(Some(2));
+        budget.set_per_day(Some(3));
+        budget.acquire_at(start).unwrap();
+        budget.acquire_at(start + secs(10)).unwrap();
+        assert!(budget.acquire_at(start + secs(20)).is_err());
+        // An hour after the first request
+        budget.acquire_at(start + HOUR).unwrap();
+        match budget.acquire_at(start + HOUR + secs(20)) {
+            Err(Error(ErrorKind::BudgetExceeded(3, ref window), _)) => assert_eq!(window, "day"),
+            result => panic!("unexpected result: {:?}", result),
+        }
+        // Refused requests are not counted
+        assert_eq!(budget.used_within(start + HOUR + secs(20), DAY), 3);
+
+        budget.acquire_at(start + DAY).unwrap();
+        assert_eq!(budget.used_within(start + DAY, DAY), 3);
+    }
+
+    #[test]
+    fn client_robots_txt_test() {
+        use std::{env, fs, process};
+
+        let dir = env::temp_dir().join(format!("scholar-client-robots-test-{}", process::id()));
+        let snapshots = SnapshotStore::open(&dir).unwrap();
+
+        let mut client = Client::new();
+        assert!(!client.get_respect_robots_txt());
+        client.set_robots_txt(RobotsTxt::parse("User-agent: *\nDisallow: /scholar?cites="));
+        assert!(client.get_respect_robots_txt());
+        // Refused before recording, without sending the request
+        client.set_snapshots(snapshots, SnapshotMode::Record);
+        match client.citations(42) {
+            Err(Error(ErrorKind::DisallowedByRobots(ref url), _)) => {
+                assert!(url.contains("cites=42"))
+            }
+            Err(e) => panic!("unexpected error: {}", e),
+            Ok(_) => panic!("fetched a page disallowed"),
+        }
+
+        client.set_respect_robots_txt(false);
+        assert!(!client.get_respect_robots_txt());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn backoff_delay_test() {
+        let base = Duration::from_millis(100);
+        for attempt in 1..4 {
+            let delay = backoff_delay(base, attempt);
+            let min = base * 2u32.pow(attempt - 1);
+            assert!(min <= delay && delay <= min + base / 2, "{:?}", delay);
+        }
+    }
+}
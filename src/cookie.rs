@@ -0,0 +1,421 @@
+//! Cookies kept across requests, and saved across runs.
+//!
+//! Requires the `client` feature.
+
+use std::collections::BTreeMap;
+use std::io::{self, BufRead};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use reqwest::header::HttpDate;
+use url::Url;
+
+use errors::*;
+
+/// Cookies received from Google Scholar, e.g. `NID` and `GSP` storing settings,
+/// or imported from a browser.
+///
+/// Cookies are kept by domain, path, and name, and sent only with requests to URLs they
+/// apply to, so that cookies of other sites imported from a `cookies.txt` stay there.
+/// Expired cookies are not sent, and dropped when saved.
+///
+/// # Example
+///
+/// ```no_run
+/// use std::fs::File;
+/// use scholar::client::Client;
+/// use scholar::cookie::CookieJar;
+///
+/// let jar = match File::open("cookies.txt") {
+///     Ok(file) => CookieJar::from_read(file).unwrap(),
+///     Err(_) => CookieJar::new(),
+/// };
+///
+/// let mut client = Client::new();
+/// client.set_cookie_jar(jar);
+/// // ...
+/// let jar = client.get_cookie_jar().unwrap();
+/// jar.write(File::create("cookies.txt").unwrap()).unwrap();
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CookieJar {
+    cookies: BTreeMap<CookieKey, Cookie>,
+}
+
+/// Domain, path, and name of a cookie.
+///
+/// The domain is lowercase, and starts with `.` if the cookie is sent to its subdomains as well.
+type CookieKey = (String, String, String);
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Cookie {
+    value: String,
+    secure: bool,
+    /// Seconds since the Unix epoch. `None` for a session cookie.
+    expires: Option<u64>,
+}
+
+const DEFAULT_DOMAIN: &str = ".scholar.google.com";
+const GOOGLESCHOLAR_HOST: &str = "scholar.google.com";
+
+/// curl marks HttpOnly cookies with this prefix, which is not a comment.
+const HTTP_ONLY_PREFIX: &str = "#HttpOnly_";
+
+impl CookieJar {
+    /// Create an empty jar.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Read cookies in the Netscape `cookies.txt` format,
+    /// as written by `write()`, curl, or browser extensions.
+    ///
+    /// Cookies of every domain are read, though only ones applying to a URL are sent there.
+    /// Empty lines and comments starting with `#` are skipped,
+    /// and malformed lines are reported as `ErrorKind::InvalidCookie`.
+    pub fn from_read<R: io::Read>(r: R) -> Result<Self> {
+        let mut jar = Self::new();
+
+        for line in io::BufReader::new(r).lines() {
+            let line = line?;
+            let line = if line.starts_with(HTTP_ONLY_PREFIX) {
+                line.split_at(HTTP_ONLY_PREFIX.len()).1
+            } else {
+                &line
+            };
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let invalid_cookie = || ErrorKind::InvalidCookie(line.to_owned());
+            let fields = line.split('\t').collect::<Vec<_>>();
+            if fields.len() != 7 || fields[0].is_empty() {
+                bail!(invalid_cookie());
+            }
+            let expires = fields[4].trim().parse::<u64>().chain_err(&invalid_cookie)?;
+
+            let domain = fields[0].trim_start_matches('.').to_lowercase();
+            let domain = if fields[1] == "TRUE" {
+                format!(".{}", domain)
+            } else {
+                domain
+            };
+            jar.cookies.insert(
+                (domain, fields[2].to_owned(), fields[5].to_owned()),
+                Cookie {
+                    value: fields[6].to_owned(),
+                    secure: fields[3] == "TRUE",
+                    expires: if expires == 0 { None } else { Some(expires) },
+                },
+            );
+        }
+
+        Ok(jar)
+    }
+
+    /// Write unexpired cookies in the Netscape `cookies.txt` format.
+    /// Session cookies are written with expiry `0` so that the session survives.
+    pub fn write<W: io::Write>(&self, mut w: W) -> Result<()> {
+        writeln!(w, "# Netscape HTTP Cookie File")?;
+
+        let now = unix_secs(SystemTime::now());
+        for ((domain, path, name), cookie) in self.cookies.iter().filter(|(_, c)| c.is_alive(now)) {
+            writeln!(
+                w,
+                "{}\t{}\t{}\t{}\t{}\t{}\t{}",
+                domain,
+                if domain.starts_with('.') { "TRUE" } else { "FALSE" },
+                path,
+                if cookie.secure { "TRUE" } else { "FALSE" },
+                cookie.expires.unwrap_or(0),
+                name,
+                cookie.value
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Set a session cookie of `name` to `value` for Google Scholar.
+    pub fn insert(&mut self, name: &str, value: &str) {
+        self.cookies.insert(
+            (DEFAULT_DOMAIN.to_owned(), String::from("/"), name.to_owned()),
+            Cookie {
+                value: value.to_owned(),
+                secure: false,
+                expires: None,
+            },
+        );
+    }
+
+    /// Value of an unexpired cookie of `name` sent to Google Scholar.
+    pub fn get(&self, name: &str) -> Option<&str> {
+        let now = unix_secs(SystemTime::now());
+        self.cookies
+            .iter()
+            .find(|&((domain, _, n), cookie)| {
+                n == name && cookie.is_alive(now) && domain_matches(domain, GOOGLESCHOLAR_HOST)
+            })
+            .map(|(_, cookie)| cookie.value.as_str())
+    }
+
+    /// Number of cookies, including expired ones not yet dropped.
+    pub fn len(&self) -> usize {
+        self.cookies.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cookies.is_empty()
+    }
+
+    /// Store a cookie from the value of a `Set-Cookie` header of a response from `url`.
+    ///
+    /// The cookie applies to the host of `url` only, unless its `Domain` attribute extends it
+    /// to subdomains of a domain of the host. A cookie of another domain is ignored.
+    /// A cookie expired already, as Google Scholar sends to delete it, is removed.
+    pub fn store(&mut self, url: &Url, set_cookie: &str) {
+        self.store_at(url, set_cookie, SystemTime::now());
+    }
+
+    /// Pairs of name and value of unexpired cookies, to be sent in a `Cookie` header to `url`.
+    ///
+    /// Cookies apply to `url` if they are of its host, or of a domain of the host
+    /// extended to subdomains, and of a prefix of its path.
+    /// Secure cookies are sent only over HTTPS.
+    pub fn matching<'a>(&'a self, url: &'a Url) -> impl Iterator<Item = (&'a str, &'a str)> + 'a {
+        let now = unix_secs(SystemTime::now());
+        let host = url.host_str().unwrap_or("").to_lowercase();
+        let https = url.scheme() == "https";
+        self.cookies
+            .iter()
+            .filter(move |&((domain, path, _), cookie)| {
+                cookie.is_alive(now)
+                    && (https || !cookie.secure)
+                    && domain_matches(domain, &host)
+                    && path_matches(path, url.path())
+            })
+            .map(|((_, _, name), cookie)| (name.as_str(), cookie.value.as_str()))
+    }
+
+    fn store_at(&mut self, url: &Url, set_cookie: &str, now: SystemTime) {
+        let host = match url.host_str() {
+            Some(host) => host.to_lowercase(),
+            None => return,
+        };
+        let mut attrs = set_cookie.split(';');
+        let (name, value) = match attrs.next().and_then(split_pair) {
+            Some((name, value)) if !name.is_empty() => (name, value),
+            _ => return,
+        };
+
+        let mut cookie = Cookie {
+            value: value.to_owned(),
+            secure: false,
+            expires: None,
+        };
+        let mut domain = host.clone();
+        let mut path = String::from("/");
+        let mut max_age = None;
+
+        for attr in attrs {
+            let (key, value) = split_pair(attr).unwrap_or((attr.trim(), ""));
+            match key.to_lowercase().as_str() {
+                "domain" if !value.is_empty() => {
+                    domain = format!(".{}", value.trim_start_matches('.').to_lowercase());
+                }
+                "path" if value.starts_with('/') => path = value.to_owned(),
+                "secure" => cookie.secure = true,
+                "expires" => cookie.expires = parse_expires(value),
+                "max-age" => max_age = value.parse::<i64>().ok(),
+                _ => {}
+            }
+        }
+        // A site may not set cookies of other sites
+        if !domain_matches(&domain, &host) {
+            return;
+        }
+
+        let now = unix_secs(now);
+        // Max-Age takes precedence over Expires
+        if let Some(max_age) = max_age {
+            cookie.expires = Some(if max_age <= 0 {
+                0
+            } else {
+                now + max_age as u64
+            });
+        }
+
+        let key = (domain, path, name.to_owned());
+        if cookie.is_alive(now) {
+            self.cookies.insert(key, cookie);
+        } else {
+            self.cookies.remove(&key);
+        }
+    }
+}
+
+impl Cookie {
+    fn is_alive(&self, now: u64) -> bool {
+        match self.expires {
+            Some(expires) => expires > now,
+            None => true,
+        }
+    }
+}
+
+/// Whether a cookie of `domain` (see `CookieKey`) applies to lowercase `host`.
+fn domain_matches(domain: &str, host: &str) -> bool {
+    if domain.starts_with('.') {
+        host == &domain[1..] || host.ends_with(domain)
+    } else {
+        host == domain
+    }
+}
+
+/// Whether a cookie of `cookie_path` applies to `path` of a URL.
+fn path_matches(cookie_path: &str, path: &str) -> bool {
+    path == cookie_path
+        || (path.starts_with(cookie_path)
+            && (cookie_path.ends_with('/') || path[cookie_path.len()..].starts_with('/')))
+}
+
+fn split_pair(s: &str) -> Option<(&str, &str)> {
+    let mut pair = s.splitn(2, '=');
+    let key = pair.next()?.trim();
+    let value = pair.next()?.trim();
+    Some((key, value))
+}
+
+/// Parse `Expires` attribute, including the form `Wed, 10-Apr-2019 08:00:00 GMT` Google uses.
+fn parse_expires(s: &str) -> Option<u64> {
+    s.replace('-', " ")
+        .parse::<HttpDate>()
+        .ok()
+        .map(|date| unix_secs(SystemTime::from(date)))
+}
+
+fn unix_secs(t: SystemTime) -> u64 {
+    t.duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::time::Duration;
+
+    fn key(domain: &str, name: &str) -> CookieKey {
+        (domain.to_owned(), String::from("/"), name.to_owned())
+    }
+
+    #[test]
+    fn cookie_jar_store_test() {
+        // 2018-04-10T08:00:00Z
+        let now = UNIX_EPOCH + Duration::from_secs(1_523_347_200);
+        let url = Url::parse("https://scholar.google.com/scholar?q=foo").unwrap();
+
+        let mut jar = CookieJar::new();
+        jar.store_at(
+            &url,
+            "NID=128=abc; expires=Wed, 10-Oct-2018 08:00:00 GMT; path=/; \
+             domain=.google.com; HttpOnly",
+            now,
+        );
+        jar.store_at(&url, "GSP=LM=1:CF=4; max-age=3600; domain=scholar.google.com", now);
+        jar.store_at(&url, "SID=xyz", now);
+        jar.store_at(&url, "broken", now);
+        jar.store_at(&url, "EVIL=1; domain=.example.com", now);
+
+        assert_eq!(jar.len(), 3);
+        let nid = &jar.cookies[&key(".google.com", "NID")];
+        assert_eq!(nid.value, "128=abc");
+        assert_eq!(nid.expires, Some(1_539_158_400));
+        let gsp = &jar.cookies[&key(".scholar.google.com", "GSP")];
+        assert_eq!(gsp.expires, Some(1_523_350_800));
+        assert!(jar.cookies.contains_key(&key("scholar.google.com", "SID")));
+
+        jar.store_at(&url, "GSP=; Max-Age=0; domain=scholar.google.com", now);
+        assert_eq!(jar.len(), 2);
+        assert!(jar.get("GSP").is_none());
+    }
+
+    #[test]
+    fn cookie_jar_matching_test() {
+        let cookies = "\
+            .google.com\tTRUE\t/\tFALSE\t0\tNID\tgoogle\n\
+            .example.com\tTRUE\t/\tFALSE\t0\tNID\texample\n\
+            scholar.google.com\tFALSE\t/\tTRUE\t0\tSSID\tsecure\n\
+            scholar.google.com\tFALSE\t/citations\tFALSE\t0\tCIT\tcit\n";
+        let jar = CookieJar::from_read(cookies.as_bytes()).unwrap();
+        assert_eq!(jar.len(), 4);
+
+        let matching = |url: &str| {
+            let url = Url::parse(url).unwrap();
+            jar.matching(&url).map(|(n, v)| format!("{}={}", n, v)).collect::<Vec<_>>()
+        };
+        assert_eq!(
+            matching("https://scholar.google.com/scholar?q=foo"),
+            vec!["NID=google", "SSID=secure"]
+        );
+        assert_eq!(matching("http://scholar.google.com/scholar"), vec!["NID=google"]);
+        assert_eq!(
+            matching("https://scholar.google.com/citations?user=foo"),
+            vec!["NID=google", "SSID=secure", "CIT=cit"]
+        );
+        assert_eq!(matching("https://www.example.com/"), vec!["NID=example"]);
+        assert!(matching("https://notgoogle.com/").is_empty());
+        assert_eq!(matching("https://www.scholar.google.com/"), vec!["NID=google"]);
+        assert_eq!(
+            matching("https://scholar.google.com/citationsfoo"),
+            vec!["NID=google", "SSID=secure"]
+        );
+
+        assert_eq!(jar.get("NID"), Some("google"));
+    }
+
+    #[test]
+    fn cookie_jar_read_write_test() {
+        let mut jar = CookieJar::new();
+        jar.insert("GSP", "LM=1:CF=4");
+        jar.cookies.insert(
+            key(".google.com", "NID"),
+            Cookie {
+                value: String::from("abc"),
+                secure: true,
+                expires: Some(4_000_000_000),
+            },
+        );
+        jar.cookies.insert(
+            key(".google.com", "OLD"),
+            Cookie {
+                value: String::from("def"),
+                secure: false,
+                expires: Some(1),
+            },
+        );
+
+        let mut buf = Vec::new();
+        jar.write(&mut buf).unwrap();
+        assert_eq!(
+            String::from_utf8(buf.clone()).unwrap(),
+            "# Netscape HTTP Cookie File\n\
+             .google.com\tTRUE\t/\tTRUE\t4000000000\tNID\tabc\n\
+             .scholar.google.com\tTRUE\t/\tFALSE\t0\tGSP\tLM=1:CF=4\n"
+        );
+
+        jar.cookies.remove(&key(".google.com", "OLD"));
+        assert_eq!(CookieJar::from_read(&buf[..]).unwrap(), jar);
+
+        let curl = "#HttpOnly_google.com\tTRUE\t/\tFALSE\t0\tNID\tabc\n\n# comment\n";
+        assert_eq!(CookieJar::from_read(curl.as_bytes()).unwrap().get("NID"), Some("abc"));
+
+        match CookieJar::from_read("foo\tbar\n".as_bytes()).unwrap_err().kind() {
+            ErrorKind::InvalidCookie(line) => assert_eq!(line, "foo\tbar"),
+            kind => panic!("unexpected error {:?}", kind),
+        }
+        let bad_expiry = ".google.com\tTRUE\t/\tFALSE\tnever\tNID\tabc\n";
+        match CookieJar::from_read(bad_expiry.as_bytes()).unwrap_err().kind() {
+            ErrorKind::InvalidCookie(_) => {}
+            kind => panic!("unexpected error {:?}", kind),
+        }
+    }
+}
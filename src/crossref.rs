@@ -0,0 +1,391 @@
+//! Enrich papers with metadata registered in Crossref, via its REST API.
+//!
+//! Requires the `crossref` feature.
+
+use std::io::Read;
+
+use reqwest::{self, StatusCode};
+use reqwest::header::UserAgent;
+use serde_json::{self, Value};
+use url::percent_encoding::{utf8_percent_encode, PATH_SEGMENT_ENCODE_SET};
+
+use matcher::{title_score, TITLE_MATCH_THRESHOLD};
+use paper::Paper;
+use request::form_urlencode;
+use errors::*;
+
+const CROSSREF_URL_BASE: &str = "https://api.crossref.org/works";
+
+/// Number of works requested per search by title.
+const SEARCH_ROWS: u32 = 5;
+
+/// Metadata of a work registered in Crossref.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CrossrefWork {
+    pub doi: String,
+    pub title: Option<String>,
+    /// Authors in the form "M. V. Berry", in the order listed.
+    pub authors: Vec<String>,
+    /// Journal, proceedings, etc. (`container-title`).
+    pub venue: Option<String>,
+    /// Year of `issued` date.
+    pub year: Option<u32>,
+    pub publisher: Option<String>,
+    /// ISSNs of the venue, in the order listed, which is usually print then electronic.
+    pub issn: Vec<String>,
+    pub references_count: Option<u32>,
+}
+
+impl CrossrefWork {
+    /// Parse a work object, i.e. `message` of `/works/{doi}` or an item of `/works?query=`.
+    ///
+    /// Returns `None` if the object has no DOI.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// #[macro_use]
+    /// extern crate serde_json;
+    /// extern crate scholar;
+    ///
+    /// use scholar::crossref::CrossrefWork;
+    ///
+    /// # fn main() {
+    /// let work = CrossrefWork::from_json(&json!({
+    ///     "DOI": "10.1098/rspa.1984.0023",
+    ///     "title": ["Quantal phase factors accompanying adiabatic changes"],
+    ///     "author": [{ "given": "M. V.", "family": "Berry" }],
+    ///     "issued": { "date-parts": [[1984, 3, 8]] },
+    /// })).unwrap();
+    ///
+    /// assert_eq!(work.authors, vec!["M. V. Berry"]);
+    /// assert_eq!(work.year, Some(1984));
+    /// # }
+    /// ```
+    pub fn from_json(work: &Value) -> Option<Self> {
+        let doi = work["DOI"].as_str()?.to_owned();
+
+        let authors = work["author"]
+            .as_array()
+            .map(|authors| authors.iter().filter_map(author_name).collect())
+            .unwrap_or_default();
+        let issn = work["ISSN"]
+            .as_array()
+            .map(|issn| {
+                issn.iter()
+                    .filter_map(Value::as_str)
+                    .map(ToOwned::to_owned)
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Some(Self {
+            doi,
+            title: first_string(&work["title"]),
+            authors,
+            venue: first_string(&work["container-title"]),
+            year: work["issued"]["date-parts"][0][0]
+                .as_u64()
+                .map(|year| year as u32),
+            publisher: work["publisher"].as_str().map(ToOwned::to_owned),
+            issn,
+            references_count: work["references-count"]
+                .as_u64()
+                .map(|count| count as u32),
+        })
+    }
+
+    /// Merge this metadata into `paper`.
+    ///
+    /// Authors, publisher, ISSN, and references count are taken as authoritative and replace
+    /// those in `paper`, since Google Scholar abbreviates and truncates authors.
+    /// DOI, venue, and year fill only those unknown in `paper`.
+    pub fn enrich(&self, paper: &mut Paper) {
+        if !self.authors.is_empty() {
+            paper.authors = Some(self.authors.join(", "));
+            paper.authors_truncated = false;
+        }
+        if self.publisher.is_some() {
+            paper.publisher = self.publisher.clone();
+        }
+        if let Some(issn) = self.issn.first() {
+            paper.issn = Some(issn.clone());
+        }
+        if self.references_count.is_some() {
+            paper.references_count = self.references_count;
+        }
+
+        if paper.doi.is_none() {
+            paper.doi = Some(self.doi.clone());
+        }
+        if paper.venue.is_none() {
+            paper.venue = self.venue.clone();
+        }
+        if paper.year.is_none() {
+            paper.year = self.year;
+        }
+    }
+}
+
+/// URL of the work of `doi`.
+///
+/// # Example
+///
+/// ```
+/// use scholar::crossref::work_url;
+///
+/// assert_eq!(
+///     work_url("10.1098/rspa.1984.0023"),
+///     "https://api.crossref.org/works/10.1098%2Frspa.1984.0023"
+/// );
+/// ```
+pub fn work_url(doi: &str) -> String {
+    format!(
+        "{}/{}",
+        CROSSREF_URL_BASE,
+        utf8_percent_encode(doi, PATH_SEGMENT_ENCODE_SET)
+    )
+}
+
+/// URL searching works by bibliographic `title`.
+///
+/// # Example
+///
+/// ```
+/// use scholar::crossref::search_url;
+///
+/// assert_eq!(
+///     search_url("Quantal phase factors"),
+///     "https://api.crossref.org/works?query.bibliographic=Quantal+phase+factors&rows=5"
+/// );
+/// ```
+pub fn search_url(title: &str) -> String {
+    format!(
+        "{}?query.bibliographic={}&rows={}",
+        CROSSREF_URL_BASE,
+        form_urlencode(title),
+        SEARCH_ROWS
+    )
+}
+
+/// Client to look papers up in Crossref.
+///
+/// # Example
+///
+/// ```no_run
+/// use scholar::crossref::CrossrefClient;
+/// use scholar::paper::Paper;
+///
+/// let mut crossref = CrossrefClient::new();
+/// crossref.set_mailto("me@example.com");
+///
+/// let mut paper = Paper::new("Quantal phase factors accompanying adiabatic changes", 42);
+/// if crossref.enrich(&mut paper).unwrap() {
+///     println!("{:?}", paper.publisher);
+/// }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct CrossrefClient {
+    mailto: Option<String>,
+}
+
+impl CrossrefClient {
+    /// Create a client without contact address.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set a contact address sent in `User-Agent`,
+    /// with which Crossref serves requests in its faster "polite" pool.
+    pub fn set_mailto(&mut self, mailto: &str) {
+        self.mailto = Some(mailto.to_owned());
+    }
+
+    pub fn get_mailto(&self) -> Option<&str> {
+        self.mailto.as_ref().map(|mailto| &mailto[..])
+    }
+
+    /// Work of `doi`, or `None` if Crossref does not know it.
+    pub fn work_by_doi(&self, doi: &str) -> Result<Option<CrossrefWork>> {
+        Ok(self.fetch_json(&work_url(doi))?
+            .and_then(|res| CrossrefWork::from_json(&res["message"])))
+    }
+
+    /// Work best matching `title` among the search results,
+    /// or `None` if none scores `matcher::TITLE_MATCH_THRESHOLD` or more.
+    pub fn work_by_title(&self, title: &str) -> Result<Option<CrossrefWork>> {
+        self.work_by_title_in(title, None)
+    }
+
+    /// Like `work_by_title()`, but skips works of another year if `year` is known.
+    fn work_by_title_in(&self, title: &str, year: Option<u32>) -> Result<Option<CrossrefWork>> {
+        let res = match self.fetch_json(&search_url(title))? {
+            Some(res) => res,
+            None => return Ok(None),
+        };
+        let items = match res["message"]["items"].as_array() {
+            Some(items) => items,
+            None => return Ok(None),
+        };
+        Ok(best_work(title, year, items))
+    }
+
+    /// Look `paper` up by its DOI if known, or else by its title,
+    /// and merge the metadata found with `CrossrefWork::enrich()`.
+    ///
+    /// A work found by title is not merged if its year differs from the year of `paper`,
+    /// since papers of similar titles, e.g. an erratum, are often published in other years.
+    ///
+    /// Returns whether a work is found.
+    pub fn enrich(&self, paper: &mut Paper) -> Result<bool> {
+        let work = match paper.doi {
+            Some(ref doi) => self.work_by_doi(doi)?,
+            None => self.work_by_title_in(&paper.title, paper.year)?,
+        };
+        match work {
+            Some(work) => {
+                work.enrich(paper);
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    fn user_agent(&self) -> String {
+        let agent = concat!("scholar/", env!("CARGO_PKG_VERSION"));
+        match self.mailto {
+            Some(ref mailto) => format!("{} (mailto:{})", agent, mailto),
+            None => agent.to_owned(),
+        }
+    }
+
+    /// Response of `url` in JSON, or `None` if not found.
+    fn fetch_json(&self, url: &str) -> Result<Option<Value>> {
+        let http_error = || ErrorKind::Http(url.to_owned());
+
+        let res = reqwest::Client::new()
+            .get(url)
+            .header(UserAgent::new(self.user_agent()))
+            .send()
+            .chain_err(&http_error)?;
+        if res.status() == StatusCode::NotFound {
+            return Ok(None);
+        }
+
+        let mut body = Vec::new();
+        res.error_for_status()
+            .chain_err(&http_error)?
+            .read_to_end(&mut body)?;
+        Ok(Some(serde_json::from_slice(&body)?))
+    }
+}
+
+/// The work among `items` whose title best matches `title`, if not below the threshold.
+/// Works of a year other than `year` are skipped if both years are known.
+fn best_work(title: &str, year: Option<u32>, items: &[Value]) -> Option<CrossrefWork> {
+    let mut best: Option<(f64, CrossrefWork)> = None;
+    for work in items.iter().filter_map(CrossrefWork::from_json) {
+        if let (Some(year), Some(work_year)) = (year, work.year) {
+            if year != work_year {
+                continue;
+            }
+        }
+        let score = work.title.as_ref().map_or(0.0, |t| title_score(title, t));
+        if score >= TITLE_MATCH_THRESHOLD && best.as_ref().map_or(true, |b| score > b.0) {
+            best = Some((score, work));
+        }
+    }
+    best.map(|(_, work)| work)
+}
+
+/// "Given Family" of an author object, or `name` of an organization.
+fn author_name(author: &Value) -> Option<String> {
+    match (author["given"].as_str(), author["family"].as_str()) {
+        (Some(given), Some(family)) => Some(format!("{} {}", given, family)),
+        (None, Some(family)) => Some(family.to_owned()),
+        _ => author["name"].as_str().map(ToOwned::to_owned),
+    }
+}
+
+/// First of an array of strings, e.g. `title`, which Crossref gives as an array.
+fn first_string(value: &Value) -> Option<String> {
+    value[0].as_str().map(ToOwned::to_owned)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn berry_json() -> Value {
+        json!({
+            "DOI": "10.1098/rspa.1984.0023",
+            "title": ["Quantal phase factors accompanying adiabatic changes"],
+            "author": [
+                { "given": "M. V.", "family": "Berry" },
+                { "name": "Royal Society" },
+            ],
+            "container-title": ["Proceedings of the Royal Society of London. A."],
+            "publisher": "The Royal Society",
+            "ISSN": ["0080-4630", "2053-9169"],
+            "references-count": 12,
+            "issued": { "date-parts": [[1984, 3, 8]] },
+        })
+    }
+
+    #[test]
+    fn crossref_work_from_json_test() {
+        assert_eq!(
+            CrossrefWork::from_json(&berry_json()).unwrap(),
+            CrossrefWork {
+                doi: String::from("10.1098/rspa.1984.0023"),
+                title: Some(String::from(
+                    "Quantal phase factors accompanying adiabatic changes"
+                )),
+                authors: vec![String::from("M. V. Berry"), String::from("Royal Society")],
+                venue: Some(String::from("Proceedings of the Royal Society of London. A.")),
+                year: Some(1984),
+                publisher: Some(String::from("The Royal Society")),
+                issn: vec![String::from("0080-4630"), String::from("2053-9169")],
+                references_count: Some(12),
+            }
+        );
+        assert_eq!(CrossrefWork::from_json(&json!({ "title": ["foo"] })), None);
+    }
+
+    #[test]
+    fn crossref_work_enrich_test() {
+        let mut paper = Paper::new("Quantal phase factors accompanying adiabatic changes", 42);
+        paper.authors = Some(String::from("MV Berry"));
+        paper.authors_truncated = true;
+        paper.venue = Some(String::from("Proc. R. Soc. Lond. A"));
+
+        CrossrefWork::from_json(&berry_json())
+            .unwrap()
+            .enrich(&mut paper);
+        assert_eq!(paper.authors, Some(String::from("M. V. Berry, Royal Society")));
+        assert!(!paper.authors_truncated);
+        assert_eq!(paper.venue, Some(String::from("Proc. R. Soc. Lond. A")));
+        assert_eq!(paper.year, Some(1984));
+        assert_eq!(paper.doi, Some(String::from("10.1098/rspa.1984.0023")));
+        assert_eq!(paper.publisher, Some(String::from("The Royal Society")));
+        assert_eq!(paper.issn, Some(String::from("0080-4630")));
+        assert_eq!(paper.references_count, Some(12));
+    }
+
+    #[test]
+    fn best_work_test() {
+        let other = json!({
+            "DOI": "10.1103/RevModPhys.82.1959",
+            "title": ["Berry phase effects on electronic properties"],
+        });
+        let items = vec![other, berry_json()];
+
+        let title = "Quantal Phase Factors Accompanying Adiabatic Changes.";
+        let work = best_work(title, None, &items);
+        assert_eq!(work.unwrap().doi, "10.1098/rspa.1984.0023");
+        let work = best_work(title, Some(1984), &items);
+        assert_eq!(work.unwrap().doi, "10.1098/rspa.1984.0023");
+        assert_eq!(best_work(title, Some(1985), &items), None);
+        assert_eq!(best_work("Geometric phases in physics", None, &items), None);
+    }
+}
@@ -0,0 +1,226 @@
+//! Merge papers referring to the same work, e.g. collected by crawls across several queries.
+
+use std::collections::{BTreeSet, HashMap};
+
+use id::ClusterId;
+use paper::Paper;
+use text::normalize_for_matching;
+
+/// Least similarity of titles for papers without cluster ID to be taken as the same work.
+/// See `title_similarity()`.
+pub const TITLE_SIMILARITY_THRESHOLD: f64 = 0.9;
+
+/// Merge papers referring to the same work into one, keeping the order of first occurrences.
+///
+/// Papers are the same work if they share a cluster ID, or, if either lacks one,
+/// their titles are similar enough (see `TITLE_SIMILARITY_THRESHOLD`)
+/// and their years, if both known, are the same.
+/// Papers with different cluster IDs are never merged.
+///
+/// A merged paper takes the larger citation count, the union of citers
+/// (merged recursively in the same way), and fields unknown in the first occurrence
+/// from the others.
+///
+/// Unlike `util::dedup_papers()`, which picks one of papers sharing a cluster ID,
+/// this combines what every duplicate knows.
+///
+/// # Example
+///
+/// ```
+/// use scholar::dedup::merge_duplicates;
+/// use scholar::paper::Paper;
+///
+/// let mut cited = Paper::new("Quantal phase factors accompanying adiabatic changes", 1);
+/// cited.citation_count = Some(10.into());
+/// let mut no_id = Paper::new("Quantal Phase Factors Accompanying Adiabatic Changes.", 0);
+/// no_id.set_cluster_id(None);
+/// no_id.year = Some(1984);
+///
+/// let papers = merge_duplicates(vec![cited, Paper::new("other", 2), no_id]);
+/// assert_eq!(papers.len(), 2);
+/// assert_eq!(papers[0].year, Some(1984));
+/// assert_eq!(papers[0].citation_count, Some(10.into()));
+/// ```
+pub fn merge_duplicates(papers: Vec<Paper>) -> Vec<Paper> {
+    let mut merged: Vec<Paper> = Vec::with_capacity(papers.len());
+    let mut positions: HashMap<ClusterId, usize> = HashMap::new();
+    // Normalized titles of `merged`, not to normalize them for every comparison
+    let mut titles: Vec<String> = Vec::with_capacity(papers.len());
+
+    for paper in papers {
+        let title = normalize_for_matching(&paper.title);
+        let position = paper
+            .cluster_id
+            .and_then(|id| positions.get(&id).cloned())
+            .or_else(|| {
+                (0..merged.len()).find(|&i| {
+                    is_same_work_by_title(&merged[i], &titles[i], &paper, &title)
+                })
+            });
+
+        match position {
+            Some(i) => {
+                merge_into(&mut merged[i], paper);
+                if let Some(id) = merged[i].cluster_id {
+                    positions.insert(id, i);
+                }
+            }
+            None => {
+                if let Some(id) = paper.cluster_id {
+                    positions.insert(id, merged.len());
+                }
+                merged.push(paper);
+                titles.push(title);
+            }
+        }
+    }
+
+    merged
+}
+
+/// Similarity of titles in `[0, 1]`, the Jaccard index of the sets of words
+/// normalized by `text::normalize_for_matching()`.
+///
+/// # Example
+///
+/// ```
+/// use scholar::dedup::title_similarity;
+///
+/// assert_eq!(title_similarity("Quantum Theory", "quantum theory."), 1.0);
+/// assert_eq!(title_similarity("quantum theory", "quantum field theory"), 2.0 / 3.0);
+/// assert_eq!(title_similarity("quantum", "classical"), 0.0);
+/// ```
+pub fn title_similarity(a: &str, b: &str) -> f64 {
+    word_overlap(&normalize_for_matching(a), &normalize_for_matching(b))
+}
+
+fn word_overlap(a: &str, b: &str) -> f64 {
+    let a = a.split_whitespace().collect::<BTreeSet<_>>();
+    let b = b.split_whitespace().collect::<BTreeSet<_>>();
+    if a.is_empty() && b.is_empty() {
+        return 1.0;
+    }
+
+    let common = a.intersection(&b).count();
+    common as f64 / (a.len() + b.len() - common) as f64
+}
+
+fn is_same_work_by_title(kept: &Paper, kept_title: &str, paper: &Paper, title: &str) -> bool {
+    if kept.cluster_id.is_some() && paper.cluster_id.is_some() {
+        // Different IDs, since the same ones are found by position
+        return false;
+    }
+    if let (Some(year), Some(other_year)) = (kept.year, paper.year) {
+        if year != other_year {
+            return false;
+        }
+    }
+    word_overlap(kept_title, title) >= TITLE_SIMILARITY_THRESHOLD
+}
+
+fn merge_into(kept: &mut Paper, mut paper: Paper) {
+    if kept.cluster_id.is_none() {
+        kept.set_cluster_id(paper.cluster_id);
+    }
+
+    let citation_count = match (kept.citation_count, paper.citation_count) {
+        (Some(c), Some(other)) if other.value > c.value => Some(other),
+        (None, other) => other,
+        (c, _) => c,
+    };
+
+    let citers = match (kept.citers.take(), paper.citers.take()) {
+        (Some(mut citers), Some(others)) => {
+            citers.extend(others);
+            Some(merge_duplicates(citers))
+        }
+        (citers, others) => citers.or(others),
+    };
+
+    kept.fill_missing(&paper);
+    kept.citation_count = citation_count;
+    kept.citers = citers;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merge_duplicates_test() {
+        let mut first = Paper::new("Quantal phase factors accompanying adiabatic changes", 1);
+        first.citation_count = Some(10.into());
+        first.citers = Some(vec![Paper::new("citer a", 11), Paper::new("citer b", 12)]);
+
+        let mut again = Paper::new("Quantal phase factors (version)", 1);
+        again.citation_count = Some(12.into());
+        again.venue = Some(String::from("Proc. R. Soc. Lond. A"));
+        again.citers = Some(vec![Paper::new("citer b", 12), Paper::new("citer c", 13)]);
+
+        let mut no_id = Paper::new("Quantal phase factors accompanying adiabatic changes", 0);
+        no_id.set_cluster_id(None);
+        no_id.year = Some(1984);
+
+        // Same title in another year is another work
+        let mut reprint = no_id.clone();
+        reprint.year = Some(2009);
+        reprint.link = Some(String::from("https://example.com/reprint"));
+
+        // Same title with another ID is another work
+        let mut another = Paper::new("Quantal phase factors accompanying adiabatic changes", 2);
+        another.year = Some(1984);
+
+        let papers = merge_duplicates(vec![
+            first,
+            another,
+            no_id,
+            again,
+            reprint.clone(),
+        ]);
+
+        assert_eq!(papers.len(), 3);
+        let merged = &papers[0];
+        assert_eq!(merged.cluster_id, Some(ClusterId(1)));
+        assert_eq!(merged.year, Some(1984));
+        assert_eq!(merged.citation_count, Some(12.into()));
+        assert_eq!(merged.venue, Some(String::from("Proc. R. Soc. Lond. A")));
+        assert_eq!(
+            merged
+                .citers
+                .as_ref()
+                .unwrap()
+                .iter()
+                .map(|p| &p.title[..])
+                .collect::<Vec<_>>(),
+            vec!["citer a", "citer b", "citer c"]
+        );
+        assert_eq!(papers[1].cluster_id, Some(ClusterId(2)));
+        assert_eq!(papers[2], reprint);
+    }
+
+    #[test]
+    fn merge_duplicates_without_ids_test() {
+        let mut a = Paper::new("On the Electrodynamics of Moving Bodies", 0);
+        a.set_cluster_id(None);
+        let mut b = Paper::new("On the electrodynamics of moving bodies.", 0);
+        b.set_cluster_id(None);
+        b.citation_count = Some(5.into());
+        let mut c = Paper::new("On the electrodynamics of moving bodies", 3);
+        c.pdf_link = Some(String::from("https://example.com/einstein.pdf"));
+
+        let papers = merge_duplicates(vec![a, b, c]);
+        assert_eq!(papers.len(), 1);
+        assert_eq!(papers[0].title, "On the Electrodynamics of Moving Bodies");
+        assert_eq!(papers[0].cluster_id, Some(ClusterId(3)));
+        assert_eq!(papers[0].citation_url, Some(ClusterId(3).citations_url()));
+        assert_eq!(papers[0].citation_count, Some(5.into()));
+        assert!(papers[0].pdf_link.is_some());
+    }
+
+    #[test]
+    fn title_similarity_test() {
+        assert_eq!(title_similarity("", ""), 1.0);
+        assert_eq!(title_similarity("foo", ""), 0.0);
+        assert_eq!(title_similarity("Berry phase", "berry phase effects"), 2.0 / 3.0);
+    }
+}
@@ -3,13 +3,16 @@
 error_chain!{
     foreign_links {
         Io(::std::io::Error);
-        Reqwest(::reqwest::Error);
+        Reqwest(::reqwest::Error) #[cfg(feature = "client")];
+        Sqlite(::rusqlite::Error) #[cfg(feature = "sqlite")];
         Parse(::std::num::ParseIntError);
+        Json(::serde_json::Error) #[cfg(feature = "serde_json")];
     }
 
     errors {
-        BadHtml {
+        BadHtml(section: String, snippet: String) {
             description("Bad HTML structure")
+            display("Bad HTML structure in {}: {}", section, snippet)
         }
         ResultNotFount {
             description("Result not found")
@@ -17,5 +20,84 @@ error_chain!{
         InvalidQuery {
             description("Invalid query")
         }
+        Blocked(kind: ::scrape::BlockKind) {
+            description("Blocked by Google Scholar")
+            display("Blocked by Google Scholar with {}", kind)
+        }
+        NoResultsContainer {
+            description("Container of results not found")
+        }
+        Http(url: String) {
+            description("HTTP request failed")
+            display("HTTP request to {} failed", url)
+        }
+        InvalidProxy(url: String) {
+            description("Invalid proxy")
+            display("Invalid proxy {}", url)
+        }
+        DisallowedByRobots(url: String) {
+            description("Disallowed by robots.txt")
+            display("Fetching {} is disallowed by robots.txt", url)
+        }
+        BudgetExceeded(limit: u32, window: String) {
+            description("Request budget exceeded")
+            display("Request budget of {} requests per {} exceeded", limit, window)
+        }
+        SnapshotNotFound(url: String) {
+            description("Snapshot not found")
+            display("Snapshot of {} not found", url)
+        }
+        ProfilePrivate {
+            description("Profile is private")
+        }
+        ExportLinkNotFound(format: String) {
+            description("Export link not found")
+            display("Link to export in {} not found", format)
+        }
+        InvalidCookie(line: String) {
+            description("Invalid cookie")
+            display("Invalid line in cookies.txt: {}", line)
+        }
+    }
+}
+
+/// Longest snippet kept in `ErrorKind::BadHtml`, in characters.
+const SNIPPET_LEN: usize = 200;
+
+impl ErrorKind {
+    /// `ErrorKind::BadHtml` reporting that scraping `section` (e.g. "title") failed,
+    /// with the beginning of the offending `html` (or text or URL), whitespace collapsed.
+    ///
+    /// The snippet helps to tell which part of the page changed its layout.
+    pub fn bad_html(section: &str, html: &str) -> Self {
+        let collapsed = html.split_whitespace().collect::<Vec<_>>().join(" ");
+        let mut snippet = collapsed.chars().take(SNIPPET_LEN).collect::<String>();
+        if snippet.len() < collapsed.len() {
+            snippet.push_str("...");
+        }
+        debug!("Failed to scrape {} from {}", section, snippet);
+        ErrorKind::BadHtml(section.to_owned(), snippet)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bad_html_test() {
+        let kind = ErrorKind::bad_html("title", "<h3 class=\"gs_rt\">\n    <span>foo</span>\n</h3>");
+        assert_eq!(
+            kind.to_string(),
+            "Bad HTML structure in title: <h3 class=\"gs_rt\"> <span>foo</span> </h3>"
+        );
+
+        match ErrorKind::bad_html("footer", &"a".repeat(300)) {
+            ErrorKind::BadHtml(section, snippet) => {
+                assert_eq!(section, "footer");
+                assert_eq!(snippet, format!("{}...", "a".repeat(200)));
+            }
+            _ => unreachable!(),
+        }
     }
 }
@@ -0,0 +1,915 @@
+//! Export scraped papers to other formats.
+
+use std::collections::{BTreeSet, HashMap};
+use std::io;
+
+use batch::{BatchEntry, LookupOutcome};
+use graph::{CitationGraph, CoauthorGraph};
+use paper::{DocType, Paper};
+use errors::*;
+
+/// Write a citation graph in Graphviz DOT format.
+///
+/// `graph` maps a cluster ID of a paper to the cluster IDs of papers citing it.
+/// Each edge is drawn from the citing paper to the cited one.
+/// Nodes are labeled with `labels` (typically paper titles),
+/// or with their cluster IDs if missing from `labels`.
+///
+/// Nodes and edges are written in ascending order of cluster IDs so that the output is stable.
+///
+/// # Example
+///
+/// ```
+/// use std::collections::HashMap;
+/// use scholar::export::export_graph_dot;
+///
+/// let mut graph = HashMap::new();
+/// graph.insert(1, vec![2]);
+///
+/// let mut labels = HashMap::new();
+/// labels.insert(1, String::from("foo"));
+/// labels.insert(2, String::from("bar"));
+///
+/// let mut dot = Vec::new();
+/// export_graph_dot(&graph, &labels, &mut dot).unwrap();
+/// assert_eq!(
+///     String::from_utf8(dot).unwrap(),
+///     r#"digraph citations {
+///     1 [label="foo"];
+///     2 [label="bar"];
+///     2 -> 1;
+/// }
+/// "#
+/// );
+/// ```
+pub fn export_graph_dot<W: io::Write>(
+    graph: &HashMap<u64, Vec<u64>>,
+    labels: &HashMap<u64, String>,
+    mut w: W,
+) -> Result<()> {
+    writeln!(w, "digraph citations {{")?;
+    for id in graph_nodes(graph) {
+        writeln!(
+            w,
+            "    {} [label=\"{}\"];",
+            id,
+            escape_dot(&node_label(labels, id))
+        )?;
+    }
+    for (cited, citer) in graph_edges(graph) {
+        writeln!(w, "    {} -> {};", citer, cited)?;
+    }
+    writeln!(w, "}}")?;
+
+    Ok(())
+}
+
+/// Write a citation graph in GraphML format, which can be imported by e.g. Gephi.
+///
+/// `graph` and `labels` are interpreted in the same way as `export_graph_dot()`.
+/// Labels are stored in the `label` attribute of nodes.
+pub fn export_graph_graphml<W: io::Write>(
+    graph: &HashMap<u64, Vec<u64>>,
+    labels: &HashMap<u64, String>,
+    mut w: W,
+) -> Result<()> {
+    writeln!(w, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(w, r#"<graphml xmlns="http://graphml.graphdrawing.org/xmlns">"#)?;
+    writeln!(
+        w,
+        r#"  <key id="label" for="node" attr.name="label" attr.type="string"/>"#
+    )?;
+    writeln!(w, r#"  <graph id="citations" edgedefault="directed">"#)?;
+    for id in graph_nodes(graph) {
+        writeln!(
+            w,
+            r#"    <node id="n{}"><data key="label">{}</data></node>"#,
+            id,
+            escape_xml(&node_label(labels, id))
+        )?;
+    }
+    for (cited, citer) in graph_edges(graph) {
+        writeln!(w, r#"    <edge source="n{}" target="n{}"/>"#, citer, cited)?;
+    }
+    writeln!(w, "  </graph>")?;
+    writeln!(w, "</graphml>")?;
+
+    Ok(())
+}
+
+/// Write a graph crawled by `graph::crawl_citations()` in Graphviz DOT format.
+///
+/// Nodes are labeled with paper titles, and edges are drawn from citing papers to cited ones,
+/// as in `export_graph_dot()`. Papers without any citation are kept as isolated nodes.
+///
+/// # Example
+///
+/// ```
+/// use scholar::export::export_citation_graph_dot;
+/// use scholar::graph::{crawl_citations, CrawlOptions};
+/// use scholar::id::ClusterId;
+/// use scholar::paper::Paper;
+///
+/// let graph = crawl_citations(ClusterId(1), &CrawlOptions::default(), |_| {
+///     let mut paper = Paper::new("foo", 1);
+///     paper.citers = Some(vec![Paper::new("bar", 2)]);
+///     Ok(paper)
+/// }).unwrap();
+///
+/// let mut dot = Vec::new();
+/// export_citation_graph_dot(&graph, &mut dot).unwrap();
+/// assert!(String::from_utf8(dot).unwrap().contains("2 -> 1;"));
+/// ```
+pub fn export_citation_graph_dot<W: io::Write>(graph: &CitationGraph, w: W) -> Result<()> {
+    let (citers, labels) = citation_graph_maps(graph);
+    export_graph_dot(&citers, &labels, w)
+}
+
+/// Write a graph crawled by `graph::crawl_citations()` in GraphML format.
+///
+/// Nodes and edges are written as in `export_citation_graph_dot()`.
+pub fn export_citation_graph_graphml<W: io::Write>(graph: &CitationGraph, w: W) -> Result<()> {
+    let (citers, labels) = citation_graph_maps(graph);
+    export_graph_graphml(&citers, &labels, w)
+}
+
+/// Convert `graph` into the map of citers and the map of labels of `export_graph_dot()`.
+fn citation_graph_maps(graph: &CitationGraph) -> (HashMap<u64, Vec<u64>>, HashMap<u64, String>) {
+    let mut citers = graph
+        .papers
+        .keys()
+        .map(|id| (id.as_u64(), Vec::new()))
+        .collect::<HashMap<_, _>>();
+    for &(citing, cited) in &graph.edges {
+        citers
+            .entry(cited.as_u64())
+            .or_insert_with(Vec::new)
+            .push(citing.as_u64());
+    }
+
+    let labels = graph
+        .papers
+        .iter()
+        .map(|(id, paper)| (id.as_u64(), paper.title.clone()))
+        .collect();
+
+    (citers, labels)
+}
+
+/// Write a graph built by `graph::coauthor_graph()` in Graphviz DOT format.
+///
+/// Nodes are labeled with names of authors and numbered in ascending order of the names.
+/// Undirected edges are weighted with the number of papers shared.
+///
+/// # Example
+///
+/// ```
+/// use scholar::export::export_coauthor_graph_dot;
+/// use scholar::graph::coauthor_graph;
+/// use scholar::paper::Paper;
+///
+/// let mut paper = Paper::new("foo", 1);
+/// paper.authors = Some(String::from("Y Aharonov, D Bohm"));
+///
+/// let mut dot = Vec::new();
+/// export_coauthor_graph_dot(&coauthor_graph(&[paper]), &mut dot).unwrap();
+/// assert_eq!(
+///     String::from_utf8(dot).unwrap(),
+///     r#"graph coauthors {
+///     0 [label="D Bohm"];
+///     1 [label="Y Aharonov"];
+///     0 -- 1 [weight=1];
+/// }
+/// "#
+/// );
+/// ```
+pub fn export_coauthor_graph_dot<W: io::Write>(graph: &CoauthorGraph, mut w: W) -> Result<()> {
+    let ids = coauthor_ids(graph);
+
+    writeln!(w, "graph coauthors {{")?;
+    for (id, author) in graph.authors.keys().enumerate() {
+        writeln!(w, "    {} [label=\"{}\"];", id, escape_dot(author))?;
+    }
+    for (a, b, weight) in coauthor_edges(graph, &ids) {
+        writeln!(w, "    {} -- {} [weight={}];", a, b, weight)?;
+    }
+    writeln!(w, "}}")?;
+
+    Ok(())
+}
+
+/// Write a graph built by `graph::coauthor_graph()` in GraphML format.
+///
+/// Nodes and edges are written as in `export_coauthor_graph_dot()`,
+/// with weights in the `weight` attribute of edges.
+pub fn export_coauthor_graph_graphml<W: io::Write>(graph: &CoauthorGraph, mut w: W) -> Result<()> {
+    let ids = coauthor_ids(graph);
+
+    writeln!(w, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(w, r#"<graphml xmlns="http://graphml.graphdrawing.org/xmlns">"#)?;
+    writeln!(
+        w,
+        r#"  <key id="label" for="node" attr.name="label" attr.type="string"/>"#
+    )?;
+    writeln!(
+        w,
+        r#"  <key id="weight" for="edge" attr.name="weight" attr.type="int"/>"#
+    )?;
+    writeln!(w, r#"  <graph id="coauthors" edgedefault="undirected">"#)?;
+    for (id, author) in graph.authors.keys().enumerate() {
+        writeln!(
+            w,
+            r#"    <node id="n{}"><data key="label">{}</data></node>"#,
+            id,
+            escape_xml(author)
+        )?;
+    }
+    for (a, b, weight) in coauthor_edges(graph, &ids) {
+        writeln!(
+            w,
+            r#"    <edge source="n{}" target="n{}"><data key="weight">{}</data></edge>"#,
+            a, b, weight
+        )?;
+    }
+    writeln!(w, "  </graph>")?;
+    writeln!(w, "</graphml>")?;
+
+    Ok(())
+}
+
+/// Numbers of authors in ascending order of names.
+fn coauthor_ids(graph: &CoauthorGraph) -> HashMap<&str, usize> {
+    graph
+        .authors
+        .keys()
+        .enumerate()
+        .map(|(id, author)| (author.as_str(), id))
+        .collect()
+}
+
+/// Edges of `graph` between numbers of `ids`, with their weights.
+/// Authors linked but missing from `graph.authors` are skipped.
+fn coauthor_edges(graph: &CoauthorGraph, ids: &HashMap<&str, usize>) -> Vec<(usize, usize, u32)> {
+    graph
+        .edges
+        .iter()
+        .filter_map(|((a, b), &weight)| {
+            Some((*ids.get(a.as_str())?, *ids.get(b.as_str())?, weight))
+        })
+        .collect()
+}
+
+/// Write `papers` in CSV format with columns `id` (cluster ID), `title`, and `citation_count`.
+///
+/// A header row comes first, and rows end with `\n`.
+/// Unknown IDs and citation counts are left empty.
+/// `papers` may be a slice or any iterator, which is consumed one paper at a time.
+///
+/// # Example
+///
+/// ```
+/// use scholar::export::papers_to_csv;
+/// use scholar::paper::Paper;
+///
+/// let mut paper = Paper::new("Space, time, and gravity", 42);
+/// paper.citation_count = Some(10.into());
+///
+/// let mut csv = Vec::new();
+/// papers_to_csv(&[paper], &mut csv).unwrap();
+/// assert_eq!(
+///     String::from_utf8(csv).unwrap(),
+///     "id,title,citation_count\n42,\"Space, time, and gravity\",10\n"
+/// );
+/// ```
+pub fn papers_to_csv<'a, I, W>(papers: I, mut w: W) -> Result<()>
+where
+    I: IntoIterator<Item = &'a Paper>,
+    W: io::Write,
+{
+    writeln!(w, "id,title,citation_count")?;
+    for paper in papers {
+        writeln!(
+            w,
+            "{},{},{}",
+            paper.cluster_id.map_or(String::new(), |id| id.to_string()),
+            escape_csv(&paper.title),
+            paper
+                .citation_count
+                .map_or(String::new(), |c| c.value.to_string())
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Write entries looked up by `batch::lookup_batch()` in CSV format with columns
+/// `entry` (the title or DOI looked up), `status` (`matched`, `unresolved`, or `failed`),
+/// `id` (cluster ID), `title`, `citation_count`, and `confidence`.
+///
+/// A header row comes first, and rows end with `\n`.
+/// The paper columns of unresolved entries are of the best paper found but not confident
+/// enough, if any, and those of failed entries are left empty.
+/// `entries` may be a slice or any iterator, which is consumed one entry at a time.
+///
+/// # Example
+///
+/// ```
+/// use scholar::batch::{lookup_batch, LookupInput};
+/// use scholar::export::batch_report_to_csv;
+/// use scholar::matcher::TitleMatch;
+/// use scholar::paper::Paper;
+///
+/// let inputs = vec![LookupInput::parse("Space, time, and gravity")];
+/// let entries = lookup_batch(inputs, 0.8, |_| {
+///     let mut paper = Paper::new("Space, time and gravity", 42);
+///     paper.citation_count = Some(10.into());
+///     Ok(Some(TitleMatch { paper, confidence: 0.95 }))
+/// }).collect::<Vec<_>>();
+///
+/// let mut csv = Vec::new();
+/// batch_report_to_csv(&entries, &mut csv).unwrap();
+/// assert_eq!(
+///     String::from_utf8(csv).unwrap(),
+///     "entry,status,id,title,citation_count,confidence\n\
+///      \"Space, time, and gravity\",matched,42,\"Space, time and gravity\",10,0.95\n"
+/// );
+/// ```
+pub fn batch_report_to_csv<'a, I, W>(entries: I, mut w: W) -> Result<()>
+where
+    I: IntoIterator<Item = &'a BatchEntry>,
+    W: io::Write,
+{
+    writeln!(w, "entry,status,id,title,citation_count,confidence")?;
+    for entry in entries {
+        let (status, title_match) = match entry.outcome {
+            LookupOutcome::Matched(ref m) => ("matched", Some(m)),
+            LookupOutcome::Unresolved(ref m) => ("unresolved", m.as_ref()),
+            LookupOutcome::Failed(_) => ("failed", None),
+        };
+        let paper = title_match.map(|m| &m.paper);
+        writeln!(
+            w,
+            "{},{},{},{},{},{}",
+            escape_csv(entry.input.as_str()),
+            status,
+            paper
+                .and_then(|p| p.cluster_id)
+                .map_or(String::new(), |id| id.to_string()),
+            paper.map_or(String::new(), |p| escape_csv(&p.title)),
+            paper
+                .and_then(|p| p.citation_count)
+                .map_or(String::new(), |c| c.value.to_string()),
+            title_match.map_or(String::new(), |m| format!("{:.2}", m.confidence))
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Write citations among `papers` and their `citers`, nested at any depth,
+/// in CSV format with columns `citer_id` and `cited_id`.
+///
+/// A header row comes first, and rows end with `\n`.
+/// Citations from or to papers without cluster ID are skipped.
+///
+/// # Example
+///
+/// ```
+/// use scholar::export::citations_to_csv;
+/// use scholar::paper::Paper;
+///
+/// let mut paper = Paper::new("foo", 1);
+/// paper.citers = Some(vec![Paper::new("bar", 2)]);
+///
+/// let mut csv = Vec::new();
+/// citations_to_csv(&[paper], &mut csv).unwrap();
+/// assert_eq!(String::from_utf8(csv).unwrap(), "citer_id,cited_id\n2,1\n");
+/// ```
+pub fn citations_to_csv<'a, I, W>(papers: I, mut w: W) -> Result<()>
+where
+    I: IntoIterator<Item = &'a Paper>,
+    W: io::Write,
+{
+    fn write_citers<W: io::Write>(paper: &Paper, w: &mut W) -> Result<()> {
+        for citer in paper.citers.iter().flatten() {
+            if let (Some(citer_id), Some(cited_id)) = (citer.cluster_id, paper.cluster_id) {
+                writeln!(w, "{},{}", citer_id, cited_id)?;
+            }
+            write_citers(citer, w)?;
+        }
+        Ok(())
+    }
+
+    writeln!(w, "citer_id,cited_id")?;
+    for paper in papers {
+        write_citers(paper, &mut w)?;
+    }
+
+    Ok(())
+}
+
+/// Write `papers` in JSON Lines format, one object of `Paper::to_json_value()` per line.
+///
+/// Citers are kept nested in each object;
+/// use `citations_to_csv()` to get citations as flat rows.
+///
+/// # Example
+///
+/// ```
+/// use scholar::export::papers_to_jsonl;
+/// use scholar::paper::Paper;
+///
+/// let mut jsonl = Vec::new();
+/// papers_to_jsonl(&[Paper::new("foo", 1), Paper::new("bar", 2)], &mut jsonl).unwrap();
+///
+/// let jsonl = String::from_utf8(jsonl).unwrap();
+/// assert_eq!(jsonl.lines().count(), 2);
+/// assert!(jsonl.starts_with(r#"{"access":"unknown","#));
+/// ```
+#[cfg(feature = "serde_json")]
+pub fn papers_to_jsonl<'a, I, W>(papers: I, mut w: W) -> Result<()>
+where
+    I: IntoIterator<Item = &'a Paper>,
+    W: io::Write,
+{
+    for paper in papers {
+        writeln!(w, "{}", paper.to_json_value())?;
+    }
+
+    Ok(())
+}
+
+/// Write `papers` in RIS format, which can be imported by e.g. Zotero and Mendeley.
+///
+/// Books are typed `BOOK` with the venue as publisher, and others `JOUR` with the venue as journal.
+/// Each author is written in a line as shown by Google Scholar, e.g. "MV Berry".
+/// Unknown fields are omitted.
+///
+/// # Example
+///
+/// ```
+/// use scholar::export::papers_to_ris;
+/// use scholar::paper::Paper;
+///
+/// let mut paper = Paper::new("Quantal phase factors", 42);
+/// paper.authors = Some(String::from("MV Berry"));
+/// paper.year = Some(1984);
+///
+/// let mut ris = Vec::new();
+/// papers_to_ris(&[paper], &mut ris).unwrap();
+/// assert_eq!(
+///     String::from_utf8(ris).unwrap(),
+///     "TY  - JOUR\nID  - 42\nTI  - Quantal phase factors\nAU  - MV Berry\nPY  - 1984\nER  - \n"
+/// );
+/// ```
+pub fn papers_to_ris<'a, I, W>(papers: I, mut w: W) -> Result<()>
+where
+    I: IntoIterator<Item = &'a Paper>,
+    W: io::Write,
+{
+    for paper in papers {
+        let book = is_book(paper);
+
+        writeln!(w, "TY  - {}", if book { "BOOK" } else { "JOUR" })?;
+        if let Some(id) = paper.cluster_id {
+            writeln!(w, "ID  - {}", id)?;
+        }
+        writeln!(w, "TI  - {}", single_line(&paper.title))?;
+        for author in paper.author_list() {
+            writeln!(w, "AU  - {}", author)?;
+        }
+        if let Some(year) = paper.year {
+            writeln!(w, "PY  - {}", year)?;
+        }
+        if let Some(ref venue) = paper.venue {
+            writeln!(w, "{}  - {}", if book { "PB" } else { "JO" }, single_line(venue))?;
+        }
+        if let Some(ref link) = paper.link {
+            writeln!(w, "UR  - {}", link)?;
+        }
+        if let Some(ref pdf_link) = paper.pdf_link {
+            writeln!(w, "L1  - {}", pdf_link)?;
+        }
+        if let Some(ref doi) = paper.doi {
+            writeln!(w, "DO  - {}", doi)?;
+        }
+        writeln!(w, "ER  - ")?;
+    }
+
+    Ok(())
+}
+
+/// Write `papers` in EndNote tagged format (`.enw`), separated by empty lines.
+///
+/// Fields are chosen as in `papers_to_ris()`; the cluster ID is written as accession number.
+///
+/// # Example
+///
+/// ```
+/// use scholar::export::papers_to_endnote;
+/// use scholar::paper::Paper;
+///
+/// let mut paper = Paper::new("Quantal phase factors", 42);
+/// paper.year = Some(1984);
+///
+/// let mut enw = Vec::new();
+/// papers_to_endnote(&[paper], &mut enw).unwrap();
+/// assert_eq!(
+///     String::from_utf8(enw).unwrap(),
+///     "%0 Journal Article\n%T Quantal phase factors\n%D 1984\n%M 42\n"
+/// );
+/// ```
+pub fn papers_to_endnote<'a, I, W>(papers: I, mut w: W) -> Result<()>
+where
+    I: IntoIterator<Item = &'a Paper>,
+    W: io::Write,
+{
+    for (i, paper) in papers.into_iter().enumerate() {
+        let book = is_book(paper);
+
+        if i > 0 {
+            writeln!(w)?;
+        }
+        writeln!(w, "%0 {}", if book { "Book" } else { "Journal Article" })?;
+        writeln!(w, "%T {}", single_line(&paper.title))?;
+        for author in paper.author_list() {
+            writeln!(w, "%A {}", author)?;
+        }
+        if let Some(year) = paper.year {
+            writeln!(w, "%D {}", year)?;
+        }
+        if let Some(ref venue) = paper.venue {
+            writeln!(w, "%{} {}", if book { "I" } else { "J" }, single_line(venue))?;
+        }
+        if let Some(ref link) = paper.link {
+            writeln!(w, "%U {}", link)?;
+        }
+        if let Some(ref doi) = paper.doi {
+            writeln!(w, "%R {}", doi)?;
+        }
+        if let Some(id) = paper.cluster_id {
+            writeln!(w, "%M {}", id)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn is_book(paper: &Paper) -> bool {
+    paper.doc_type == Some(DocType::Book)
+}
+
+/// Fold line breaks, which end a field in RIS and EndNote formats, into spaces.
+fn single_line(s: &str) -> String {
+    s.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn graph_nodes(graph: &HashMap<u64, Vec<u64>>) -> BTreeSet<u64> {
+    graph
+        .iter()
+        .flat_map(|(cited, citers)| citers.iter().chain(Some(cited)))
+        .cloned()
+        .collect()
+}
+
+fn graph_edges(graph: &HashMap<u64, Vec<u64>>) -> BTreeSet<(u64, u64)> {
+    graph
+        .iter()
+        .flat_map(|(&cited, citers)| citers.iter().map(move |&citer| (cited, citer)))
+        .collect()
+}
+
+fn node_label(labels: &HashMap<u64, String>, id: u64) -> String {
+    match labels.get(&id) {
+        Some(label) => label.clone(),
+        None => id.to_string(),
+    }
+}
+
+fn escape_dot(s: &str) -> String {
+    s.replace('\\', r"\\").replace('"', r#"\""#)
+}
+
+/// Quote `s` if it contains a comma, a double quote, or a line break,
+/// doubling the double quotes in it.
+fn escape_csv(s: &str) -> String {
+    if s.contains(&[',', '"', '\n', '\r'][..]) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_owned()
+    }
+}
+
+fn escape_xml(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_graph() -> (HashMap<u64, Vec<u64>>, HashMap<u64, String>) {
+        let mut graph = HashMap::new();
+        graph.insert(1, vec![2, 3]);
+        graph.insert(2, vec![3]);
+
+        let mut labels = HashMap::new();
+        labels.insert(1, String::from(r#"Significance of "potentials""#));
+        labels.insert(2, String::from("Quantal phase factors & adiabatic changes"));
+
+        (graph, labels)
+    }
+
+    #[test]
+    fn export_graph_dot_test() {
+        let (graph, labels) = test_graph();
+
+        let mut dot = Vec::new();
+        export_graph_dot(&graph, &labels, &mut dot).unwrap();
+
+        assert_eq!(
+            String::from_utf8(dot).unwrap(),
+            r#"digraph citations {
+    1 [label="Significance of \"potentials\""];
+    2 [label="Quantal phase factors & adiabatic changes"];
+    3 [label="3"];
+    2 -> 1;
+    3 -> 1;
+    3 -> 2;
+}
+"#
+        );
+    }
+
+    #[test]
+    fn export_citation_graph_test() {
+        use graph::{crawl_citations, CrawlOptions};
+        use id::ClusterId;
+
+        let options = CrawlOptions {
+            depth: 2,
+            max_requests: 10,
+        };
+        let graph = crawl_citations(ClusterId(1), &options, |id| {
+            let mut paper = Paper::new(&format!("paper {}", id), id.as_u64());
+            if id == ClusterId(1) {
+                paper.citers = Some(vec![Paper::new("paper 2", 2), Paper::new("paper 3", 3)]);
+            }
+            Ok(paper)
+        }).unwrap();
+
+        let mut dot = Vec::new();
+        export_citation_graph_dot(&graph, &mut dot).unwrap();
+        assert_eq!(
+            String::from_utf8(dot).unwrap(),
+            r#"digraph citations {
+    1 [label="paper 1"];
+    2 [label="paper 2"];
+    3 [label="paper 3"];
+    2 -> 1;
+    3 -> 1;
+}
+"#
+        );
+
+        let mut graphml = Vec::new();
+        export_citation_graph_graphml(&graph, &mut graphml).unwrap();
+        let graphml = String::from_utf8(graphml).unwrap();
+        assert!(graphml.contains(r#"<node id="n3"><data key="label">paper 3</data></node>"#));
+        assert!(graphml.contains(r#"<edge source="n2" target="n1"/>"#));
+    }
+
+    #[test]
+    fn export_coauthor_graph_test() {
+        use graph::coauthor_graph;
+
+        let papers = [
+            ("A Tonomura, N Osakabe", 1),
+            ("N Osakabe, A Tonomura, T Matsuda", 2),
+            ("A \"Quoted\" Name & Co", 3),
+        ].iter()
+            .map(|&(authors, id)| {
+                let mut paper = Paper::new("foo", id);
+                paper.authors = Some(authors.to_owned());
+                paper
+            })
+            .collect::<Vec<_>>();
+        let graph = coauthor_graph(&papers);
+
+        let mut dot = Vec::new();
+        export_coauthor_graph_dot(&graph, &mut dot).unwrap();
+        assert_eq!(
+            String::from_utf8(dot).unwrap(),
+            r#"graph coauthors {
+    0 [label="A \"Quoted\" Name & Co"];
+    1 [label="A Tonomura"];
+    2 [label="N Osakabe"];
+    3 [label="T Matsuda"];
+    1 -- 2 [weight=2];
+    1 -- 3 [weight=1];
+    2 -- 3 [weight=1];
+}
+"#
+        );
+
+        let mut graphml = Vec::new();
+        export_coauthor_graph_graphml(&graph, &mut graphml).unwrap();
+        let graphml = String::from_utf8(graphml).unwrap();
+        assert!(graphml.contains(r#"<graph id="coauthors" edgedefault="undirected">"#));
+        assert!(graphml.contains(
+            r#"<node id="n0"><data key="label">A &quot;Quoted&quot; Name &amp; Co</data></node>"#
+        ));
+        assert!(graphml.contains(
+            r#"<edge source="n1" target="n2"><data key="weight">2</data></edge>"#
+        ));
+    }
+
+    #[test]
+    fn papers_to_csv_test() {
+        let mut quoted = Paper::new(r#"Berry's phase, "geometric" phase"#, 1);
+        quoted.citation_count = Some(13000.into());
+        let mut no_id = Paper::new("Quantum field theory", 0);
+        no_id.set_cluster_id(None);
+
+        let mut csv = Vec::new();
+        papers_to_csv(&[quoted, no_id], &mut csv).unwrap();
+
+        assert_eq!(
+            String::from_utf8(csv).unwrap(),
+            r#"id,title,citation_count
+1,"Berry's phase, ""geometric"" phase",13000
+,Quantum field theory,
+"#
+        );
+    }
+
+    #[test]
+    fn batch_report_to_csv_test() {
+        use batch::{lookup_batch, LookupInput};
+        use matcher::TitleMatch;
+
+        let inputs = vec!["10.1234/foo", "bar", "baz"].into_iter().map(LookupInput::parse);
+        let entries = lookup_batch(inputs, 0.8, |input| match input.as_str() {
+            "10.1234/foo" => Ok(Some(TitleMatch {
+                paper: Paper::new("foo", 1),
+                confidence: 1.0,
+            })),
+            "bar" => Ok(Some(TitleMatch {
+                paper: Paper::new("barbell", 2),
+                confidence: 0.4,
+            })),
+            _ => Err(ErrorKind::Http(String::from("500")).into()),
+        }).collect::<Vec<_>>();
+
+        let mut csv = Vec::new();
+        batch_report_to_csv(&entries, &mut csv).unwrap();
+
+        assert_eq!(
+            String::from_utf8(csv).unwrap(),
+            "entry,status,id,title,citation_count,confidence\n\
+             10.1234/foo,matched,1,foo,,1.00\n\
+             bar,unresolved,2,barbell,,0.40\n\
+             baz,failed,,,,\n"
+        );
+    }
+
+    #[test]
+    fn citations_to_csv_test() {
+        let mut citer = Paper::new("citer", 2);
+        citer.citers = Some(vec![Paper::new("citer of citer", 3)]);
+        let mut no_id = Paper::new("no ID", 0);
+        no_id.set_cluster_id(None);
+        no_id.citers = Some(vec![Paper::new("citer of no ID", 5)]);
+
+        let mut paper = Paper::new("cited", 1);
+        paper.citers = Some(vec![citer, no_id, Paper::new("another citer", 4)]);
+
+        let mut csv = Vec::new();
+        citations_to_csv([paper].iter(), &mut csv).unwrap();
+
+        assert_eq!(
+            String::from_utf8(csv).unwrap(),
+            "citer_id,cited_id\n2,1\n3,2\n4,1\n"
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "serde_json")]
+    fn papers_to_jsonl_test() {
+        use serde_json::{self, Value};
+
+        let mut paper = Paper::new("foo\nbar", 1);
+        paper.citers = Some(vec![Paper::new("baz", 2)]);
+
+        let mut jsonl = Vec::new();
+        papers_to_jsonl(&[paper.clone(), Paper::new("qux", 3)], &mut jsonl).unwrap();
+
+        let jsonl = String::from_utf8(jsonl).unwrap();
+        let lines = jsonl.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(
+            serde_json::from_str::<Value>(lines[0]).unwrap(),
+            paper.to_json_value()
+        );
+        assert_eq!(serde_json::from_str::<Value>(lines[1]).unwrap()["title"], "qux");
+    }
+
+    fn ris_test_papers() -> Vec<Paper> {
+        let mut article = Paper::new("Quantal phase factors\naccompanying adiabatic changes", 1);
+        article.authors = Some(String::from("MV Berry, B Simon"));
+        article.venue = Some(String::from("Proc. R. Soc. Lond. A"));
+        article.link = Some(String::from("https://example.com/berry"));
+        article.pdf_link = Some(String::from("https://example.com/berry.pdf"));
+
+        let mut book = Paper::new("Quantum field theory", 0);
+        book.set_cluster_id(None);
+        book.year = Some(1996);
+        book.venue = Some(String::from("Oxford University Press"));
+        book.doc_type = Some(DocType::Book);
+
+        vec![article, book]
+    }
+
+    #[test]
+    fn papers_to_ris_test() {
+        let mut ris = Vec::new();
+        papers_to_ris(&ris_test_papers(), &mut ris).unwrap();
+
+        assert_eq!(
+            String::from_utf8(ris).unwrap(),
+            "TY  - JOUR
+ID  - 1
+TI  - Quantal phase factors accompanying adiabatic changes
+AU  - MV Berry
+AU  - B Simon
+JO  - Proc. R. Soc. Lond. A
+UR  - https://example.com/berry
+L1  - https://example.com/berry.pdf
+ER  - \n\
+TY  - BOOK
+TI  - Quantum field theory
+PY  - 1996
+PB  - Oxford University Press
+ER  - \n"
+        );
+    }
+
+    #[test]
+    fn papers_to_endnote_test() {
+        let mut enw = Vec::new();
+        papers_to_endnote(&ris_test_papers(), &mut enw).unwrap();
+
+        assert_eq!(
+            String::from_utf8(enw).unwrap(),
+            "%0 Journal Article
+%T Quantal phase factors accompanying adiabatic changes
+%A MV Berry
+%A B Simon
+%J Proc. R. Soc. Lond. A
+%U https://example.com/berry
+%M 1
+
+%0 Book
+%T Quantum field theory
+%D 1996
+%I Oxford University Press
+"
+        );
+    }
+
+    #[test]
+    fn escape_csv_test() {
+        assert_eq!(escape_csv("foo"), "foo");
+        assert_eq!(escape_csv("foo, bar"), r#""foo, bar""#);
+        assert_eq!(escape_csv(r#"say "foo""#), r#""say ""foo""""#);
+        assert_eq!(escape_csv("foo\nbar"), "\"foo\nbar\"");
+    }
+
+    #[test]
+    fn export_graph_graphml_test() {
+        let (graph, labels) = test_graph();
+
+        let mut graphml = Vec::new();
+        export_graph_graphml(&graph, &labels, &mut graphml).unwrap();
+
+        assert_eq!(
+            String::from_utf8(graphml).unwrap(),
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<graphml xmlns="http://graphml.graphdrawing.org/xmlns">
+  <key id="label" for="node" attr.name="label" attr.type="string"/>
+  <graph id="citations" edgedefault="directed">
+    <node id="n1"><data key="label">Significance of &quot;potentials&quot;</data></node>
+    <node id="n2"><data key="label">Quantal phase factors &amp; adiabatic changes</data></node>
+    <node id="n3"><data key="label">3</data></node>
+    <edge source="n2" target="n1"/>
+    <edge source="n3" target="n1"/>
+    <edge source="n3" target="n2"/>
+  </graph>
+</graphml>
+"#
+        );
+    }
+}
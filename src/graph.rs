@@ -0,0 +1,553 @@
+//! Crawl citation pages into a graph of papers citing each other,
+//! and build a graph of authors writing papers together.
+
+use std::cmp;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+
+use id::ClusterId;
+use paper::Paper;
+use errors::*;
+
+/// Limits of crawling citations.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CrawlOptions {
+    /// How many hops of citations to follow from the root.
+    /// 1 fetches papers citing the root only.
+    pub depth: u32,
+    /// Maximum number of citation pages to fetch.
+    pub max_requests: u32,
+}
+
+impl Default for CrawlOptions {
+    /// Follow one hop with at most 10 requests.
+    fn default() -> Self {
+        Self {
+            depth: 1,
+            max_requests: 10,
+        }
+    }
+}
+
+/// Papers found by crawling citations, and which of them cites which.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CitationGraph {
+    /// Papers keyed by cluster ID, including the root.
+    /// `citers` of each paper are left `None`; see `edges` instead.
+    pub papers: HashMap<ClusterId, Paper>,
+    /// Pairs of `(citing, cited)` cluster IDs, in the order found, without duplicates.
+    pub edges: Vec<(ClusterId, ClusterId)>,
+    /// Whether the crawl stopped at `CrawlOptions::max_requests` before reaching the depth.
+    pub truncated: bool,
+}
+
+impl CitationGraph {
+    /// Cluster IDs of papers citing `cited`.
+    pub fn citers_of(&self, cited: ClusterId) -> Vec<ClusterId> {
+        self.edges
+            .iter()
+            .filter(|&&(_, to)| to == cited)
+            .map(|&(from, _)| from)
+            .collect()
+    }
+}
+
+/// Crawl citations breadth-first from `root`, fetching each citation page with `fetch`.
+///
+/// `fetch` takes a cluster ID and returns the cited paper with `citers` populated,
+/// e.g. by `CitationDocument::scrape_target_paper_with_citers()`.
+/// Each cluster ID is fetched at most once, so cycles of citations are crawled safely.
+/// Papers seen more than once are merged with `Paper::merge()`.
+/// Citers without cluster ID cannot be told apart, so they are skipped.
+///
+/// Returns the first error of `fetch`.
+///
+/// # Example
+///
+/// ```
+/// use scholar::graph::{crawl_citations, CrawlOptions};
+/// use scholar::id::ClusterId;
+/// use scholar::paper::Paper;
+///
+/// let graph = crawl_citations(ClusterId(1), &CrawlOptions::default(), |id| {
+///     let mut paper = Paper::new("foo", id.as_u64());
+///     paper.citers = Some(vec![Paper::new("bar", 2)]);
+///     Ok(paper)
+/// }).unwrap();
+/// assert_eq!(graph.edges, vec![(ClusterId(2), ClusterId(1))]);
+/// ```
+pub fn crawl_citations<F>(
+    root: ClusterId,
+    options: &CrawlOptions,
+    mut fetch: F,
+) -> Result<CitationGraph>
+where
+    F: FnMut(ClusterId) -> Result<Paper>,
+{
+    crawl_citations_batched(root, options, 1, |ids| vec![fetch(ids[0])])
+}
+
+/// Like `crawl_citations()`, but fetches up to `batch_size` citation pages at once
+/// with `fetch`, e.g. in parallel by `Client::crawl_citations_parallel()`.
+///
+/// `fetch` takes cluster IDs in the order queued, and returns a result for each of them
+/// in the same order. Papers are added to the graph in that order,
+/// so the graph does not depend on which page arrives first.
+/// A `batch_size` of 0 is taken as 1.
+///
+/// # Example
+///
+/// ```
+/// use scholar::graph::{crawl_citations_batched, CrawlOptions};
+/// use scholar::id::ClusterId;
+/// use scholar::paper::Paper;
+///
+/// let options = CrawlOptions { depth: 2, max_requests: 10 };
+/// let graph = crawl_citations_batched(ClusterId(1), &options, 4, |ids| {
+///     ids.iter()
+///         .map(|id| {
+///             let mut paper = Paper::new("foo", id.as_u64());
+///             if id.as_u64() == 1 {
+///                 paper.citers = Some(vec![Paper::new("bar", 2), Paper::new("baz", 3)]);
+///             }
+///             Ok(paper)
+///         })
+///         .collect()
+/// }).unwrap();
+/// assert_eq!(graph.papers.len(), 3);
+/// ```
+pub fn crawl_citations_batched<F>(
+    root: ClusterId,
+    options: &CrawlOptions,
+    batch_size: usize,
+    mut fetch: F,
+) -> Result<CitationGraph>
+where
+    F: FnMut(&[ClusterId]) -> Vec<Result<Paper>>,
+{
+    let batch_size = cmp::max(batch_size, 1);
+    let mut graph = CitationGraph::default();
+    let mut edges = HashSet::new();
+    let mut visited = HashSet::new();
+    let mut queue = VecDeque::new();
+    let mut requests = 0;
+
+    visited.insert(root);
+    queue.push_back((root, 0));
+
+    while !graph.truncated {
+        let mut batch = Vec::new();
+        while batch.len() < batch_size {
+            let (id, level) = match queue.pop_front() {
+                Some(queued) => queued,
+                None => break,
+            };
+            if level >= options.depth {
+                continue;
+            }
+            if requests >= options.max_requests {
+                info!("Stopped crawling at {} requests", requests);
+                graph.truncated = true;
+                break;
+            }
+
+            requests += 1;
+            batch.push((id, level));
+        }
+        if batch.is_empty() {
+            break;
+        }
+
+        let ids = batch.iter().map(|&(id, _)| id).collect::<Vec<_>>();
+        debug!("Crawling citations of {:?}", ids);
+        for (&(id, level), paper) in batch.iter().zip(fetch(&ids)) {
+            let mut paper = paper?;
+            let citers = paper.citers.take().unwrap_or_default();
+            insert_paper(&mut graph.papers, id, paper);
+
+            for citer in citers {
+                let citer_id = match citer.cluster_id {
+                    Some(citer_id) => citer_id,
+                    None => continue,
+                };
+
+                if edges.insert((citer_id, id)) {
+                    graph.edges.push((citer_id, id));
+                }
+                insert_paper(&mut graph.papers, citer_id, citer);
+                if visited.insert(citer_id) {
+                    queue.push_back((citer_id, level + 1));
+                }
+            }
+        }
+    }
+
+    Ok(graph)
+}
+
+/// A citation page fetched for `collect_all_citers()`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CiterPage {
+    /// Papers listed in the page.
+    pub citers: Vec<Paper>,
+    /// `start` offset of the next page, or `None` if this is the last page,
+    /// e.g. by `CitationDocument::next_page_start()`.
+    pub next_start: Option<u32>,
+}
+
+/// Collect papers citing a paper of `cluster_id` from every citation page,
+/// fetching each page from its `start` offset with `fetch`, up to `limit` citers.
+///
+/// Pages are followed until the last one, or until `limit` citers are collected.
+/// Citers listed again in later pages, as Google Scholar may shift results between requests,
+/// are skipped by their cluster IDs.
+/// Google Scholar lists no more than `MAX_REACHABLE_RESULT_COUNT` citers.
+///
+/// Returns the first error of `fetch`.
+///
+/// # Example
+///
+/// ```
+/// use scholar::graph::{collect_all_citers, CiterPage};
+/// use scholar::id::ClusterId;
+/// use scholar::paper::Paper;
+///
+/// let citers = collect_all_citers(ClusterId(1), 100, |_, start| {
+///     Ok(CiterPage {
+///         citers: vec![Paper::new("foo", u64::from(start) + 2)],
+///         next_start: if start < 20 { Some(start + 10) } else { None },
+///     })
+/// }).unwrap();
+/// assert_eq!(citers.len(), 3);
+/// ```
+pub fn collect_all_citers<F>(
+    cluster_id: ClusterId,
+    limit: usize,
+    mut fetch: F,
+) -> Result<Vec<Paper>>
+where
+    F: FnMut(ClusterId, u32) -> Result<CiterPage>,
+{
+    let mut citers: Vec<Paper> = Vec::new();
+    let mut seen = HashSet::new();
+    let mut start = Some(0);
+
+    while let Some(s) = start {
+        if citers.len() >= limit {
+            break;
+        }
+
+        let page = fetch(cluster_id, s)?;
+        for citer in page.citers {
+            let new = match citer.cluster_id {
+                Some(id) => seen.insert(id),
+                None => true,
+            };
+            if new && citers.len() < limit {
+                citers.push(citer);
+            }
+        }
+        // Never go back, which would loop forever
+        start = page.next_start.filter(|&next| next > s);
+    }
+
+    Ok(citers)
+}
+
+/// Co-authorship network of authors, linking authors who write papers together.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CoauthorGraph {
+    /// Names of authors as in `Paper::author_list()`, mapped to the number of their papers.
+    pub authors: BTreeMap<String, u32>,
+    /// Pairs of co-authors mapped to the number of papers they share.
+    /// The first name of each pair sorts before the second.
+    pub edges: BTreeMap<(String, String), u32>,
+}
+
+impl CoauthorGraph {
+    /// Number of papers `a` and `b` share, 0 if they never write together.
+    pub fn shared_papers(&self, a: &str, b: &str) -> u32 {
+        let key = if a <= b { (a, b) } else { (b, a) };
+        self.edges
+            .get(&(key.0.to_owned(), key.1.to_owned()))
+            .cloned()
+            .unwrap_or(0)
+    }
+
+    /// Co-authors of `author` with the number of papers shared, in ascending order of names.
+    pub fn coauthors_of(&self, author: &str) -> Vec<(&str, u32)> {
+        let mut coauthors = self.edges
+            .iter()
+            .filter_map(|((a, b), &weight)| {
+                if a == author {
+                    Some((b.as_str(), weight))
+                } else if b == author {
+                    Some((a.as_str(), weight))
+                } else {
+                    None
+                }
+            })
+            .collect::<Vec<_>>();
+        coauthors.sort();
+        coauthors
+    }
+}
+
+/// Build the co-authorship network of authors of `papers`.
+///
+/// Authors are told apart by their names as shown, e.g. "MV Berry",
+/// so that the same author shown in different forms makes different nodes.
+/// Papers listing one author add the node only, and names repeated in a paper count once.
+/// Truncated author lists link the visible authors only.
+///
+/// # Example
+///
+/// ```
+/// use scholar::graph::coauthor_graph;
+/// use scholar::paper::Paper;
+///
+/// let mut foo = Paper::new("foo", 1);
+/// foo.authors = Some(String::from("Y Aharonov, D Bohm"));
+/// let mut bar = Paper::new("bar", 2);
+/// bar.authors = Some(String::from("D Bohm, Y Aharonov, BJ Hiley"));
+///
+/// let graph = coauthor_graph(&[foo, bar]);
+/// assert_eq!(graph.authors.len(), 3);
+/// assert_eq!(graph.shared_papers("Y Aharonov", "D Bohm"), 2);
+/// assert_eq!(graph.coauthors_of("BJ Hiley"), vec![("D Bohm", 1), ("Y Aharonov", 1)]);
+/// ```
+pub fn coauthor_graph<'a, I>(papers: I) -> CoauthorGraph
+where
+    I: IntoIterator<Item = &'a Paper>,
+{
+    let mut graph = CoauthorGraph::default();
+    for paper in papers {
+        let authors = paper.author_list().into_iter().collect::<BTreeSet<_>>();
+        for (i, a) in authors.iter().enumerate() {
+            *graph.authors.entry(a.clone()).or_insert(0) += 1;
+            for b in authors.iter().skip(i + 1) {
+                *graph.edges.entry((a.clone(), b.clone())).or_insert(0) += 1;
+            }
+        }
+    }
+    graph
+}
+
+fn insert_paper(papers: &mut HashMap<ClusterId, Paper>, id: ClusterId, mut paper: Paper) {
+    paper.citers = None;
+    match papers.get_mut(&id) {
+        Some(known) => {
+            known.merge(&paper);
+        }
+        None => {
+            papers.insert(id, paper);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use scrape::BlockKind;
+
+    /// 1 <- 2 <- 3 <- 1 (a cycle), 2 <- 4, and 1 <- a paper without cluster ID.
+    fn fetch(id: ClusterId, fetched: &mut Vec<ClusterId>) -> Result<Paper> {
+        fetched.push(id);
+
+        let citer_ids: &[u64] = match id.as_u64() {
+            1 => &[2, 0],
+            2 => &[3, 4],
+            3 => &[1],
+            _ => &[],
+        };
+        let mut paper = Paper::new(&format!("paper {}", id), id.as_u64());
+        paper.citers = Some(
+            citer_ids
+                .iter()
+                .map(|&c| {
+                    let mut citer = Paper::new(&format!("paper {}", c), c);
+                    if c == 0 {
+                        citer.set_cluster_id(None);
+                    }
+                    citer
+                })
+                .collect(),
+        );
+        Ok(paper)
+    }
+
+    #[test]
+    fn crawl_citations_test() {
+        let mut fetched = Vec::new();
+        let options = CrawlOptions {
+            depth: 10,
+            max_requests: 10,
+        };
+        let graph = crawl_citations(ClusterId(1), &options, |id| fetch(id, &mut fetched)).unwrap();
+
+        assert_eq!(
+            fetched,
+            vec![ClusterId(1), ClusterId(2), ClusterId(3), ClusterId(4)]
+        );
+        assert_eq!(
+            graph.edges,
+            vec![
+                (ClusterId(2), ClusterId(1)),
+                (ClusterId(3), ClusterId(2)),
+                (ClusterId(4), ClusterId(2)),
+                (ClusterId(1), ClusterId(3)),
+            ]
+        );
+        assert_eq!(graph.papers.len(), 4);
+        assert_eq!(graph.papers[&ClusterId(3)].title, "paper 3");
+        assert!(graph.papers.values().all(|p| p.citers.is_none()));
+        assert_eq!(graph.citers_of(ClusterId(2)), vec![ClusterId(3), ClusterId(4)]);
+        assert!(!graph.truncated);
+    }
+
+    #[test]
+    fn crawl_citations_limits_test() {
+        let mut fetched = Vec::new();
+        let graph =
+            crawl_citations(ClusterId(1), &CrawlOptions::default(), |id| fetch(id, &mut fetched))
+                .unwrap();
+        assert_eq!(fetched, vec![ClusterId(1)]);
+        assert_eq!(graph.edges, vec![(ClusterId(2), ClusterId(1))]);
+        assert_eq!(graph.papers.len(), 2);
+        assert!(!graph.truncated);
+
+        let mut fetched = Vec::new();
+        let options = CrawlOptions {
+            depth: 10,
+            max_requests: 2,
+        };
+        let graph = crawl_citations(ClusterId(1), &options, |id| fetch(id, &mut fetched)).unwrap();
+        assert_eq!(fetched, vec![ClusterId(1), ClusterId(2)]);
+        assert_eq!(graph.papers.len(), 4);
+        assert!(graph.truncated);
+
+        let result = crawl_citations(ClusterId(1), &options, |_| -> Result<Paper> {
+            bail!(ErrorKind::Blocked(BlockKind::Captcha))
+        });
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn crawl_citations_batched_test() {
+        let mut batches = Vec::new();
+        let options = CrawlOptions {
+            depth: 10,
+            max_requests: 10,
+        };
+        let graph = crawl_citations_batched(ClusterId(1), &options, 2, |ids| {
+            batches.push(ids.to_vec());
+            let mut fetched = Vec::new();
+            ids.iter().map(|&id| fetch(id, &mut fetched)).collect()
+        }).unwrap();
+
+        assert_eq!(
+            batches,
+            vec![
+                vec![ClusterId(1)],
+                vec![ClusterId(2)],
+                vec![ClusterId(3), ClusterId(4)],
+            ]
+        );
+        assert_eq!(graph.papers.len(), 4);
+        assert!(!graph.truncated);
+
+        // Same graph as fetched one by one
+        let mut fetched = Vec::new();
+        let serial = crawl_citations(ClusterId(1), &options, |id| fetch(id, &mut fetched)).unwrap();
+        assert_eq!(graph, serial);
+    }
+
+    #[test]
+    fn collect_all_citers_test() {
+        // Pages of 2 citers, where the second page shifts by one
+        let pages = [
+            (0, vec![2, 3], Some(2)),
+            (2, vec![3, 4], Some(4)),
+            (4, vec![0, 5], Some(2)),
+        ];
+        let fetch = |fetched: &mut Vec<u32>, start| {
+            fetched.push(start);
+            let &(_, ref ids, next_start) = pages.iter().find(|p| p.0 == start).unwrap();
+            let citers = ids.iter()
+                .map(|&id| {
+                    let mut paper = Paper::new(&format!("paper {}", id), id);
+                    if id == 0 {
+                        paper.set_cluster_id(None);
+                    }
+                    paper
+                })
+                .collect();
+            Ok(CiterPage { citers, next_start })
+        };
+
+        let mut fetched = Vec::new();
+        let citers = collect_all_citers(ClusterId(1), 10, |id, start| {
+            assert_eq!(id, ClusterId(1));
+            fetch(&mut fetched, start)
+        }).unwrap();
+        assert_eq!(fetched, vec![0, 2, 4]);
+        assert_eq!(
+            citers.iter().map(|p| &p.title[..]).collect::<Vec<_>>(),
+            vec!["paper 2", "paper 3", "paper 4", "paper 0", "paper 5"]
+        );
+
+        let mut fetched = Vec::new();
+        let citers =
+            collect_all_citers(ClusterId(1), 3, |_, start| fetch(&mut fetched, start)).unwrap();
+        assert_eq!(fetched, vec![0, 2]);
+        assert_eq!(citers.len(), 3);
+
+        let result = collect_all_citers(ClusterId(1), 10, |_, start| {
+            if start == 0 {
+                Ok(CiterPage {
+                    citers: vec![Paper::new("foo", 2)],
+                    next_start: Some(10),
+                })
+            } else {
+                Err(ErrorKind::Blocked(BlockKind::Captcha).into())
+            }
+        });
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn coauthor_graph_test() {
+        let paper = |authors: Option<&str>| {
+            let mut paper = Paper::new("foo", 0);
+            paper.authors = authors.map(ToOwned::to_owned);
+            paper
+        };
+        let papers = vec![
+            paper(Some("MV Berry, JP Keating")),
+            paper(Some("JP Keating, MV Berry, MV Berry")),
+            paper(Some("MV Berry")),
+            paper(Some("JP Keating, SD Prado")),
+            paper(None),
+        ];
+
+        let graph = coauthor_graph(&papers);
+        assert_eq!(
+            graph.authors.into_iter().collect::<Vec<_>>(),
+            vec![
+                (String::from("JP Keating"), 3),
+                (String::from("MV Berry"), 3),
+                (String::from("SD Prado"), 1),
+            ]
+        );
+        let graph = coauthor_graph(&papers);
+        assert_eq!(graph.edges.len(), 2);
+        assert_eq!(graph.shared_papers("MV Berry", "JP Keating"), 2);
+        assert_eq!(graph.shared_papers("JP Keating", "MV Berry"), 2);
+        assert_eq!(graph.shared_papers("MV Berry", "SD Prado"), 0);
+        assert_eq!(
+            graph.coauthors_of("JP Keating"),
+            vec![("MV Berry", 2), ("SD Prado", 1)]
+        );
+        assert!(graph.coauthors_of("nobody").is_empty());
+    }
+}
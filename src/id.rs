@@ -0,0 +1,236 @@
+//! `ClusterId` struct, and other identifiers of papers found in URLs.
+
+use std::fmt;
+use std::num::ParseIntError;
+use std::str::FromStr;
+
+use errors::*;
+
+/// ID of a cluster of papers, i.e. versions of the same work,
+/// found in `cluster=` and `cites=` parameters of Google Scholar's URLs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde-derive", derive(Serialize, Deserialize))]
+pub struct ClusterId(pub u64);
+
+/// Parameter in which a `ClusterId` is found.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IdKind {
+    /// `cluster=`, listing versions of the paper.
+    Cluster,
+    /// `cites=`, listing papers citing the paper.
+    Cites,
+}
+
+impl ClusterId {
+    pub fn as_u64(self) -> u64 {
+        self.0
+    }
+
+    /// URL listing versions of the paper.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use scholar::id::ClusterId;
+    ///
+    /// assert_eq!(
+    ///     ClusterId(42).cluster_url(),
+    ///     "https://scholar.google.com/scholar?cluster=42"
+    /// );
+    /// ```
+    pub fn cluster_url(self) -> String {
+        format!("{}?cluster={}", super::GOOGLESCHOLAR_URL_BASE, self.0)
+    }
+
+    /// URL listing papers citing the paper.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use scholar::id::ClusterId;
+    ///
+    /// assert_eq!(
+    ///     ClusterId(42).citations_url(),
+    ///     "https://scholar.google.com/scholar?cites=42"
+    /// );
+    /// ```
+    pub fn citations_url(self) -> String {
+        format!("{}?cites={}", super::GOOGLESCHOLAR_URL_BASE, self.0)
+    }
+}
+
+impl From<u64> for ClusterId {
+    fn from(id: u64) -> Self {
+        ClusterId(id)
+    }
+}
+
+impl fmt::Display for ClusterId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl FromStr for ClusterId {
+    type Err = ParseIntError;
+
+    fn from_str(s: &str) -> ::std::result::Result<Self, Self::Err> {
+        s.parse().map(ClusterId)
+    }
+}
+
+/// Parse the first `cluster=` or `cites=` parameter in `url`,
+/// returning which of them is found.
+///
+/// # Example
+///
+/// ```
+/// use scholar::id::{parse_id_from_url, ClusterId, IdKind};
+///
+/// assert_eq!(
+///     parse_id_from_url("/scholar?cites=42&hl=en").unwrap(),
+///     (IdKind::Cites, ClusterId(42))
+/// );
+/// assert!(parse_id_from_url("/scholar?q=foo").is_err());
+/// ```
+pub fn parse_id_from_url(url: &str) -> Result<(IdKind, ClusterId)> {
+    use regex::Regex;
+
+    lazy_static! {
+        static ref RE: Regex = Regex::new(r"(cluster|cites)=(\d+)").unwrap();
+    }
+
+    let caps = RE.captures(url)
+        .ok_or_else(|| ErrorKind::bad_html("ID URL", url))?;
+    let kind = match caps.get(1).map(|k| k.as_str()) {
+        Some("cluster") => IdKind::Cluster,
+        _ => IdKind::Cites,
+    };
+    let id = caps.get(2)
+        .ok_or_else(|| ErrorKind::bad_html("ID URL", url))?
+        .as_str()
+        .parse()?;
+
+    Ok((kind, id))
+}
+
+/// Parse a DOI in `url`, e.g. "10.1103/PhysRevLett.13.508"
+/// for `https://journals.aps.org/prl/abstract/10.1103/PhysRevLett.13.508`.
+///
+/// Percent-encoded DOIs are decoded, and trailing path segments publishers commonly append
+/// (e.g. `/full`, `/pdf`) are dropped.
+///
+/// # Example
+///
+/// ```
+/// use scholar::id::parse_doi;
+///
+/// assert_eq!(
+///     parse_doi("https://doi.org/10.1098%2Frspa.1984.0023").unwrap(),
+///     "10.1098/rspa.1984.0023"
+/// );
+/// assert_eq!(parse_doi("https://example.com/paper.pdf"), None);
+/// ```
+pub fn parse_doi(url: &str) -> Option<String> {
+    use regex::Regex;
+    use url::percent_encoding::percent_decode;
+
+    const SUFFIXES: &[&str] = &["/abstract", "/full", "/pdf", "/epdf", ".pdf"];
+
+    lazy_static! {
+        static ref RE: Regex = Regex::new(r"10\.\d{4,9}/[^\s?#&]+").unwrap();
+    }
+
+    let url = percent_decode(url.as_bytes()).decode_utf8_lossy();
+    let mut doi = RE.find(&url)?.as_str();
+    while let Some(suffix) = SUFFIXES.iter().find(|s| doi.ends_with(*s)) {
+        doi = &doi[..doi.len() - suffix.len()];
+    }
+    Some(doi.to_owned())
+}
+
+/// Parse an arXiv identifier in a URL of `arxiv.org`, without the version,
+/// e.g. "2103.12345" for `https://arxiv.org/pdf/2103.12345v2.pdf`,
+/// or "quant-ph/9508027" in the old scheme.
+///
+/// # Example
+///
+/// ```
+/// use scholar::id::parse_arxiv_id;
+///
+/// assert_eq!(
+///     parse_arxiv_id("https://arxiv.org/abs/2103.12345v2").unwrap(),
+///     "2103.12345"
+/// );
+/// assert_eq!(parse_arxiv_id("https://example.com/abs/2103.12345"), None);
+/// ```
+pub fn parse_arxiv_id(url: &str) -> Option<String> {
+    use regex::Regex;
+
+    lazy_static! {
+        static ref RE: Regex = Regex::new(
+            r"arxiv\.org/(?:abs|pdf)/(\d{4}\.\d{4,5}|[a-z-]+(?:\.[A-Z]{2})?/\d{7})(?:v\d+)?"
+        ).unwrap();
+    }
+
+    RE.captures(url)
+        .and_then(|caps| caps.get(1))
+        .map(|id| id.as_str().to_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_id_from_url_test() {
+        assert_eq!(
+            parse_id_from_url("/scholar?cluster=222222&foo=bar").unwrap(),
+            (IdKind::Cluster, ClusterId(222222))
+        );
+        assert_eq!(
+            parse_id_from_url("/scholar?oi=bibs&cites=111,222").unwrap(),
+            (IdKind::Cites, ClusterId(111))
+        );
+        assert!(parse_id_from_url("/scholar?cluster=aaaaaa").is_err());
+    }
+
+    #[test]
+    fn parse_doi_test() {
+        let doi = |url| parse_doi(url).unwrap();
+        assert_eq!(
+            doi("https://journals.aps.org/prl/abstract/10.1103/PhysRevLett.13.508"),
+            "10.1103/PhysRevLett.13.508"
+        );
+        assert_eq!(
+            doi("https://onlinelibrary.wiley.com/doi/pdf/10.1002/andp.19053220607/full"),
+            "10.1002/andp.19053220607"
+        );
+        assert_eq!(
+            doi("https://link.springer.com/content/pdf/10.1007/BF01344458.pdf"),
+            "10.1007/BF01344458"
+        );
+        assert_eq!(
+            doi("https://www.nature.com/articles/10.1038/nature12373?foo=bar"),
+            "10.1038/nature12373"
+        );
+        assert_eq!(parse_doi("http://rspa.royalsocietypublishing.org/content/392/1802/45"), None);
+    }
+
+    #[test]
+    fn parse_arxiv_id_test() {
+        let id = |url| parse_arxiv_id(url).unwrap();
+        assert_eq!(id("https://arxiv.org/pdf/2103.12345v2.pdf"), "2103.12345");
+        assert_eq!(id("http://arxiv.org/abs/0706.1234"), "0706.1234");
+        assert_eq!(id("https://arxiv.org/abs/quant-ph/9508027v2"), "quant-ph/9508027");
+        assert_eq!(id("https://arxiv.org/abs/math.GT/0309136"), "math.GT/0309136");
+        assert_eq!(parse_arxiv_id("https://arxiv.org/list/quant-ph/new"), None);
+    }
+
+    #[test]
+    fn from_str_test() {
+        assert_eq!("42".parse::<ClusterId>().unwrap(), ClusterId(42));
+        assert!("-1".parse::<ClusterId>().is_err());
+        assert_eq!(ClusterId(42).to_string(), "42");
+    }
+}
@@ -1,21 +1,76 @@
 //! Google Scholar scraper.
 
+// `json!` of `Paper` with all of its fields needs more than the default
+#![recursion_limit = "256"]
+
 #[macro_use]
 extern crate error_chain;
+#[cfg(feature = "gzip")]
+extern crate flate2;
 #[macro_use]
 extern crate lazy_static;
+#[macro_use]
+extern crate log;
 extern crate regex;
+#[cfg(feature = "client")]
 extern crate reqwest;
+#[cfg(feature = "sqlite")]
+extern crate rusqlite;
 extern crate select;
+#[cfg(feature = "serde-derive")]
 extern crate serde;
+#[cfg(feature = "serde-derive")]
 #[macro_use]
 extern crate serde_derive;
+#[cfg(feature = "serde_json")]
+#[macro_use]
+extern crate serde_json;
+extern crate unicode_normalization;
+extern crate url;
 
+pub mod batch;
+#[cfg(feature = "fs")]
+pub mod cache;
+pub mod case_law;
+pub mod cite;
+#[cfg(feature = "client")]
+pub mod client;
+#[cfg(feature = "client")]
+pub mod cookie;
+#[cfg(feature = "crossref")]
+pub mod crossref;
+pub mod dedup;
 pub mod errors;
+pub mod export;
+pub mod graph;
+pub mod id;
+pub mod library;
+pub mod matcher;
+pub mod metrics;
+pub mod observer;
 pub mod paper;
+pub mod profile;
 pub mod request;
+pub mod robots;
+pub mod saved_search;
 pub mod scrape;
+pub mod selector;
+#[cfg(feature = "semantic_scholar")]
+pub mod semantic_scholar;
+pub mod settings;
+#[cfg(feature = "fs")]
+pub mod snapshot;
+pub mod source;
+pub mod stats;
+#[cfg(feature = "sqlite")]
+pub mod storage;
+pub mod text;
+pub mod util;
+pub mod watch;
 
 const GOOGLESCHOLAR_URL_BASE: &str = "https://scholar.google.com/scholar";
 
 pub const MAX_RESULT_COUNT: u32 = 10;
+
+/// Google Scholar does not show results beyond this number for a query.
+pub const MAX_REACHABLE_RESULT_COUNT: u32 = 1000;
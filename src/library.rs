@@ -0,0 +1,27 @@
+//! `LibraryArticle` and `LibraryLabel` structs.
+
+use paper::Paper;
+
+/// An article saved to "My library" of a signed-in user.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-derive", derive(Serialize))]
+pub struct LibraryArticle {
+    /// Saved paper, scraped as in search results.
+    pub paper: Paper,
+    /// ID of the article in the library, used by Google Scholar to edit its labels.
+    pub library_id: Option<String>,
+    /// Names of labels attached to the article by the user.
+    pub labels: Vec<String>,
+}
+
+/// A label the user organizes the library with, listed beside saved articles.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-derive", derive(Serialize))]
+pub struct LibraryLabel {
+    /// Name of the label, e.g. "Thesis".
+    pub name: String,
+    /// Number of articles with the label, if shown.
+    pub count: Option<u32>,
+    /// URL of the library page listing articles with the label, as written in the page.
+    pub url: String,
+}
@@ -0,0 +1,123 @@
+//! Find papers matching free-text titles, e.g. of a reference list.
+
+use dedup::title_similarity;
+use paper::Paper;
+use text::normalize_for_matching;
+
+/// Lowest `title_score()` for a paper found by title to be taken as the paper,
+/// e.g. when looking a paper of Google Scholar up in another service.
+pub const TITLE_MATCH_THRESHOLD: f64 = 0.9;
+
+/// A paper best matching a title, with how confident the match is.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TitleMatch {
+    pub paper: Paper,
+    /// Score in `[0, 1]` given by `title_score()`; 1 for the same title.
+    pub confidence: f64,
+}
+
+/// Score how well `title` of a paper matches `query`, in `[0, 1]`.
+///
+/// This is the mean of the similarity by the edit distance of the normalized titles,
+/// which tolerates typos, and the overlap of their words (see `dedup::title_similarity()`),
+/// which tolerates reordered or dropped words.
+///
+/// # Example
+///
+/// ```
+/// use scholar::matcher::title_score;
+///
+/// let query = "Quantal phase factors accompanying adiabatic changes";
+/// assert_eq!(title_score(query, "Quantal Phase Factors Accompanying Adiabatic Changes."), 1.0);
+/// assert!(title_score(query, "Quantal phase factors acompanying adiabatic changes") > 0.8);
+/// assert!(title_score(query, "Berry phase effects on electronic properties") < 0.3);
+/// ```
+pub fn title_score(query: &str, title: &str) -> f64 {
+    let query_normalized = normalize_for_matching(query);
+    let title_normalized = normalize_for_matching(title);
+    let edit = edit_similarity(&query_normalized, &title_normalized);
+    (edit + title_similarity(query, title)) / 2.0
+}
+
+/// The paper among `papers` whose title best matches `query`, scored by `title_score()`.
+///
+/// The first one wins a tie, so that higher-ranked results are preferred.
+/// Returns `None` if `papers` is empty.
+/// `Client::resolve_title()` searches for `query` and passes the results here.
+pub fn best_match(query: &str, papers: &[Paper]) -> Option<TitleMatch> {
+    let mut best: Option<TitleMatch> = None;
+    for paper in papers {
+        let confidence = title_score(query, &paper.title);
+        if best.as_ref().map_or(true, |b| confidence > b.confidence) {
+            best = Some(TitleMatch {
+                paper: paper.clone(),
+                confidence,
+            });
+        }
+    }
+    best
+}
+
+/// `1 - (Levenshtein distance / length of the longer)`, counted in characters.
+fn edit_similarity(a: &str, b: &str) -> f64 {
+    let a = a.chars().collect::<Vec<_>>();
+    let b = b.chars().collect::<Vec<_>>();
+    let longer = a.len().max(b.len());
+    if longer == 0 {
+        return 1.0;
+    }
+    1.0 - levenshtein(&a, &b) as f64 / longer as f64
+}
+
+fn levenshtein(a: &[char], b: &[char]) -> usize {
+    // Distances from a prefix of `a` to each prefix of `b`, one row at a time
+    let mut row = (0..b.len() + 1).collect::<Vec<_>>();
+    for (i, ca) in a.iter().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = diagonal + if ca == cb { 0 } else { 1 };
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+    row[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn levenshtein_test() {
+        let distance = |a: &str, b: &str| {
+            levenshtein(
+                &a.chars().collect::<Vec<_>>(),
+                &b.chars().collect::<Vec<_>>(),
+            )
+        };
+        assert_eq!(distance("", ""), 0);
+        assert_eq!(distance("kitten", "sitting"), 3);
+        assert_eq!(distance("flaw", "lawn"), 2);
+        assert_eq!(distance("schrodinger", ""), 11);
+    }
+
+    #[test]
+    fn best_match_test() {
+        let papers = vec![
+            Paper::new("Berry phase effects on electronic properties", 1),
+            Paper::new("Quantal phase factors accompanying adiabatic changes", 2),
+            Paper::new("Quantal phase factors accompanying adiabatic changes", 3),
+        ];
+
+        let best = best_match("M. V. Berry, Quantal phase factors, 1984", &papers);
+        let best = best.unwrap();
+        assert_eq!(best.paper.cluster_id, papers[1].cluster_id);
+        assert!(best.confidence > 0.0 && best.confidence < 1.0);
+
+        let best = best_match("quantal phase factors accompanying adiabatic changes", &papers);
+        assert_eq!(best.unwrap().confidence, 1.0);
+
+        assert_eq!(best_match("foo", &[]), None);
+    }
+}
@@ -0,0 +1,142 @@
+//! `VenueMetrics` struct, scraped from a Google Scholar Metrics page,
+//! and citation metrics of authors computed from their papers.
+
+use paper::Paper;
+
+/// A publication venue ranked in the top publications page of Google Scholar Metrics.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-derive", derive(Serialize))]
+pub struct VenueMetrics {
+    /// Rank in the list, starting from 1.
+    pub rank: u32,
+    /// Name of the venue, e.g. "Physical Review Letters".
+    pub name: String,
+    /// h-index of articles published in the last five complete years.
+    pub h5_index: u32,
+    /// Median of citations of the articles in the h5-core.
+    pub h5_median: u32,
+    /// Absolute URL of the page listing the articles in the h5-core.
+    pub h5_core_url: Option<String>,
+    /// Category of the list, e.g. "Physics & Mathematics". `None` for the overall list.
+    pub category: Option<String>,
+    /// Subcategory of the list, e.g. "Quantum Physics".
+    pub subcategory: Option<String>,
+}
+
+/// Citation metrics of an author computed from papers, e.g. articles of the profile.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-derive", derive(Serialize))]
+pub struct AuthorMetrics {
+    /// Sum of citation counts.
+    pub citations: u64,
+    pub h_index: u32,
+    pub g_index: u32,
+    pub i10_index: u32,
+}
+
+/// Compute citation metrics of an author from their `papers`. See `h_index()` etc.
+pub fn author_metrics(papers: &[Paper]) -> AuthorMetrics {
+    AuthorMetrics {
+        citations: papers.iter().map(|p| u64::from(citations(p))).sum(),
+        h_index: h_index(papers),
+        g_index: g_index(papers),
+        i10_index: i10_index(papers),
+    }
+}
+
+/// The largest `h` such that `h` of `papers` are cited at least `h` times each.
+///
+/// Papers without citation count are taken as not cited,
+/// and approximate counts are taken as is, in all of the metrics.
+///
+/// # Example
+///
+/// ```
+/// use scholar::metrics::h_index;
+/// use scholar::paper::Paper;
+///
+/// let papers = [10, 8, 5, 4, 3]
+///     .iter()
+///     .map(|&count| {
+///         let mut paper = Paper::new("foo", 0);
+///         paper.citation_count = Some(count.into());
+///         paper
+///     })
+///     .collect::<Vec<_>>();
+/// assert_eq!(h_index(&papers), 4);
+/// ```
+pub fn h_index(papers: &[Paper]) -> u32 {
+    sorted_citations(papers)
+        .iter()
+        .zip(1..)
+        .take_while(|&(&c, rank)| c >= rank)
+        .count() as u32
+}
+
+/// The largest `g` such that the `g` most cited of `papers` are cited at least `g * g` times
+/// in total. `g` never exceeds the number of papers.
+pub fn g_index(papers: &[Paper]) -> u32 {
+    let mut total = 0;
+    let mut g = 0;
+    for (c, rank) in sorted_citations(papers).into_iter().zip(1u64..) {
+        total += u64::from(c);
+        if total >= rank * rank {
+            g = rank as u32;
+        }
+    }
+    g
+}
+
+/// Number of `papers` cited at least 10 times each.
+pub fn i10_index(papers: &[Paper]) -> u32 {
+    papers.iter().filter(|p| citations(p) >= 10).count() as u32
+}
+
+fn citations(paper: &Paper) -> u32 {
+    paper.citation_count.map_or(0, |c| c.value)
+}
+
+/// Citation counts of `papers` in descending order.
+fn sorted_citations(papers: &[Paper]) -> Vec<u32> {
+    let mut counts = papers.iter().map(citations).collect::<Vec<_>>();
+    counts.sort_by(|a, b| b.cmp(a));
+    counts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn papers(counts: &[Option<u32>]) -> Vec<Paper> {
+        counts
+            .iter()
+            .map(|&count| {
+                let mut paper = Paper::new("foo", 0);
+                paper.citation_count = count.map(Into::into);
+                paper
+            })
+            .collect()
+    }
+
+    #[test]
+    fn author_metrics_test() {
+        let papers = papers(&[Some(3), None, Some(25), Some(10), Some(0), Some(12), Some(4)]);
+        assert_eq!(
+            author_metrics(&papers),
+            AuthorMetrics {
+                citations: 54,
+                h_index: 4,
+                g_index: 7,
+                i10_index: 3,
+            }
+        );
+
+        // g-index is bounded by the number of papers
+        let papers = self::papers(&[Some(100), Some(1)]);
+        assert_eq!(h_index(&papers), 1);
+        assert_eq!(g_index(&papers), 2);
+
+        assert_eq!(author_metrics(&[]), AuthorMetrics::default());
+        assert_eq!(h_index(&self::papers(&[None, Some(0)])), 0);
+    }
+}
@@ -0,0 +1,48 @@
+//! Hooks to observe progress of long crawls, e.g. to drive progress bars and logging.
+
+use std::time::Duration;
+
+use paper::Paper;
+use errors::*;
+
+/// Callbacks invoked by `Client` as it fetches pages and crawls citations.
+///
+/// Every method does nothing by default, so implement only those of interest.
+/// Since a client may fetch pages on several threads (see `Client::citations_parallel()`),
+/// observers are shared across threads.
+///
+/// # Example
+///
+/// ```
+/// use std::sync::atomic::{AtomicUsize, Ordering};
+/// use scholar::observer::Observer;
+///
+/// #[derive(Default)]
+/// struct PageCounter(AtomicUsize);
+///
+/// impl Observer for PageCounter {
+///     fn on_page_fetched(&self, url: &str, cached: bool) {
+///         let count = self.0.fetch_add(1, Ordering::Relaxed) + 1;
+///         println!("#{} {}{}", count, url, if cached { " (cached)" } else { "" });
+///     }
+/// }
+///
+/// let counter = PageCounter::default();
+/// counter.on_page_fetched("https://scholar.google.com/scholar?q=foo", false);
+/// assert_eq!(counter.0.load(Ordering::Relaxed), 1);
+/// ```
+pub trait Observer: Send + Sync {
+    /// A page of `url` is fetched, or found in the page cache if `cached`.
+    fn on_page_fetched(&self, _url: &str, _cached: bool) {}
+
+    /// A paper is scraped from a citation page while crawling,
+    /// either the cited paper or one of its citers.
+    fn on_paper_scraped(&self, _paper: &Paper) {}
+
+    /// Fetching `url` failed with `error` at the `attempt`-th attempt,
+    /// and will be retried after `delay`.
+    fn on_retry(&self, _url: &str, _attempt: u32, _delay: Duration, _error: &Error) {}
+
+    /// Google Scholar blocked the request to `url`.
+    fn on_blocked(&self, _url: &str) {}
+}
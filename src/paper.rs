@@ -2,20 +2,187 @@
 
 use std::fmt;
 use std::borrow::Cow;
+use std::cmp::Ordering;
+use std::hash::{Hash, Hasher};
 
-#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+use id::ClusterId;
+use scrape::parse_related_from_url;
+use text::normalize_for_matching;
+
+#[cfg(feature = "serde_json")]
+use serde_json::Value;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-derive", derive(Serialize, Deserialize))]
 pub struct Paper {
     pub title: String,
+    /// Authors as listed by Google Scholar.
+    /// The list may be truncated; see `authors_truncated`.
+    pub authors: Option<String>,
+    /// Whether Google Scholar truncated the author list, e.g. "A Foo, B Bar…" or "+3 more".
+    /// The truncation marker is removed from `authors`.
+    pub authors_truncated: bool,
+    /// Journal, conference, etc. in which the paper was published.
+    pub venue: Option<String>,
     /// Link to PDF, HTML, etc.
     pub link: Option<String>,
+    /// Link to full text shown beside the result, e.g. "[PDF] arxiv.org".
+    pub pdf_link: Option<String>,
     /// Published year.
     pub year: Option<u32>,
+    /// Snippet of the abstract or the body shown below the byline.
+    /// Highlights are stripped unless `TextCleaner::strip_highlights` is disabled.
+    pub snippet: Option<String>,
     /// Cluster ID of paper.
-    pub cluster_id: u64,
-    pub citation_count: Option<u32>,
+    ///
+    /// This is taken from whichever of "Cited by" (`cites=`) or "All N versions" (`cluster=`)
+    /// links is found first. Use `work_id()` to get the ID of the work.
+    /// `None` if neither link is shown, e.g. for a new paper with a single version.
+    pub cluster_id: Option<ClusterId>,
+    /// `None` if "Cited by" link is not shown, e.g. for a paper not cited yet.
+    pub citation_count: Option<CitationCount>,
     pub citers: Option<Vec<Paper>>,
     /// URL of citation list page of Google Scholar.
-    pub citation_url: String,
+    /// `None` if `cluster_id` is `None`.
+    pub citation_url: Option<String>,
+    /// Cluster ID found in "All N versions" (`cluster=`) link.
+    pub versions_cluster_id: Option<ClusterId>,
+    /// Number of versions shown in "All N versions" link.
+    pub versions_count: Option<u32>,
+    /// Absolute URL of "All N versions" link, listing the versions.
+    pub versions_url: Option<String>,
+    /// URL of "Related articles" (`q=related:`) link as written in the page.
+    pub related_url: Option<String>,
+    /// ID used to fetch formatted citations, e.g. BibTeX,
+    /// via `/scholar?q=info:ID:scholar.google.com/&output=cite`.
+    pub info_id: Option<String>,
+    /// ID of the article in an author profile, e.g. "qc6CJjYAAAAJ:u5HHmVD_uO8C",
+    /// found in `citation_for_view=` link. Set only for papers scraped from profile pages.
+    pub profile_article_id: Option<String>,
+    /// DOI found in the title link or the full text link, e.g. "10.1103/PhysRevLett.13.508".
+    /// See `id::parse_doi()`.
+    pub doi: Option<String>,
+    /// arXiv identifier without the version found in the title link or the full text link,
+    /// e.g. "2103.12345". See `id::parse_arxiv_id()`.
+    pub arxiv_id: Option<String>,
+    /// Publisher of the paper, e.g. "American Physical Society".
+    /// Not shown by Google Scholar; filled by `crossref::CrossrefWork::enrich()`.
+    pub publisher: Option<String>,
+    /// ISSN of the venue, filled by `crossref::CrossrefWork::enrich()`.
+    pub issn: Option<String>,
+    /// Number of references the paper makes, filled by `crossref::CrossrefWork::enrich()`.
+    pub references_count: Option<u32>,
+    /// Editorial type badge shown with the result, e.g. "Review article".
+    pub article_type: Option<String>,
+    /// Type of the document labeled before the title, e.g. "[BOOK]".
+    pub doc_type: Option<DocType>,
+    /// Position of the result assigned by Google Scholar, counted from 0 across pages.
+    pub result_position: Option<u32>,
+    /// Days since Google Scholar added the paper, e.g. `Some(3)` for "3 days ago".
+    /// Shown only in results sorted by date.
+    pub age_days: Option<u32>,
+    /// Raw HTML of the result block from which this paper was scraped.
+    /// Kept only if `ScrapeOptions::keep_raw_html` is set.
+    pub raw_html: Option<String>,
+    /// Availability of full text.
+    pub access: Access,
+    /// Token attached to the "Save" button, used to save the paper to the user's library.
+    /// Shown only on pages fetched while signed in.
+    pub save_token: Option<String>,
+}
+
+/// Number of citations shown by Google Scholar.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde-derive", derive(Serialize, Deserialize))]
+pub struct CitationCount {
+    pub value: u32,
+    /// Whether the count is shown as an estimate, e.g. "about 1,000".
+    pub approximate: bool,
+}
+
+impl From<u32> for CitationCount {
+    /// Create an exact count.
+    fn from(value: u32) -> Self {
+        Self {
+            value,
+            approximate: false,
+        }
+    }
+}
+
+impl fmt::Display for CitationCount {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.approximate {
+            write!(f, "about {}", self.value)
+        } else {
+            write!(f, "{}", self.value)
+        }
+    }
+}
+
+/// Availability of the full text of a paper, judged from links shown with the result.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-derive", derive(Serialize, Deserialize))]
+pub enum Access {
+    /// Full text is linked, e.g. "[PDF] arxiv.org".
+    FullText,
+    /// Only library resolver links are shown, e.g. "Find it@My Library".
+    Resolver,
+    /// Google Scholar explicitly states "No full text available".
+    ExplicitlyUnavailable,
+    /// No indicator is shown.
+    Unknown,
+}
+
+impl Default for Access {
+    fn default() -> Self {
+        Access::Unknown
+    }
+}
+
+/// Type of a document, labeled before the title like "[PDF]".
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-derive", derive(Serialize, Deserialize))]
+pub enum DocType {
+    Pdf,
+    Html,
+    Book,
+    /// Entry known only from citations in other papers, often without cluster ID.
+    Citation,
+    /// Other label without brackets, e.g. "DOC".
+    Other(String),
+}
+
+impl DocType {
+    /// Parse a label like "[BOOK]", or its abbreviation like "[B]".
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use scholar::paper::DocType;
+    ///
+    /// assert_eq!(DocType::from_label("[BOOK]"), Some(DocType::Book));
+    /// assert_eq!(DocType::from_label("[C]"), Some(DocType::Citation));
+    /// assert_eq!(DocType::from_label("[DOC]"), Some(DocType::Other(String::from("DOC"))));
+    /// assert_eq!(DocType::from_label("BOOK"), None);
+    /// ```
+    pub fn from_label(label: &str) -> Option<Self> {
+        let label = label.trim();
+        if !(label.starts_with('[') && label.ends_with(']')) {
+            return None;
+        }
+
+        let label = label[1..label.len() - 1].trim();
+        let doc_type = match label.to_uppercase().as_str() {
+            "" => return None,
+            "PDF" => DocType::Pdf,
+            "HTML" => DocType::Html,
+            "BOOK" | "B" => DocType::Book,
+            "CITATION" | "C" => DocType::Citation,
+            _ => DocType::Other(label.to_owned()),
+        };
+        Some(doc_type)
+    }
 }
 
 impl fmt::Display for Paper {
@@ -31,14 +198,72 @@ Citation count: {}
             self.title,
             option_na(&self.link),
             option_na(&self.year),
-            self.cluster_id,
-            option_na(&self.citation_count.map(|u| u.to_string())),
-            self.citation_url,
+            option_na(&self.cluster_id),
+            option_na(&self.citation_count),
+            option_na(&self.citation_url),
         )
     }
 }
 
-fn option_na<T: ToString>(c: &Option<T>) -> Cow<'static, str> {
+impl Hash for Paper {
+    /// Hash by `cluster_id` only, so that papers are cheap to hash as keys of maps.
+    /// Equal papers share the cluster ID, as `Eq` requires.
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.cluster_id.hash(state);
+    }
+}
+
+/// One-line citation of a paper, returned by `Paper::display_citation()`.
+#[derive(Clone, Copy, Debug)]
+pub struct PaperCitation<'a>(&'a Paper);
+
+impl<'a> fmt::Display for PaperCitation<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let paper = self.0;
+        if let Some(ref authors) = paper.authors {
+            write!(f, "{}", authors)?;
+            if paper.authors_truncated {
+                write!(f, " et al.")?;
+            }
+            write!(f, " ")?;
+        }
+        if let Some(year) = paper.year {
+            write!(f, "({}). ", year)?;
+        }
+        write!(f, "{}.", without_period(&paper.title))?;
+        if let Some(ref venue) = paper.venue {
+            write!(f, " {}.", without_period(venue))?;
+        }
+        Ok(())
+    }
+}
+
+/// `text` without a trailing period, not to be doubled by the one following it.
+fn without_period(text: &str) -> &str {
+    let text = text.trim();
+    if text.ends_with('.') {
+        text.split_at(text.len() - 1).0
+    } else {
+        text
+    }
+}
+
+fn bibtex_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' | '%' | '$' | '#' | '_' | '{' | '}' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '\\' => escaped.push_str("\\textbackslash{}"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+pub(crate) fn option_na<T: ToString>(c: &Option<T>) -> Cow<'static, str> {
     match *c {
         Some(ref c) => c.to_string().into(),
         None => "N/A".into(),
@@ -48,42 +273,1042 @@ fn option_na<T: ToString>(c: &Option<T>) -> Cow<'static, str> {
 impl Paper {
     /// Create new `Paper` with specified `title` and `cluster_id`.
     /// `citation_url` is set according to `cluster_id`.
-    /// `citation_count` and `citers` are left `None`.
+    /// Other optional fields are left `None`, `authors_truncated` is `false`,
+    /// and `access` is `Access::Unknown`.
     ///
     /// # Example
     ///
     /// ```
-    /// use scholar::paper::Paper;
+    /// use scholar::id::ClusterId;
+    /// use scholar::paper::{Access, Paper};
     ///
     /// let paper = Paper::new("foo", 42);
     /// assert_eq!(
     ///     paper,
     ///     Paper {
     ///         title: String::from("foo"),
+    ///         authors: None,
+    ///         authors_truncated: false,
+    ///         venue: None,
     ///         link: None,
+    ///         pdf_link: None,
     ///         year: None,
-    ///         cluster_id: 42,
+    ///         snippet: None,
+    ///         cluster_id: Some(ClusterId(42)),
     ///         citation_count: None,
     ///         citers: None,
-    ///         citation_url: format!("https://scholar.google.com/scholar?cites={}", 42),
+    ///         citation_url: Some(format!("https://scholar.google.com/scholar?cites={}", 42)),
+    ///         versions_cluster_id: None,
+    ///         versions_count: None,
+    ///         versions_url: None,
+    ///         related_url: None,
+    ///         info_id: None,
+    ///         profile_article_id: None,
+    ///         doi: None,
+    ///         arxiv_id: None,
+    ///         publisher: None,
+    ///         issn: None,
+    ///         references_count: None,
+    ///         article_type: None,
+    ///         doc_type: None,
+    ///         result_position: None,
+    ///         age_days: None,
+    ///         raw_html: None,
+    ///         access: Access::Unknown,
+    ///         save_token: None,
     ///     });
     /// ```
     pub fn new(title: &str, cluster_id: u64) -> Self {
         let title = title.to_owned();
-        let citation_url = Self::cluster_id_to_citation_url(cluster_id);
+        let cluster_id = ClusterId(cluster_id);
+        let citation_url = Some(cluster_id.citations_url());
 
         Self {
             title,
+            authors: None,
+            authors_truncated: false,
+            venue: None,
             link: None,
+            pdf_link: None,
             year: None,
-            cluster_id,
+            snippet: None,
+            cluster_id: Some(cluster_id),
             citation_count: None,
             citers: None,
             citation_url,
+            versions_cluster_id: None,
+            versions_count: None,
+            versions_url: None,
+            related_url: None,
+            info_id: None,
+            profile_article_id: None,
+            doi: None,
+            arxiv_id: None,
+            publisher: None,
+            issn: None,
+            references_count: None,
+            article_type: None,
+            doc_type: None,
+            result_position: None,
+            age_days: None,
+            raw_html: None,
+            access: Access::Unknown,
+            save_token: None,
+        }
+    }
+
+    /// Set `cluster_id`, and `citation_url` accordingly.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use scholar::id::ClusterId;
+    /// use scholar::paper::Paper;
+    ///
+    /// let mut paper = Paper::new("foo", 42);
+    /// paper.set_cluster_id(None);
+    /// assert_eq!(paper.cluster_id, None);
+    /// assert_eq!(paper.citation_url, None);
+    ///
+    /// paper.set_cluster_id(Some(ClusterId(43)));
+    /// assert_eq!(
+    ///     paper.citation_url,
+    ///     Some(String::from("https://scholar.google.com/scholar?cites=43"))
+    /// );
+    /// ```
+    pub fn set_cluster_id(&mut self, cluster_id: Option<ClusterId>) {
+        self.cluster_id = cluster_id;
+        self.citation_url = cluster_id.map(ClusterId::citations_url);
+    }
+
+    /// Start building a paper of `title`, without cluster ID. See `PaperBuilder`.
+    pub fn builder(title: &str) -> PaperBuilder {
+        PaperBuilder::new(title)
+    }
+
+    /// Human-readable citation in one line, e.g.
+    /// "MV Berry (1984). Quantal phase factors accompanying adiabatic changes. Proc. R. Soc. Lond. A."
+    ///
+    /// Unknown authors, year, and venue are omitted.
+    /// Formatting the paper itself with `Display` shows the details in multiple lines instead.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use scholar::paper::Paper;
+    ///
+    /// let paper = Paper::builder("Quantum field theory")
+    ///     .authors("M Srednicki")
+    ///     .year(2007)
+    ///     .build();
+    /// assert_eq!(
+    ///     paper.display_citation().to_string(),
+    ///     "M Srednicki (2007). Quantum field theory."
+    /// );
+    /// ```
+    pub fn display_citation<'a>(&'a self) -> PaperCitation<'a> {
+        PaperCitation(self)
+    }
+
+    /// Number of citations, 0 if "Cited by" link is not shown.
+    pub fn citations(&self) -> u32 {
+        self.citation_count.map_or(0, |c| c.value)
+    }
+
+    /// Name of the first author, if any. See `author_list()`.
+    pub fn first_author(&self) -> Option<String> {
+        self.author_list().into_iter().nth(0)
+    }
+
+    /// Whether full text is linked, by `access` or `pdf_link`.
+    pub fn has_full_text(&self) -> bool {
+        self.access == Access::FullText || self.pdf_link.is_some()
+    }
+
+    /// Order papers by `cluster_id`, putting papers without one last,
+    /// e.g. for `papers.sort_by(Paper::cmp_by_cluster_id)`.
+    ///
+    /// `Paper` does not implement `Ord` itself,
+    /// since papers sharing a cluster ID may still differ in the other fields.
+    pub fn cmp_by_cluster_id(&self, other: &Paper) -> Ordering {
+        match (self.cluster_id, other.cluster_id) {
+            (Some(id), Some(other_id)) => id.cmp(&other_id),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        }
+    }
+
+    /// Split `authors` into names of each author.
+    ///
+    /// Returns an empty `Vec` if `authors` is `None`.
+    /// A truncated list yields only the visible names (see `authors_truncated`).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use scholar::paper::Paper;
+    ///
+    /// let mut paper = Paper::new("foo", 42);
+    /// assert!(paper.author_list().is_empty());
+    ///
+    /// paper.authors = Some(String::from("Y Aharonov, D Bohm"));
+    /// assert_eq!(paper.author_list(), vec!["Y Aharonov", "D Bohm"]);
+    /// ```
+    pub fn author_list(&self) -> Vec<String> {
+        match self.authors {
+            Some(ref authors) => authors
+                .split(',')
+                .map(str::trim)
+                .filter(|a| !a.is_empty())
+                .map(ToOwned::to_owned)
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Whether the title matches `query`, ignoring case, diacritics, punctuation,
+    /// and differences of whitespaces. See `text::normalize_for_matching()`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use scholar::paper::Paper;
+    ///
+    /// let paper = Paper::new("Schrödinger's cat: Quantum Field Theory!", 42);
+    /// assert!(paper.title_matches("schrodingers cat quantum field theory"));
+    /// assert!(!paper.title_matches("schrodingers cat"));
+    /// ```
+    pub fn title_matches(&self, query: &str) -> bool {
+        normalize_for_matching(&self.title) == normalize_for_matching(query)
+    }
+
+    /// Fill in missing fields of this paper from `other`, another view of the same paper,
+    /// e.g. the target of a citation page enriched with the result in a search page.
+    ///
+    /// Fields already set on `self` are kept as is. `authors_truncated` is taken along with
+    /// `authors`, and `access` is taken only if it is `Access::Unknown` on `self`.
+    ///
+    /// Returns `false` and leaves `self` untouched
+    /// unless both papers have the same `cluster_id`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use scholar::paper::Paper;
+    ///
+    /// let mut target = Paper::new("foo", 42);
+    /// let mut result = Paper::new("foo", 42);
+    /// result.citation_count = Some(10.into());
+    ///
+    /// assert!(target.merge(&result));
+    /// assert_eq!(target.citation_count, Some(10.into()));
+    /// assert!(!target.merge(&Paper::new("bar", 43)));
+    /// ```
+    pub fn merge(&mut self, other: &Paper) -> bool {
+        match (self.cluster_id, other.cluster_id) {
+            (Some(id), Some(other_id)) if id == other_id => {}
+            _ => return false,
+        }
+
+        self.fill_missing(other);
+        true
+    }
+
+    /// Fill fields unknown in `self` with those of `other` like `merge()`,
+    /// without checking their cluster IDs.
+    pub(crate) fn fill_missing(&mut self, other: &Paper) {
+        fn fill<T: Clone>(field: &mut Option<T>, other: &Option<T>) {
+            if field.is_none() {
+                *field = other.clone();
+            }
+        }
+
+        if self.authors.is_none() {
+            self.authors = other.authors.clone();
+            self.authors_truncated = other.authors_truncated;
+        }
+        fill(&mut self.venue, &other.venue);
+        fill(&mut self.link, &other.link);
+        fill(&mut self.pdf_link, &other.pdf_link);
+        fill(&mut self.year, &other.year);
+        fill(&mut self.snippet, &other.snippet);
+        fill(&mut self.citation_count, &other.citation_count);
+        fill(&mut self.citers, &other.citers);
+        fill(&mut self.citation_url, &other.citation_url);
+        fill(&mut self.versions_cluster_id, &other.versions_cluster_id);
+        fill(&mut self.versions_count, &other.versions_count);
+        fill(&mut self.versions_url, &other.versions_url);
+        fill(&mut self.related_url, &other.related_url);
+        fill(&mut self.info_id, &other.info_id);
+        fill(&mut self.profile_article_id, &other.profile_article_id);
+        fill(&mut self.doi, &other.doi);
+        fill(&mut self.arxiv_id, &other.arxiv_id);
+        fill(&mut self.publisher, &other.publisher);
+        fill(&mut self.issn, &other.issn);
+        fill(&mut self.references_count, &other.references_count);
+        fill(&mut self.article_type, &other.article_type);
+        fill(&mut self.doc_type, &other.doc_type);
+        fill(&mut self.result_position, &other.result_position);
+        fill(&mut self.age_days, &other.age_days);
+        fill(&mut self.raw_html, &other.raw_html);
+        fill(&mut self.save_token, &other.save_token);
+        if self.access == Access::Unknown {
+            self.access = other.access;
+        }
+    }
+
+    /// Get the cluster ID of the work, which groups all versions of this paper.
+    ///
+    /// The ID found in "All N versions" (`cluster=`) link takes precedence.
+    /// Otherwise, `cluster_id` is returned, which may come from "Cited by" (`cites=`) link.
+    /// `None` if the paper has neither ID.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use scholar::id::ClusterId;
+    /// use scholar::paper::Paper;
+    ///
+    /// let mut paper = Paper::new("foo", 42);
+    /// assert_eq!(paper.work_id(), Some(ClusterId(42)));
+    ///
+    /// paper.versions_cluster_id = Some(ClusterId(43));
+    /// assert_eq!(paper.work_id(), Some(ClusterId(43)));
+    /// ```
+    pub fn work_id(&self) -> Option<ClusterId> {
+        self.versions_cluster_id.or(self.cluster_id)
+    }
+
+    /// Get the key of related articles found in `related_url`,
+    /// e.g. "abcDEF012-_J" for `/scholar?q=related:abcDEF012-_J:scholar.google.com/`.
+    /// Pass it to `ScholarQuery::related()` to list the related articles.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use scholar::paper::Paper;
+    ///
+    /// let mut paper = Paper::new("foo", 42);
+    /// assert_eq!(paper.related_id(), None);
+    ///
+    /// paper.related_url = Some(String::from("/scholar?q=related:abcDEF012-_J:scholar.google.com/"));
+    /// assert_eq!(paper.related_id(), Some(String::from("abcDEF012-_J")));
+    /// ```
+    pub fn related_id(&self) -> Option<String> {
+        self.related_url
+            .as_ref()
+            .and_then(|url| parse_related_from_url(url).ok())
+    }
+
+    /// Describe changes from `self` to `newer` in human-readable form,
+    /// e.g. "citations 120 → 135" or "venue added: Nature".
+    ///
+    /// Citers are compared by their number. `raw_html` and `save_token` are not compared.
+    /// Returns an empty `Vec` if nothing has changed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use scholar::paper::Paper;
+    ///
+    /// let mut older = Paper::new("foo", 42);
+    /// older.citation_count = Some(120.into());
+    ///
+    /// let mut newer = older.clone();
+    /// newer.citation_count = Some(135.into());
+    /// newer.venue = Some(String::from("Nature"));
+    ///
+    /// assert_eq!(
+    ///     older.describe_changes(&newer),
+    ///     vec!["venue added: Nature", "citations 120 → 135"]
+    /// );
+    /// ```
+    pub fn describe_changes(&self, newer: &Paper) -> Vec<String> {
+        fn describe<T: fmt::Display + PartialEq>(
+            changes: &mut Vec<String>,
+            name: &str,
+            older: Option<T>,
+            newer: Option<T>,
+        ) {
+            match (older, newer) {
+                (Some(o), Some(n)) => if o != n {
+                    changes.push(format!("{} {} → {}", name, o, n));
+                },
+                (None, Some(n)) => changes.push(format!("{} added: {}", name, n)),
+                (Some(o), None) => changes.push(format!("{} removed: {}", name, o)),
+                (None, None) => {}
+            }
+        }
+
+        let mut changes = Vec::new();
+        let c = &mut changes;
+
+        describe(c, "title", Some(&self.title), Some(&newer.title));
+        describe(c, "authors", self.authors.as_ref(), newer.authors.as_ref());
+        if self.authors_truncated != newer.authors_truncated {
+            c.push(format!(
+                "authors truncated {} → {}",
+                self.authors_truncated, newer.authors_truncated
+            ));
+        }
+        describe(c, "venue", self.venue.as_ref(), newer.venue.as_ref());
+        describe(c, "link", self.link.as_ref(), newer.link.as_ref());
+        describe(c, "PDF link", self.pdf_link.as_ref(), newer.pdf_link.as_ref());
+        describe(c, "year", self.year, newer.year);
+        describe(c, "snippet", self.snippet.as_ref(), newer.snippet.as_ref());
+        describe(c, "cluster ID", self.cluster_id, newer.cluster_id);
+        describe(c, "DOI", self.doi.as_ref(), newer.doi.as_ref());
+        describe(c, "arXiv ID", self.arxiv_id.as_ref(), newer.arxiv_id.as_ref());
+        describe(c, "citations", self.citation_count, newer.citation_count);
+        describe(
+            c,
+            "citers",
+            self.citers.as_ref().map(Vec::len),
+            newer.citers.as_ref().map(Vec::len),
+        );
+        describe(
+            c,
+            "citation URL",
+            self.citation_url.as_ref(),
+            newer.citation_url.as_ref(),
+        );
+        describe(
+            c,
+            "versions cluster ID",
+            self.versions_cluster_id,
+            newer.versions_cluster_id,
+        );
+        describe(c, "versions", self.versions_count, newer.versions_count);
+        describe(
+            c,
+            "versions URL",
+            self.versions_url.as_ref(),
+            newer.versions_url.as_ref(),
+        );
+        describe(
+            c,
+            "related URL",
+            self.related_url.as_ref(),
+            newer.related_url.as_ref(),
+        );
+        describe(c, "info ID", self.info_id.as_ref(), newer.info_id.as_ref());
+        describe(
+            c,
+            "profile article ID",
+            self.profile_article_id.as_ref(),
+            newer.profile_article_id.as_ref(),
+        );
+        describe(
+            c,
+            "publisher",
+            self.publisher.as_ref(),
+            newer.publisher.as_ref(),
+        );
+        describe(c, "ISSN", self.issn.as_ref(), newer.issn.as_ref());
+        describe(
+            c,
+            "references",
+            self.references_count,
+            newer.references_count,
+        );
+        describe(
+            c,
+            "article type",
+            self.article_type.as_ref(),
+            newer.article_type.as_ref(),
+        );
+        describe(
+            c,
+            "result position",
+            self.result_position,
+            newer.result_position,
+        );
+        describe(c, "age in days", self.age_days, newer.age_days);
+        if self.access != newer.access {
+            c.push(format!("access {:?} → {:?}", self.access, newer.access));
+        }
+        if self.doc_type != newer.doc_type {
+            c.push(format!(
+                "document type {:?} → {:?}",
+                self.doc_type, newer.doc_type
+            ));
         }
+
+        changes
     }
 
-    fn cluster_id_to_citation_url(id: u64) -> String {
-        format!("{}?cites={}", super::GOOGLESCHOLAR_URL_BASE, id)
+    /// Convert to a JSON value with a stable schema,
+    /// which does not change even if fields of `Paper` are renamed.
+    ///
+    /// The value is an object with the following keys:
+    ///
+    /// | Key               | Type                 | Null if missing |
+    /// |-------------------|----------------------|-----------------|
+    /// | `title`           | string               | no              |
+    /// | `authors`         | string               | yes             |
+    /// | `authors_truncated` | boolean            | no              |
+    /// | `venue`           | string               | yes             |
+    /// | `link`            | string               | yes             |
+    /// | `pdf_link`        | string               | yes             |
+    /// | `year`            | number               | yes             |
+    /// | `snippet`         | string               | yes             |
+    /// | `cluster_id`      | number               | yes             |
+    /// | `citation_count`  | number               | yes             |
+    /// | `citation_count_approximate` | boolean   | yes             |
+    /// | `citation_url`    | string               | yes             |
+    /// | `versions_cluster_id` | number           | yes             |
+    /// | `versions_count`  | number               | yes             |
+    /// | `versions_url`    | string               | yes             |
+    /// | `related_url`     | string               | yes             |
+    /// | `info_id`         | string               | yes             |
+    /// | `profile_article_id` | string            | yes             |
+    /// | `doi`             | string               | yes             |
+    /// | `arxiv_id`        | string               | yes             |
+    /// | `publisher`       | string               | yes             |
+    /// | `issn`            | string               | yes             |
+    /// | `references_count` | number              | yes             |
+    /// | `article_type`    | string               | yes             |
+    /// | `doc_type`        | string               | yes             |
+    /// | `result_position` | number               | yes             |
+    /// | `age_days`        | number               | yes             |
+    /// | `access`          | string               | no              |
+    /// | `citers`          | array of this schema | yes             |
+    ///
+    /// `access` is one of `"full_text"`, `"resolver"`, `"unavailable"`, and `"unknown"`.
+    /// `doc_type` is one of `"pdf"`, `"html"`, `"book"`, and `"citation"`, or the label as is.
+    /// Every field but `raw_html` and `save_token` is included.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use scholar::paper::Paper;
+    ///
+    /// let json = Paper::new("foo", 42).to_json_value();
+    /// assert_eq!(json["title"], "foo");
+    /// assert_eq!(json["cluster_id"], 42);
+    /// assert!(json["year"].is_null());
+    /// assert_eq!(json["access"], "unknown");
+    /// ```
+    #[cfg(feature = "serde_json")]
+    pub fn to_json_value(&self) -> Value {
+        let access = match self.access {
+            Access::FullText => "full_text",
+            Access::Resolver => "resolver",
+            Access::ExplicitlyUnavailable => "unavailable",
+            Access::Unknown => "unknown",
+        };
+        let doc_type = self.doc_type.as_ref().map(|t| match *t {
+            DocType::Pdf => "pdf",
+            DocType::Html => "html",
+            DocType::Book => "book",
+            DocType::Citation => "citation",
+            DocType::Other(ref label) => label.as_str(),
+        });
+        let citers = self.citers
+            .as_ref()
+            .map(|c| c.iter().map(Paper::to_json_value).collect::<Vec<_>>());
+
+        json!({
+            "title": self.title,
+            "authors": self.authors,
+            "authors_truncated": self.authors_truncated,
+            "venue": self.venue,
+            "link": self.link,
+            "pdf_link": self.pdf_link,
+            "year": self.year,
+            "snippet": self.snippet,
+            "cluster_id": self.cluster_id.map(ClusterId::as_u64),
+            "citation_count": self.citation_count.map(|c| c.value),
+            "citation_count_approximate": self.citation_count.map(|c| c.approximate),
+            "citation_url": self.citation_url,
+            "versions_cluster_id": self.versions_cluster_id.map(ClusterId::as_u64),
+            "versions_count": self.versions_count,
+            "versions_url": self.versions_url,
+            "related_url": self.related_url,
+            "info_id": self.info_id,
+            "profile_article_id": self.profile_article_id,
+            "doi": self.doi,
+            "arxiv_id": self.arxiv_id,
+            "publisher": self.publisher,
+            "issn": self.issn,
+            "references_count": self.references_count,
+            "article_type": self.article_type,
+            "doc_type": doc_type,
+            "result_position": self.result_position,
+            "age_days": self.age_days,
+            "access": access,
+            "citers": citers
+        })
+    }
+
+    /// Format as a BibTeX entry keyed on `cluster_id`.
+    ///
+    /// As in `export::papers_to_ris()`, books are typed `@book` with the venue as `publisher`,
+    /// and others `@article` with the venue as `journal`.
+    /// The title is wrapped in braces to preserve capitalization.
+    /// `author`, `year`, and the venue fields are omitted if unknown.
+    /// A truncated author list ends with "and others".
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use scholar::paper::Paper;
+    ///
+    /// let mut paper = Paper::new("Quantal phase factors & Berry's phase", 42);
+    /// paper.year = Some(1984);
+    /// assert_eq!(
+    ///     paper.to_bibtex(),
+    ///     "@article{42,\n  title = {{Quantal phase factors \\& Berry's phase}},\n  year = {1984}\n}\n"
+    /// );
+    /// ```
+    pub fn to_bibtex(&self) -> String {
+        let key = self.cluster_id
+            .map_or_else(|| String::from("unknown"), |id| id.to_string());
+
+        let mut fields = vec![format!("title = {{{{{}}}}}", bibtex_escape(&self.title))];
+        let authors = self.author_list();
+        if !authors.is_empty() {
+            let mut authors = authors.join(" and ");
+            if self.authors_truncated {
+                authors.push_str(" and others");
+            }
+            fields.push(format!("author = {{{}}}", bibtex_escape(&authors)));
+        }
+        if let Some(year) = self.year {
+            fields.push(format!("year = {{{}}}", year));
+        }
+        let book = self.doc_type == Some(DocType::Book);
+        if let Some(ref venue) = self.venue {
+            let field = if book { "publisher" } else { "journal" };
+            fields.push(format!("{} = {{{}}}", field, bibtex_escape(venue)));
+        }
+
+        format!(
+            "@{}{{{},\n  {}\n}}\n",
+            if book { "book" } else { "article" },
+            key,
+            fields.join(",\n  ")
+        )
+    }
+}
+
+/// Builder of `Paper`, started with `Paper::builder()`.
+///
+/// Fields not set are left as `Paper::new()` does, except that the cluster ID is unknown.
+///
+/// # Example
+///
+/// ```
+/// use scholar::id::ClusterId;
+/// use scholar::paper::Paper;
+///
+/// let paper = Paper::builder("Quantal phase factors accompanying adiabatic changes")
+///     .cluster_id(15_570_691_018_430_890_829)
+///     .authors("MV Berry")
+///     .venue("Proc. R. Soc. Lond. A")
+///     .year(1984)
+///     .citation_count(12_345)
+///     .build();
+///
+/// assert_eq!(paper.cluster_id, Some(ClusterId(15_570_691_018_430_890_829)));
+/// assert_eq!(paper.citations(), 12_345);
+/// ```
+#[derive(Clone, Debug)]
+pub struct PaperBuilder {
+    paper: Paper,
+}
+
+impl PaperBuilder {
+    /// Start building a paper of `title`, without cluster ID.
+    pub fn new(title: &str) -> Self {
+        let mut paper = Paper::new(title, 0);
+        paper.set_cluster_id(None);
+        Self { paper }
+    }
+
+    /// Set `cluster_id`, and `citation_url` accordingly.
+    pub fn cluster_id(mut self, cluster_id: u64) -> Self {
+        self.paper.set_cluster_id(Some(ClusterId(cluster_id)));
+        self
+    }
+
+    pub fn authors(mut self, authors: &str) -> Self {
+        self.paper.authors = Some(authors.to_owned());
+        self
+    }
+
+    pub fn venue(mut self, venue: &str) -> Self {
+        self.paper.venue = Some(venue.to_owned());
+        self
+    }
+
+    pub fn year(mut self, year: u32) -> Self {
+        self.paper.year = Some(year);
+        self
+    }
+
+    pub fn link(mut self, link: &str) -> Self {
+        self.paper.link = Some(link.to_owned());
+        self
+    }
+
+    pub fn pdf_link(mut self, pdf_link: &str) -> Self {
+        self.paper.pdf_link = Some(pdf_link.to_owned());
+        self
+    }
+
+    pub fn snippet(mut self, snippet: &str) -> Self {
+        self.paper.snippet = Some(snippet.to_owned());
+        self
+    }
+
+    /// Set an exact citation count.
+    pub fn citation_count(mut self, citation_count: u32) -> Self {
+        self.paper.citation_count = Some(citation_count.into());
+        self
+    }
+
+    pub fn doi(mut self, doi: &str) -> Self {
+        self.paper.doi = Some(doi.to_owned());
+        self
+    }
+
+    pub fn doc_type(mut self, doc_type: DocType) -> Self {
+        self.paper.doc_type = Some(doc_type);
+        self
+    }
+
+    pub fn citers(mut self, citers: Vec<Paper>) -> Self {
+        self.paper.citers = Some(citers);
+        self
+    }
+
+    pub fn build(self) -> Paper {
+        self.paper
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn title_matches_test() {
+        let accented = Paper::new("Équations de Schrödinger", 0);
+        let plain = Paper::new("equations de SCHRODINGER", 1);
+        assert!(accented.title_matches(&plain.title));
+        assert!(plain.title_matches(&accented.title));
+
+        let paper = Paper::new("Quantum Field Theory!", 2);
+        assert!(paper.title_matches("quantum   field theory"));
+        assert!(!paper.title_matches("quantum field theories"));
+    }
+
+    #[test]
+    fn display_citation_test() {
+        let mut paper = Paper::builder("Quantal phase factors accompanying adiabatic changes.")
+            .authors("MV Berry, B Simon")
+            .venue("Proc. R. Soc. Lond. A.")
+            .year(1984)
+            .build();
+        assert_eq!(
+            paper.display_citation().to_string(),
+            "MV Berry, B Simon (1984). \
+             Quantal phase factors accompanying adiabatic changes. Proc. R. Soc. Lond. A."
+        );
+
+        paper.authors_truncated = true;
+        paper.year = None;
+        paper.venue = None;
+        assert_eq!(
+            paper.display_citation().to_string(),
+            "MV Berry, B Simon et al. Quantal phase factors accompanying adiabatic changes."
+        );
+        assert_eq!(
+            Paper::builder("foo").build().display_citation().to_string(),
+            "foo."
+        );
+    }
+
+    #[test]
+    fn paper_key_test() {
+        use std::collections::HashSet;
+
+        let mut papers = [
+            Paper::builder("no ID").build(),
+            Paper::builder("b").cluster_id(2).build(),
+            Paper::builder("a").cluster_id(1).build(),
+        ];
+        papers.sort_by(Paper::cmp_by_cluster_id);
+        assert_eq!(
+            papers.iter().map(|p| p.title.as_str()).collect::<Vec<_>>(),
+            vec!["a", "b", "no ID"]
+        );
+
+        let mut set = HashSet::new();
+        assert!(set.insert(papers[0].clone()));
+        assert!(!set.insert(papers[0].clone()));
+        assert!(set.insert(papers[1].clone()));
+        assert_eq!(set.len(), 2);
+
+        assert_eq!(papers[0].first_author(), None);
+        assert_eq!(papers[0].citations(), 0);
+        assert!(!papers[0].has_full_text());
+    }
+
+    #[test]
+    fn merge_test() {
+        // Target of a citation page: citers are known, but the count is not shown.
+        let mut target = Paper::new("foo", 42);
+        target.citation_count = None;
+        target.citers = Some(vec![Paper::new("bar", 1)]);
+        target.link = Some(String::from("https://example.com/foo"));
+
+        // Result in a search page.
+        let mut result = Paper::new("foo", 42);
+        result.authors = Some(String::from("A Foo, B Bar"));
+        result.authors_truncated = true;
+        result.year = Some(1999);
+        result.citation_count = Some(10.into());
+        result.link = Some(String::from("https://example.com/foo.pdf"));
+        result.access = Access::FullText;
+
+        assert!(target.merge(&result));
+        assert_eq!(target.authors, Some(String::from("A Foo, B Bar")));
+        assert!(target.authors_truncated);
+        assert_eq!(target.year, Some(1999));
+        assert_eq!(target.citation_count, Some(10.into()));
+        assert_eq!(target.citers, Some(vec![Paper::new("bar", 1)]));
+        assert_eq!(target.link, Some(String::from("https://example.com/foo")));
+        assert_eq!(target.access, Access::FullText);
+
+        // Existing values are not overwritten.
+        let mut update = result.clone();
+        update.citation_count = Some(20.into());
+        assert!(target.merge(&update));
+        assert_eq!(target.citation_count, Some(10.into()));
+
+        let before = target.clone();
+        assert!(!target.merge(&Paper::new("foo", 43)));
+        let mut no_id = Paper::new("foo", 0);
+        no_id.set_cluster_id(None);
+        assert!(!target.merge(&no_id));
+        assert_eq!(target, before);
+    }
+
+    #[test]
+    fn to_bibtex_test() {
+        let mut paper = Paper::new(
+            "Significance of electromagnetic potentials in the quantum theory",
+            5545735591029960915,
+        );
+        paper.authors = Some(String::from("Y Aharonov, D Bohm"));
+        paper.year = Some(1959);
+        paper.venue = Some(String::from("Physical Review"));
+
+        assert_eq!(
+            paper.to_bibtex(),
+            r#"@article{5545735591029960915,
+  title = {{Significance of electromagnetic potentials in the quantum theory}},
+  author = {Y Aharonov and D Bohm},
+  year = {1959},
+  journal = {Physical Review}
+}
+"#
+        );
+
+        let mut book = Paper::new("Quantum field theory", 7);
+        book.authors = Some(String::from("M Srednicki"));
+        book.year = Some(2007);
+        book.venue = Some(String::from("Cambridge University Press & Assessment"));
+        book.doc_type = Some(DocType::Book);
+
+        assert_eq!(
+            book.to_bibtex(),
+            r#"@book{7,
+  title = {{Quantum field theory}},
+  author = {M Srednicki},
+  year = {2007},
+  publisher = {Cambridge University Press \& Assessment}
+}
+"#
+        );
+
+        let mut paper = Paper::new(r"100% of {C_60} & $\alpha$ #1", 42);
+        paper.authors = Some(String::from("G Aad, T Abajyan"));
+        paper.authors_truncated = true;
+        paper.set_cluster_id(None);
+
+        assert_eq!(
+            paper.to_bibtex(),
+            r#"@article{unknown,
+  title = {{100\% of \{C\_60\} \& \$\textbackslash{}alpha\$ \#1}},
+  author = {G Aad and T Abajyan and others}
+}
+"#
+        );
+    }
+
+    #[test]
+    fn describe_changes_test() {
+        let mut older = Paper::new("Quantal phase factors", 15570691018430890829);
+        older.link = Some(String::from("http://example.com/old.pdf"));
+        older.citation_count = Some(7813.into());
+        older.result_position = Some(3);
+
+        assert!(older.describe_changes(&older).is_empty());
+
+        let mut newer = older.clone();
+        newer.title = String::from("Quantal phase factors accompanying adiabatic changes");
+        newer.authors = Some(String::from("MV Berry"));
+        newer.link = None;
+        newer.year = Some(1984);
+        newer.snippet = Some(String::from("A quantal system in an eigenstate"));
+        newer.citation_count = Some(CitationCount {
+            value: 7900,
+            approximate: true,
+        });
+        newer.citers = Some(vec![Paper::new("foo", 42)]);
+        newer.publisher = Some(String::from("The Royal Society"));
+        newer.references_count = Some(12);
+        newer.result_position = Some(0);
+        newer.age_days = Some(2);
+        newer.access = Access::FullText;
+        newer.raw_html = Some(String::from("<div></div>"));
+
+        assert_eq!(
+            older.describe_changes(&newer),
+            vec![
+                "title Quantal phase factors → Quantal phase factors accompanying adiabatic changes",
+                "authors added: MV Berry",
+                "link removed: http://example.com/old.pdf",
+                "year added: 1984",
+                "snippet added: A quantal system in an eigenstate",
+                "citations 7813 → about 7900",
+                "citers added: 1",
+                "publisher added: The Royal Society",
+                "references added: 12",
+                "result position 3 → 0",
+                "age in days added: 2",
+                "access Unknown → FullText",
+            ]
+        );
+    }
+
+    #[cfg(all(feature = "serde-derive", feature = "serde_json"))]
+    #[test]
+    fn serde_round_trip_test() {
+        use serde_json;
+
+        let mut paper = Paper::new("Quantal phase factors accompanying adiabatic changes", 1);
+        paper.authors = Some(String::from("MV Berry"));
+        paper.year = Some(1984);
+        paper.citation_count = Some(CitationCount {
+            value: 7900,
+            approximate: true,
+        });
+        paper.access = Access::FullText;
+        paper.citers = Some(vec![
+            {
+                let mut citer = Paper::new("Berry phase effects on electronic properties", 2);
+                citer.citers = Some(vec![Paper::new("foo", 3)]);
+                citer
+            },
+            Paper::new("bar", 4),
+        ]);
+
+        let json = serde_json::to_string(&paper).unwrap();
+        assert!(json.contains(r#""citation_count":{"value":7900,"approximate":true}"#));
+        assert_eq!(serde_json::from_str::<Paper>(&json).unwrap(), paper);
+    }
+
+    #[cfg(feature = "serde_json")]
+    #[test]
+    fn to_json_value_test() {
+        let mut paper = Paper::new(
+            "Significance of electromagnetic potentials in the quantum theory",
+            5545735591029960915,
+        );
+        paper.authors = Some(String::from("Y Aharonov, D Bohm"));
+        paper.venue = Some(String::from("Physical Review"));
+        paper.link = Some(String::from(
+            "https://journals.aps.org/pr/abstract/10.1103/PhysRev.115.485",
+        ));
+        paper.year = Some(1959);
+        paper.citation_count = Some(6961.into());
+        paper.citers = Some(vec![Paper::new("foo", 42)]);
+        paper.versions_cluster_id = paper.cluster_id;
+        paper.versions_count = Some(4);
+        paper.article_type = Some(String::from("Review article"));
+        paper.doc_type = Some(DocType::Book);
+        paper.result_position = Some(2);
+        paper.raw_html = Some(String::from("<div></div>"));
+        paper.access = Access::FullText;
+
+        assert_eq!(
+            paper.to_json_value(),
+            json!({
+                "title": "Significance of electromagnetic potentials in the quantum theory",
+                "authors": "Y Aharonov, D Bohm",
+                "authors_truncated": false,
+                "venue": "Physical Review",
+                "link": "https://journals.aps.org/pr/abstract/10.1103/PhysRev.115.485",
+                "pdf_link": null,
+                "year": 1959,
+                "snippet": null,
+                "cluster_id": 5545735591029960915u64,
+                "citation_count": 6961,
+                "citation_count_approximate": false,
+                "citation_url": "https://scholar.google.com/scholar?cites=5545735591029960915",
+                "versions_cluster_id": 5545735591029960915u64,
+                "versions_count": 4,
+                "versions_url": null,
+                "related_url": null,
+                "info_id": null,
+                "profile_article_id": null,
+                "doi": null,
+                "arxiv_id": null,
+                "publisher": null,
+                "issn": null,
+                "references_count": null,
+                "article_type": "Review article",
+                "doc_type": "book",
+                "result_position": 2,
+                "age_days": null,
+                "access": "full_text",
+                "citers": [{
+                    "title": "foo",
+                    "authors": null,
+                    "authors_truncated": false,
+                    "venue": null,
+                    "link": null,
+                    "pdf_link": null,
+                    "year": null,
+                    "snippet": null,
+                    "cluster_id": 42,
+                    "citation_count": null,
+                    "citation_count_approximate": null,
+                    "citation_url": "https://scholar.google.com/scholar?cites=42",
+                    "versions_cluster_id": null,
+                    "versions_count": null,
+                    "versions_url": null,
+                    "related_url": null,
+                    "info_id": null,
+                    "profile_article_id": null,
+                    "doi": null,
+                    "arxiv_id": null,
+                    "publisher": null,
+                    "issn": null,
+                    "references_count": null,
+                    "article_type": null,
+                    "doc_type": null,
+                    "result_position": null,
+                    "age_days": null,
+                    "access": "unknown",
+                    "citers": null
+                }]
+            })
+        );
     }
 }
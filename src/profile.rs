@@ -0,0 +1,61 @@
+//! Structs scraped from an author profile page of Google Scholar Citations.
+
+/// Summary of an author shown at the top of the profile page.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-derive", derive(Serialize))]
+pub struct AuthorProfile {
+    pub name: String,
+    /// Affiliation as shown, e.g. "Physics, University of Bristol".
+    pub affiliation: Option<String>,
+    /// Domain of the verified email address, e.g. "bristol.ac.uk".
+    pub email_domain: Option<String>,
+    /// Absolute URL of the homepage of the institution, if the domain of the verified email
+    /// address links to it.
+    pub verified_institution_url: Option<String>,
+    /// Research interests labeled by the author.
+    pub interests: Vec<String>,
+    /// Number of citations.
+    pub citations: Option<ProfileMetric>,
+    pub h_index: Option<ProfileMetric>,
+    pub i10_index: Option<ProfileMetric>,
+    /// First year of the recent period of metrics, e.g. 2013 for the "Since 2013" column.
+    pub recent_since: Option<u32>,
+}
+
+/// A co-author listed in the sidebar of the profile page.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-derive", derive(Serialize))]
+pub struct Coauthor {
+    pub name: String,
+    /// ID of the profile of the co-author, to be passed to `request::profile_url()`.
+    pub user_id: String,
+    /// Title or affiliation shown under the name, e.g. "Professor of Physics, MIT".
+    pub title: Option<String>,
+    /// Number of citations in total, if shown.
+    pub citation_count: Option<u32>,
+}
+
+/// Value of a citation metric of an author, over all years and over recent years.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-derive", derive(Serialize))]
+pub struct ProfileMetric {
+    pub all: u32,
+    /// Value since `AuthorProfile::recent_since`.
+    pub recent: u32,
+}
+
+/// An author listed in the result of searching authors.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-derive", derive(Serialize))]
+pub struct AuthorSummary {
+    pub name: String,
+    /// ID of the profile, e.g. "qc6CJjYAAAAJ", to be passed to `request::profile_url()`.
+    pub user_id: String,
+    pub affiliation: Option<String>,
+    /// Domain of the verified email address, e.g. "bristol.ac.uk".
+    pub email_domain: Option<String>,
+    /// Number of citations in total.
+    pub cited_by: Option<u32>,
+    /// Absolute URL of the photo, which may be a placeholder.
+    pub thumbnail_url: Option<String>,
+}
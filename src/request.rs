@@ -3,9 +3,11 @@
 use std::fmt;
 use std::borrow::Cow;
 
-use reqwest::{self, Url};
+#[cfg(feature = "client")]
+use reqwest;
+use url::Url;
 
-use super::{GOOGLESCHOLAR_URL_BASE, MAX_RESULT_COUNT};
+use super::{GOOGLESCHOLAR_URL_BASE, MAX_REACHABLE_RESULT_COUNT, MAX_RESULT_COUNT};
 use errors::*;
 
 /// Query to Google Scholar.
@@ -14,16 +16,24 @@ pub trait Query {
     fn to_url(&self) -> Result<Url>;
 }
 
+/// Language of Google Scholar's interface, used as the `hl` parameter of every query
+/// unless specified otherwise.
+pub const DEFAULT_LANGUAGE: &str = "en";
+
+/// `User-Agent` header sent with requests, since Google Scholar rejects unknown clients.
+#[cfg(feature = "client")]
+pub(crate) const USER_AGENT: &str =
+    "Mozilla/5.0 (X11; Linux x86_64; rv:57.0) Gecko/20100101 Firefox/57.0";
+
 /// Sends a GET request with `query` to Google Scholar.
 ///
 /// # Return value
 ///
 /// `Ok` of response body in `String`, or `Error`.
+#[cfg(feature = "client")]
 pub fn send_request<Q: Query + fmt::Display>(query: &Q, verbose: bool) -> Result<String> {
     use reqwest::header::UserAgent;
 
-    const USER_AGENT: &str = "Mozilla/5.0 (X11; Linux x86_64; rv:57.0) Gecko/20100101 Firefox/57.0";
-
     let client = reqwest::Client::new();
     let url = query.to_url()?;
 
@@ -38,12 +48,106 @@ pub fn send_request<Q: Query + fmt::Display>(query: &Q, verbose: bool) -> Result
     Ok(body)
 }
 
+/// Plan pages to fetch for `total` results, returning `start` offsets of the pages.
+///
+/// At most `cap` results are planned,
+/// and no more than `MAX_REACHABLE_RESULT_COUNT` since Google Scholar shows no more.
+/// `page_size` will be rounded down to `MAX_RESULT_COUNT`.
+/// Returns an empty `Vec` if `page_size` is 0.
+///
+/// # Example
+///
+/// ```
+/// use scholar::request::paginate_plan;
+///
+/// assert_eq!(paginate_plan(25, 10, 100), vec![0, 10, 20]);
+/// assert_eq!(paginate_plan(25, 10, 15), vec![0, 10]);
+/// assert_eq!(paginate_plan(5000, 10, 5000).len(), 100);
+/// ```
+pub fn paginate_plan(total: u32, page_size: u32, cap: u32) -> Vec<u32> {
+    use std::cmp;
+
+    let page_size = cmp::min(page_size, MAX_RESULT_COUNT);
+    if page_size == 0 {
+        return Vec::new();
+    }
+
+    let limit = cmp::min(cmp::min(total, cap), MAX_REACHABLE_RESULT_COUNT);
+    (0..limit).step_by(page_size as usize).collect()
+}
+
+/// Relative URLs of citation pages of a paper of `cluster_id` cited `total` times,
+/// from the first page, planned with `paginate_plan()` for pages of `MAX_RESULT_COUNT` citers
+/// and at most `cap` citers.
+///
+/// # Example
+///
+/// ```
+/// use scholar::request::citation_page_urls;
+///
+/// assert_eq!(
+///     citation_page_urls(42, 25, 100),
+///     vec![
+///         "/scholar?cites=42&hl=en",
+///         "/scholar?cites=42&hl=en&start=10",
+///         "/scholar?cites=42&hl=en&start=20",
+///     ]
+/// );
+/// ```
+pub fn citation_page_urls(cluster_id: u64, total: u32, cap: u32) -> Vec<String> {
+    paginate_plan(total, MAX_RESULT_COUNT, cap)
+        .into_iter()
+        .map(|start| {
+            let query = ScholarQuery::new().cites(cluster_id);
+            if start > 0 {
+                query.start(start).to_url()
+            } else {
+                query.to_url()
+            }
+        })
+        .collect()
+}
+
+/// Order of search results.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SortOrder {
+    /// Most relevant first, the default.
+    Relevance,
+    /// Most recently added first, sent as `scisbd=1`.
+    ///
+    /// Google Scholar lists only papers added in the last year in this order,
+    /// and their results lack some footer links, e.g. "Related articles".
+    Date,
+}
+
+impl Default for SortOrder {
+    fn default() -> Self {
+        SortOrder::Relevance
+    }
+}
+
+impl fmt::Display for SortOrder {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            SortOrder::Relevance => write!(f, "relevance"),
+            SortOrder::Date => write!(f, "date"),
+        }
+    }
+}
+
 /// Query to search Google Scholar for papers.
 pub struct SearchQuery {
     max_result_count: u32,
     words: Option<String>,
     authors: Option<String>,
+    source: Option<String>,
+    year_low: Option<u32>,
+    year_high: Option<u32>,
     title_only: bool,
+    include_citations: bool,
+    include_patents: bool,
+    sort_order: SortOrder,
+    language: String,
 }
 
 impl fmt::Display for SearchQuery {
@@ -53,12 +157,26 @@ impl fmt::Display for SearchQuery {
             r#"query to search for papers of:
           authors: {},
             words: {},
+           source: {},
+        year from: {},
+          year to: {},
 title-only search: {},
-     max #results: {}"#,
+        citations: {},
+          patents: {},
+          sort by: {},
+     max #results: {},
+         language: {}"#,
             option_unspecified(&self.authors),
             option_unspecified(&self.words),
+            option_unspecified(&self.source),
+            option_unspecified(&self.year_low),
+            option_unspecified(&self.year_high),
             self.title_only,
-            self.max_result_count
+            self.include_citations,
+            self.include_patents,
+            self.sort_order,
+            self.max_result_count,
+            self.language
         )
     }
 }
@@ -70,18 +188,31 @@ fn option_unspecified<T: ToString>(c: &Option<T>) -> Cow<'static, str> {
     }
 }
 
+fn option_year(year: Option<u32>) -> String {
+    year.map(|y| y.to_string()).unwrap_or_default()
+}
+
 const DEFAULT_MAX_RESULT_COUNT: u32 = 5;
 
 impl Default for SearchQuery {
     /// Create default SearchQuery.
     /// Maximum number of search result is defaulting to 5.
     /// Title-only search is disabled.
+    /// Citations and patents are included.
+    /// Language is defaulting to `DEFAULT_LANGUAGE`.
     fn default() -> Self {
         SearchQuery {
             max_result_count: DEFAULT_MAX_RESULT_COUNT,
             words: None,
             authors: None,
+            source: None,
+            year_low: None,
+            year_high: None,
             title_only: false,
+            include_citations: true,
+            include_patents: true,
+            sort_order: SortOrder::Relevance,
+            language: DEFAULT_LANGUAGE.to_owned(),
         }
     }
 }
@@ -92,11 +223,12 @@ impl Query for SearchQuery {
             return Err(ErrorKind::InvalidQuery.into());
         }
 
+        // Values are form-urlencoded, since `Url::set_query()` leaves `&`, `+`, and `#` as is
         macro_rules! option_stringify {
             ($x: expr) => {
                 match $x {
-                    Some(ref y) => y,
-                    None => "",
+                    Some(ref y) => Cow::Owned(form_urlencode(y)),
+                    None => Cow::Borrowed(""),
                 }
             }
         }
@@ -109,19 +241,29 @@ impl Query for SearchQuery {
              &as_eq=\
              &as_occt={}\
              &as_sauthors={}\
-             &as_publication=\
-             &as_ylo=\
-             &as_yhi=\
-             &as_vis=0\
+             &as_publication={}\
+             &as_ylo={}\
+             &as_yhi={}\
+             &as_vis={}\
              &btnG=\
-             &hl=en\
+             &hl={}\
              &num={}\
-             &as_sdt=0%2C5",
+             &as_sdt={}%2C5",
             option_stringify!(self.words),
             if self.title_only { "title" } else { "any" },
             option_stringify!(self.authors),
+            option_stringify!(self.source),
+            option_year(self.year_low),
+            option_year(self.year_high),
+            if self.include_citations { 0 } else { 1 },
+            self.language,
             self.max_result_count,
+            if self.include_patents { 0 } else { 1 },
         );
+        let query = match self.sort_order {
+            SortOrder::Relevance => query,
+            SortOrder::Date => query + "&scisbd=1",
+        };
         url.set_query(Some(&query));
 
         Ok(url)
@@ -245,6 +387,69 @@ impl SearchQuery {
         self.append_words(&format!("\"{}\"", phrase));
     }
 
+    /// Append `words` to search query to exclude papers containing any of them.
+    /// Each word in `words` is prefixed with `-`,
+    /// and appended to the query in the same way as `append_words()`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use scholar::request::SearchQuery;
+    ///
+    /// let mut q = SearchQuery::default();
+    ///
+    /// q.append_words("quantum");
+    /// q.append_excluded_words("gravity field");
+    /// assert_eq!(q.get_words(), &Some(String::from("quantum -gravity -field")));
+    /// ```
+    pub fn append_excluded_words(&mut self, words: &str) {
+        let excluded = words
+            .split_whitespace()
+            .map(|w| format!("-{}", w))
+            .collect::<Vec<_>>()
+            .join(" ");
+        if !excluded.is_empty() {
+            self.append_words(&excluded);
+        }
+    }
+
+    /// Append `terms` to search query to find papers containing at least one of them.
+    /// Terms are joined with `OR`, and appended to the query in the same way as `append_words()`.
+    /// A term consisting of multiple words is treated as a phrase.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use scholar::request::SearchQuery;
+    ///
+    /// let mut q = SearchQuery::default();
+    ///
+    /// q.append_words("quantum");
+    /// q.append_any_of(&["field theory", "gravity"]);
+    /// assert_eq!(
+    ///     q.get_words(),
+    ///     &Some(String::from(r#"quantum "field theory" OR gravity"#))
+    /// );
+    /// ```
+    pub fn append_any_of(&mut self, terms: &[&str]) {
+        let any_of = terms
+            .iter()
+            .map(|t| t.trim())
+            .filter(|t| !t.is_empty())
+            .map(|t| {
+                if t.contains(char::is_whitespace) {
+                    format!("\"{}\"", t)
+                } else {
+                    t.to_owned()
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(" OR ");
+        if !any_of.is_empty() {
+            self.append_words(&any_of);
+        }
+    }
+
     /// Set `authors` to search query.
     /// 'Authors' query specified so far will be cleared.
     ///
@@ -298,6 +503,51 @@ impl SearchQuery {
         &self.authors
     }
 
+    /// Set `source` to search query, restricting results to papers published in it,
+    /// e.g. a journal name. The `as_publication` parameter is set to it.
+    /// 'Source' query specified so far will be cleared.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use scholar::request::SearchQuery;
+    ///
+    /// let mut q = SearchQuery::default();
+    ///
+    /// q.set_source("Physical Review");
+    /// assert_eq!(q.get_source(), &Some(String::from("Physical Review")));
+    /// ```
+    pub fn set_source(&mut self, source: &str) {
+        self.source = Some(source.to_owned());
+    }
+
+    pub fn get_source(&self) -> &Option<String> {
+        &self.source
+    }
+
+    /// Restrict results to papers published from `low` to `high`, both inclusive.
+    /// `None` leaves the bound open.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use scholar::request::SearchQuery;
+    ///
+    /// let mut q = SearchQuery::default();
+    /// assert_eq!(q.get_year_range(), (None, None));
+    ///
+    /// q.set_year_range(Some(1984), None);
+    /// assert_eq!(q.get_year_range(), (Some(1984), None));
+    /// ```
+    pub fn set_year_range(&mut self, low: Option<u32>, high: Option<u32>) {
+        self.year_low = low;
+        self.year_high = high;
+    }
+
+    pub fn get_year_range(&self) -> (Option<u32>, Option<u32>) {
+        (self.year_low, self.year_high)
+    }
+
     /// Enable or disable title-only search.
     ///
     /// To enable, set `title_only` argument `true`;
@@ -326,8 +576,96 @@ impl SearchQuery {
         self.title_only
     }
 
+    /// Include or exclude citations, i.e. results Google Scholar knows only as cited by others.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use scholar::request::SearchQuery;
+    ///
+    /// let mut q = SearchQuery::default();
+    /// assert_eq!(q.get_include_citations(), true);
+    ///
+    /// q.set_include_citations(false);
+    /// assert_eq!(q.get_include_citations(), false);
+    /// ```
+    pub fn set_include_citations(&mut self, include_citations: bool) {
+        self.include_citations = include_citations;
+    }
+
+    pub fn get_include_citations(&self) -> bool {
+        self.include_citations
+    }
+
+    /// Include or exclude patents.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use scholar::request::SearchQuery;
+    ///
+    /// let mut q = SearchQuery::default();
+    /// assert_eq!(q.get_include_patents(), true);
+    ///
+    /// q.set_include_patents(false);
+    /// assert_eq!(q.get_include_patents(), false);
+    /// ```
+    pub fn set_include_patents(&mut self, include_patents: bool) {
+        self.include_patents = include_patents;
+    }
+
+    pub fn get_include_patents(&self) -> bool {
+        self.include_patents
+    }
+
+    /// Set the order of results. See `SortOrder`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use scholar::request::{SearchQuery, SortOrder};
+    ///
+    /// let mut q = SearchQuery::default();
+    /// assert_eq!(q.get_sort_order(), SortOrder::Relevance);
+    ///
+    /// q.set_sort_order(SortOrder::Date);
+    /// assert_eq!(q.get_sort_order(), SortOrder::Date);
+    /// ```
+    pub fn set_sort_order(&mut self, sort_order: SortOrder) {
+        self.sort_order = sort_order;
+    }
+
+    pub fn get_sort_order(&self) -> SortOrder {
+        self.sort_order
+    }
+
+    /// Set `language` to the `hl` parameter, the language of Google Scholar's interface.
+    ///
+    /// Labels such as "Cited by" are shown in this language.
+    /// Scraping is tested against English pages only,
+    /// so a language other than `"en"` requires scrapers to know its labels.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use scholar::request::SearchQuery;
+    ///
+    /// let mut q = SearchQuery::default();
+    /// assert_eq!(q.get_language(), "en");
+    ///
+    /// q.set_language("ja");
+    /// assert_eq!(q.get_language(), "ja");
+    /// ```
+    pub fn set_language(&mut self, language: &str) {
+        self.language = language.to_owned();
+    }
+
+    pub fn get_language(&self) -> &str {
+        &self.language
+    }
+
     fn is_valid(&self) -> bool {
-        self.words.is_some() || self.authors.is_some()
+        self.words.is_some() || self.authors.is_some() || self.source.is_some()
     }
 }
 
@@ -335,6 +673,7 @@ impl SearchQuery {
 pub struct CitationQuery {
     citation_url: String,
     max_result_count: u32,
+    language: String,
 }
 
 impl fmt::Display for CitationQuery {
@@ -343,8 +682,9 @@ impl fmt::Display for CitationQuery {
             f,
             r#"query to get list of papers which cites a paper of:
 URL of the paper: {},
-    max #results: {}"#,
-            self.citation_url, self.max_result_count
+    max #results: {},
+        language: {}"#,
+            self.citation_url, self.max_result_count, self.language
         )
     }
 }
@@ -354,7 +694,7 @@ impl Query for CitationQuery {
         let mut url = Url::parse(&self.citation_url).unwrap();
         let query = {
             let q = url.query().unwrap();
-            format!("{}&hl=en&num={}", q, self.max_result_count)
+            format!("{}&hl={}&num={}", q, self.language, self.max_result_count)
         };
         url.set_query(Some(&query));
 
@@ -365,10 +705,12 @@ impl Query for CitationQuery {
 impl CitationQuery {
     /// Create new CitationQuery with `citation_url`.
     /// Maximum number of search result is defaulting to 5.
+    /// Language is defaulting to `DEFAULT_LANGUAGE`.
     pub fn new(citation_url: &str) -> Self {
         Self {
             citation_url: citation_url.to_owned(),
             max_result_count: DEFAULT_MAX_RESULT_COUNT,
+            language: DEFAULT_LANGUAGE.to_owned(),
         }
     }
 
@@ -395,11 +737,37 @@ impl CitationQuery {
     pub fn get_count(&self) -> u32 {
         self.max_result_count
     }
+
+    /// Set `language` to the `hl` parameter, the language of Google Scholar's interface.
+    ///
+    /// Labels such as "Cited by" are shown in this language.
+    /// Scraping is tested against English pages only,
+    /// so a language other than `"en"` requires scrapers to know its labels.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use scholar::request::CitationQuery;
+    ///
+    /// let mut q = CitationQuery::new("https://example.com");
+    /// assert_eq!(q.get_language(), "en");
+    ///
+    /// q.set_language("ja");
+    /// assert_eq!(q.get_language(), "ja");
+    /// ```
+    pub fn set_language(&mut self, language: &str) {
+        self.language = language.to_owned();
+    }
+
+    pub fn get_language(&self) -> &str {
+        &self.language
+    }
 }
 
 /// Query to get paper cluster of a specified cluster ID.
 pub struct ClusterQuery {
     cluster_id: u64,
+    language: String,
 }
 
 impl fmt::Display for ClusterQuery {
@@ -415,22 +783,420 @@ impl fmt::Display for ClusterQuery {
 impl Query for ClusterQuery {
     fn to_url(&self) -> Result<Url> {
         let mut url = Url::parse(GOOGLESCHOLAR_URL_BASE).unwrap();
-        let query = format!("cluster={}", self.cluster_id);
+        let query = format!("cluster={}&hl={}", self.cluster_id, self.language);
         url.set_query(Some(&query));
         Ok(url)
     }
 }
 
 impl ClusterQuery {
+    /// Create new ClusterQuery with `cluster_id`.
+    /// Language is defaulting to `DEFAULT_LANGUAGE`.
     pub fn new(cluster_id: u64) -> Self {
-        Self { cluster_id }
+        Self {
+            cluster_id,
+            language: DEFAULT_LANGUAGE.to_owned(),
+        }
+    }
+
+    /// Set `language` to the `hl` parameter, the language of Google Scholar's interface.
+    ///
+    /// Labels such as "Cited by" are shown in this language.
+    /// Scraping is tested against English pages only,
+    /// so a language other than `"en"` requires scrapers to know its labels.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use scholar::request::ClusterQuery;
+    ///
+    /// let mut q = ClusterQuery::new(0);
+    /// assert_eq!(q.get_language(), "en");
+    ///
+    /// q.set_language("ja");
+    /// assert_eq!(q.get_language(), "ja");
+    /// ```
+    pub fn set_language(&mut self, language: &str) {
+        self.language = language.to_owned();
+    }
+
+    pub fn get_language(&self) -> &str {
+        &self.language
     }
 }
 
+/// Builder of relative URLs to Google Scholar, e.g. `/scholar?q=quantum+theory&hl=en&start=10`.
+///
+/// Unlike other queries, this does not send requests by itself,
+/// so that callers can choose the base URL and the HTTP client.
+///
+/// # Example
+///
+/// ```
+/// use scholar::request::ScholarQuery;
+///
+/// let url = ScholarQuery::new()
+///     .query("quantum theory")
+///     .start(10)
+///     .year_range(Some(1990), None)
+///     .to_url();
+/// assert_eq!(url, "/scholar?q=quantum+theory&hl=en&start=10&as_ylo=1990");
+///
+/// assert_eq!(ScholarQuery::new().cites(42).to_url(), "/scholar?cites=42&hl=en");
+/// ```
+#[derive(Clone, Debug)]
+pub struct ScholarQuery {
+    target: ScholarQueryTarget,
+    start: Option<u32>,
+    year_low: Option<u32>,
+    year_high: Option<u32>,
+    sort_order: SortOrder,
+    language: String,
+}
+
+#[derive(Clone, Debug)]
+enum ScholarQueryTarget {
+    Words(String),
+    Cites(u64),
+    Cluster(u64),
+    Related(String),
+}
+
+impl Default for ScholarQuery {
+    /// Create ScholarQuery searching for nothing.
+    /// Language is defaulting to `DEFAULT_LANGUAGE`.
+    fn default() -> Self {
+        Self {
+            target: ScholarQueryTarget::Words(String::new()),
+            start: None,
+            year_low: None,
+            year_high: None,
+            sort_order: SortOrder::Relevance,
+            language: DEFAULT_LANGUAGE.to_owned(),
+        }
+    }
+}
+
+impl ScholarQuery {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Search for papers with `query`.
+    /// Citing papers or cluster specified so far will be cleared.
+    pub fn query(mut self, query: &str) -> Self {
+        self.target = ScholarQueryTarget::Words(query.to_owned());
+        self
+    }
+
+    /// List papers citing a paper of `cluster_id`.
+    /// Query or cluster specified so far will be cleared.
+    pub fn cites(mut self, cluster_id: u64) -> Self {
+        self.target = ScholarQueryTarget::Cites(cluster_id);
+        self
+    }
+
+    /// List versions of a paper of `cluster_id`.
+    /// Query or citing papers specified so far will be cleared.
+    pub fn cluster(mut self, cluster_id: u64) -> Self {
+        self.target = ScholarQueryTarget::Cluster(cluster_id);
+        self
+    }
+
+    /// List papers related to a paper of `related_id`; see `Paper::related_id()`.
+    /// Query, citing papers, or cluster specified so far will be cleared.
+    pub fn related(mut self, related_id: &str) -> Self {
+        self.target = ScholarQueryTarget::Related(related_id.to_owned());
+        self
+    }
+
+    /// Start listing from the `start`-th result, counted from 0.
+    pub fn start(mut self, start: u32) -> Self {
+        self.start = Some(start);
+        self
+    }
+
+    /// Restrict results to those published from `low` to `high`, both inclusive.
+    /// `None` leaves the bound open.
+    pub fn year_range(mut self, low: Option<u32>, high: Option<u32>) -> Self {
+        self.year_low = low;
+        self.year_high = high;
+        self
+    }
+
+    /// Restrict results to those published in `year` or later, like "Since 2017" of the sidebar.
+    /// The upper bound specified so far will be cleared.
+    pub fn since(self, year: u32) -> Self {
+        self.year_range(Some(year), None)
+    }
+
+    /// List results in `sort_order`. See `SortOrder`.
+    pub fn sort_by(mut self, sort_order: SortOrder) -> Self {
+        self.sort_order = sort_order;
+        self
+    }
+
+    /// Set the `hl` parameter, the language of Google Scholar's interface.
+    /// See `SearchQuery::set_language()`.
+    pub fn lang(mut self, language: &str) -> Self {
+        self.language = language.to_owned();
+        self
+    }
+
+    /// Assemble the relative URL.
+    pub fn to_url(&self) -> String {
+        let mut url = String::from("/scholar?");
+        match self.target {
+            ScholarQueryTarget::Words(ref words) => {
+                url.push_str(&format!("q={}", form_urlencode(words)))
+            }
+            ScholarQueryTarget::Cites(id) => url.push_str(&format!("cites={}", id)),
+            ScholarQueryTarget::Cluster(id) => url.push_str(&format!("cluster={}", id)),
+            ScholarQueryTarget::Related(ref id) => url.push_str(&format!(
+                "q=related:{}:scholar.google.com/",
+                form_urlencode(id)
+            )),
+        }
+        url.push_str(&format!("&hl={}", form_urlencode(&self.language)));
+
+        if let Some(start) = self.start {
+            url.push_str(&format!("&start={}", start));
+        }
+        if let Some(year) = self.year_low {
+            url.push_str(&format!("&as_ylo={}", year));
+        }
+        if let Some(year) = self.year_high {
+            url.push_str(&format!("&as_yhi={}", year));
+        }
+        if self.sort_order == SortOrder::Date {
+            url.push_str("&scisbd=1");
+        }
+
+        url
+    }
+}
+
+/// Relative URL of the profile page of an author of `user_id`, e.g. "qc6CJjYAAAAJ",
+/// listing `page_size` articles from the `start`-th, counted from 0.
+///
+/// # Example
+///
+/// ```
+/// use scholar::request::profile_url;
+///
+/// assert_eq!(
+///     profile_url("qc6CJjYAAAAJ", 20, 100),
+///     "/citations?user=qc6CJjYAAAAJ&hl=en&cstart=20&pagesize=100"
+/// );
+/// ```
+pub fn profile_url(user_id: &str, start: u32, page_size: u32) -> String {
+    format!(
+        "/citations?user={}&hl={}&cstart={}&pagesize={}",
+        form_urlencode(user_id),
+        DEFAULT_LANGUAGE,
+        start,
+        page_size
+    )
+}
+
+/// Relative URL of the page of an article in an author profile, of `citation_for_view`
+/// as in `Paper::profile_article_id`. See `scrape::CitationDetailDocument`.
+///
+/// # Example
+///
+/// ```
+/// use scholar::request::citation_detail_url;
+///
+/// assert_eq!(
+///     citation_detail_url("qc6CJjYAAAAJ:u5HHmVD_uO8C"),
+///     "/citations?view_op=view_citation&hl=en&citation_for_view=qc6CJjYAAAAJ%3Au5HHmVD_uO8C"
+/// );
+/// ```
+pub fn citation_detail_url(citation_for_view: &str) -> String {
+    format!(
+        "/citations?view_op=view_citation&hl={}&citation_for_view={}",
+        DEFAULT_LANGUAGE,
+        form_urlencode(citation_for_view)
+    )
+}
+
+/// Relative URL of the "Cite" popup of a paper of `info_id` as in `Paper::info_id`.
+/// See `scrape::CitePopupDocument`.
+///
+/// # Example
+///
+/// ```
+/// use scholar::request::cite_popup_url;
+///
+/// assert_eq!(
+///     cite_popup_url("5HbwekOPDqkJ"),
+///     "/scholar?q=info:5HbwekOPDqkJ:scholar.google.com/&output=cite&hl=en"
+/// );
+/// ```
+pub fn cite_popup_url(info_id: &str) -> String {
+    format!(
+        "/scholar?q=info:{}:scholar.google.com/&output=cite&hl={}",
+        form_urlencode(info_id),
+        DEFAULT_LANGUAGE
+    )
+}
+
+/// Relative URL of the result of searching authors named `name`.
+/// See `scrape::AuthorSearchDocument`.
+///
+/// # Example
+///
+/// ```
+/// use scholar::request::author_search_url;
+///
+/// assert_eq!(
+///     author_search_url("michael berry"),
+///     "/citations?view_op=search_authors&mauthors=michael+berry&hl=en"
+/// );
+/// ```
+pub fn author_search_url(name: &str) -> String {
+    format!(
+        "/citations?view_op=search_authors&mauthors={}&hl={}",
+        form_urlencode(name),
+        DEFAULT_LANGUAGE
+    )
+}
+
+/// Relative URL of the top publications page of Google Scholar Metrics,
+/// in a category or subcategory like "phy" or "phy_quantumphysics", or overall.
+/// See `scrape::MetricsDocument`.
+///
+/// # Example
+///
+/// ```
+/// use scholar::request::top_venues_url;
+///
+/// assert_eq!(top_venues_url(None), "/citations?view_op=top_venues&hl=en");
+/// assert_eq!(
+///     top_venues_url(Some("phy_quantumphysics")),
+///     "/citations?view_op=top_venues&hl=en&vq=phy_quantumphysics"
+/// );
+/// ```
+pub fn top_venues_url(category: Option<&str>) -> String {
+    let mut url = format!("/citations?view_op=top_venues&hl={}", DEFAULT_LANGUAGE);
+    if let Some(category) = category {
+        url.push_str("&vq=");
+        url.push_str(&form_urlencode(category));
+    }
+    url
+}
+
+/// Relative URL of the "My library" page of the signed-in user,
+/// listing saved articles from the `start`-th, counted from 0.
+/// See `scrape::LibraryDocument`.
+///
+/// # Example
+///
+/// ```
+/// use scholar::request::library_url;
+///
+/// assert_eq!(library_url(0), "/scholar?scilib=1&hl=en");
+/// assert_eq!(library_url(10), "/scholar?scilib=1&hl=en&start=10");
+/// ```
+pub fn library_url(start: u32) -> String {
+    let mut url = format!("/scholar?scilib=1&hl={}", DEFAULT_LANGUAGE);
+    if start > 0 {
+        url.push_str(&format!("&start={}", start));
+    }
+    url
+}
+
+/// Percent-encode `s` as `application/x-www-form-urlencoded`, with spaces as `+`.
+pub(crate) fn form_urlencode(s: &str) -> String {
+    let mut encoded = String::with_capacity(s.len());
+    for &b in s.as_bytes() {
+        match b {
+            b'-' | b'.' | b'_' | b'*' => encoded.push(b as char),
+            b if b.is_ascii_alphanumeric() => encoded.push(b as char),
+            b' ' => encoded.push('+'),
+            b => encoded.push_str(&format!("%{:02X}", b)),
+        }
+    }
+    encoded
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn scholar_query_to_url() {
+        assert_eq!(ScholarQuery::new().to_url(), "/scholar?q=&hl=en");
+        assert_eq!(
+            ScholarQuery::new().query("quantum theory").start(10).to_url(),
+            "/scholar?q=quantum+theory&hl=en&start=10"
+        );
+        assert_eq!(
+            ScholarQuery::new()
+                .query(r#""berry phase" author:berry & 量子"#)
+                .lang("ja")
+                .year_range(Some(1984), Some(2000))
+                .to_url(),
+            "/scholar?q=%22berry+phase%22+author%3Aberry+%26+%E9%87%8F%E5%AD%90\
+             &hl=ja&as_ylo=1984&as_yhi=2000"
+        );
+        assert_eq!(
+            ScholarQuery::new()
+                .query("foo")
+                .cites(5545735591029960915)
+                .start(20)
+                .to_url(),
+            "/scholar?cites=5545735591029960915&hl=en&start=20"
+        );
+        assert_eq!(
+            ScholarQuery::new()
+                .cluster(42)
+                .year_range(None, Some(1999))
+                .to_url(),
+            "/scholar?cluster=42&hl=en&as_yhi=1999"
+        );
+        assert_eq!(
+            ScholarQuery::new()
+                .query("berry phase")
+                .year_range(None, Some(1999))
+                .since(2017)
+                .sort_by(SortOrder::Date)
+                .to_url(),
+            "/scholar?q=berry+phase&hl=en&as_ylo=2017&scisbd=1"
+        );
+        assert_eq!(
+            ScholarQuery::new().related("abcDEF012-_J").start(10).to_url(),
+            "/scholar?q=related:abcDEF012-_J:scholar.google.com/&hl=en&start=10"
+        );
+    }
+
+    #[test]
+    fn paginate_plan_test() {
+        assert_eq!(paginate_plan(0, 10, 100), Vec::<u32>::new());
+        assert_eq!(paginate_plan(10, 0, 100), Vec::<u32>::new());
+        assert_eq!(paginate_plan(10, 10, 100), vec![0]);
+        assert_eq!(paginate_plan(11, 10, 100), vec![0, 10]);
+        assert_eq!(paginate_plan(30, 20, 100), vec![0, 10, 20]);
+        assert_eq!(paginate_plan(7, 3, 100), vec![0, 3, 6]);
+    }
+
+    #[test]
+    fn paginate_plan_cap_test() {
+        let plan = paginate_plan(MAX_REACHABLE_RESULT_COUNT, 10, 5000);
+        assert_eq!(plan.len(), 100);
+        assert_eq!(plan.last(), Some(&990));
+
+        let plan = paginate_plan(MAX_REACHABLE_RESULT_COUNT + 1, 10, 5000);
+        assert_eq!(plan.len(), 100);
+        assert_eq!(plan.last(), Some(&990));
+
+        let plan = paginate_plan(5000, 10, 991);
+        assert_eq!(plan.last(), Some(&990));
+
+        let plan = paginate_plan(5000, 10, 990);
+        assert_eq!(plan.last(), Some(&980));
+    }
+
     #[test]
     fn search_query_to_url() {
         let mut q = SearchQuery::default();
@@ -445,11 +1211,11 @@ mod tests {
             q.to_url().unwrap(),
             Url::parse(&format!(
                 "{}?\
-                 as_q=\"quantum theory\"\
+                 as_q=%22quantum+theory%22\
                  &as_epq=\
                  &as_eq=\
                  &as_occt=title\
-                 &as_sauthors=albert%20einstein\
+                 &as_sauthors=albert+einstein\
                  &as_publication=\
                  &as_ylo=\
                  &as_yhi=\
@@ -463,6 +1229,108 @@ mod tests {
         );
     }
 
+    #[test]
+    fn search_query_filters_to_url() {
+        let mut q = SearchQuery::default();
+
+        q.set_words("berry phase");
+        q.set_source("Proc. R. Soc.");
+        q.set_year_range(Some(1984), Some(1990));
+        q.set_include_citations(false);
+        q.set_include_patents(false);
+
+        assert_eq!(
+            q.to_url().unwrap(),
+            Url::parse(&format!(
+                "{}?\
+                 as_q=berry+phase\
+                 &as_epq=\
+                 &as_eq=\
+                 &as_occt=any\
+                 &as_sauthors=\
+                 &as_publication=Proc.+R.+Soc.\
+                 &as_ylo=1984\
+                 &as_yhi=1990\
+                 &as_vis=1\
+                 &btnG=\
+                 &hl=en\
+                 &num={}\
+                 &as_sdt=1%2C5",
+                GOOGLESCHOLAR_URL_BASE, DEFAULT_MAX_RESULT_COUNT
+            )).unwrap()
+        );
+
+        q.set_year_range(None, Some(1990));
+        assert!(
+            q.to_url()
+                .unwrap()
+                .as_str()
+                .contains("&as_ylo=&as_yhi=1990&")
+        );
+
+        q.set_sort_order(SortOrder::Date);
+        assert!(q.to_url().unwrap().as_str().ends_with("&as_sdt=1%2C5&scisbd=1"));
+    }
+
+    #[test]
+    fn search_query_operators() {
+        let mut q = SearchQuery::default();
+
+        q.append_words("berry");
+        q.append_phrase("geometric phase");
+        q.append_excluded_words("classical  optics");
+        q.append_any_of(&["adiabatic", " ", "quantum evolution"]);
+        q.append_excluded_words(" ");
+        q.append_any_of(&[]);
+
+        assert_eq!(
+            q.get_words(),
+            &Some(String::from(
+                r#"berry "geometric phase" -classical -optics adiabatic OR "quantum evolution""#
+            ))
+        );
+        assert!(
+            q.to_url()
+                .unwrap()
+                .as_str()
+                .contains("as_q=berry+%22geometric+phase%22+-classical+-optics+\
+                           adiabatic+OR+%22quantum+evolution%22&")
+        );
+    }
+
+    #[test]
+    fn search_query_to_url_encoding() {
+        let mut q = SearchQuery::default();
+        q.set_words(r#"C++ R&D #1 "it's""#);
+
+        let url = q.to_url().unwrap();
+        assert!(
+            url.as_str()
+                .contains("?as_q=C%2B%2B+R%26D+%231+%22it%27s%22&as_epq=&")
+        );
+        assert_eq!(url.fragment(), None);
+        let words = url.query_pairs()
+            .find(|(key, _)| key == "as_q")
+            .map(|(_, value)| value.into_owned());
+        assert_eq!(words, Some(String::from(r#"C++ R&D #1 "it's""#)));
+
+        let mut q = SearchQuery::default();
+        q.set_authors("O'Neil & Smith");
+        q.set_source("R&D Management");
+
+        let url = q.to_url().unwrap();
+        assert!(url.as_str().contains(
+            "&as_sauthors=O%27Neil+%26+Smith&as_publication=R%26D+Management&as_ylo=&"
+        ));
+        let param = |name| {
+            url.query_pairs()
+                .find(|(key, _)| key == name)
+                .map(|(_, value)| value.into_owned())
+        };
+        assert_eq!(param("as_publication"), Some(String::from("R&D Management")));
+        assert_eq!(param("D Management"), None);
+    }
+
     #[test]
     fn search_query_is_valid_pass() {
         {
@@ -478,6 +1346,13 @@ mod tests {
             q.set_authors("foo");
             assert!(q.is_valid());
         }
+
+        {
+            let mut q = SearchQuery::default();
+
+            q.set_source("foo");
+            assert!(q.is_valid());
+        }
     }
 
     #[test]
@@ -518,9 +1393,25 @@ mod tests {
         assert_eq!(
             q.to_url().unwrap(),
             Url::parse(&format!(
-                "{}?cluster={}",
+                "{}?cluster={}&hl=en",
                 GOOGLESCHOLAR_URL_BASE, TEST_CLUSTER_ID
             )).unwrap()
         );
     }
+
+    #[test]
+    fn query_language() {
+        let mut search = SearchQuery::default();
+        search.set_words("foo");
+        search.set_language("ja");
+        assert!(search.to_url().unwrap().as_str().contains("&hl=ja&"));
+
+        let mut citation = CitationQuery::new(&format!("{}?cites=0", GOOGLESCHOLAR_URL_BASE));
+        citation.set_language("ja");
+        assert!(citation.to_url().unwrap().as_str().contains("&hl=ja&"));
+
+        let mut cluster = ClusterQuery::new(0);
+        cluster.set_language("ja");
+        assert!(cluster.to_url().unwrap().as_str().ends_with("&hl=ja"));
+    }
 }
@@ -0,0 +1,210 @@
+//! Parse robots.txt to tell which pages crawlers are allowed to fetch.
+
+/// Rules of a robots.txt, grouped by user agents they apply to.
+///
+/// Rules are matched as Google documents: the group of the longest user agent name found in
+/// the user agent, or else the group of `*`, applies, and in it the rule of the longest path
+/// matching wins, with `Allow` winning ties. Paths may contain `*` wildcards and a `$` anchor.
+///
+/// # Example
+///
+/// ```
+/// use scholar::robots::RobotsTxt;
+///
+/// let robots = RobotsTxt::parse(
+///     "User-agent: *\n\
+///      Disallow: /scholar\n\
+///      Allow: /scholar?q=\n",
+/// );
+/// assert!(!robots.is_allowed("my-crawler/0.1", "/scholar?cites=42"));
+/// assert!(robots.is_allowed("my-crawler/0.1", "/scholar?q=quantum+theory"));
+/// assert!(robots.is_allowed("my-crawler/0.1", "/citations?user=foo"));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RobotsTxt {
+    groups: Vec<Group>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+struct Group {
+    /// Lowercase names of user agents.
+    agents: Vec<String>,
+    rules: Vec<Rule>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Rule {
+    allow: bool,
+    path: String,
+}
+
+impl RobotsTxt {
+    /// Parse `text` of a robots.txt, ignoring lines not understood.
+    ///
+    /// An empty `text`, e.g. for a robots.txt not found, allows everything.
+    pub fn parse(text: &str) -> Self {
+        let mut groups: Vec<Group> = Vec::new();
+        // Whether the last line is `User-agent`, so that consecutive ones share a group
+        let mut in_agents = false;
+
+        for line in text.lines() {
+            let line = line.split('#').nth(0).unwrap_or("").trim();
+            let mut key_value = line.splitn(2, ':');
+            let (key, value) = match (key_value.next(), key_value.next()) {
+                (Some(key), Some(value)) => (key.trim().to_lowercase(), value.trim()),
+                _ => continue,
+            };
+
+            match key.as_str() {
+                "user-agent" => {
+                    if !in_agents {
+                        groups.push(Group::default());
+                    }
+                    in_agents = true;
+                    if let Some(group) = groups.last_mut() {
+                        group.agents.push(value.to_lowercase());
+                    }
+                }
+                "allow" | "disallow" => {
+                    in_agents = false;
+                    // An empty `Disallow` allows everything, as no rule does
+                    if value.is_empty() {
+                        continue;
+                    }
+                    if let Some(group) = groups.last_mut() {
+                        group.rules.push(Rule {
+                            allow: key == "allow",
+                            path: value.to_owned(),
+                        });
+                    }
+                }
+                _ => in_agents = false,
+            }
+        }
+
+        Self { groups }
+    }
+
+    /// Whether a crawler of `user_agent` may fetch `path`, which may include the query.
+    pub fn is_allowed(&self, user_agent: &str, path: &str) -> bool {
+        let group = match self.group(&user_agent.to_lowercase()) {
+            Some(group) => group,
+            None => return true,
+        };
+
+        let mut best: Option<&Rule> = None;
+        for rule in group.rules.iter().filter(|r| path_matches(&r.path, path)) {
+            let better = match best {
+                None => true,
+                Some(b) => {
+                    rule.path.len() > b.path.len()
+                        || (rule.path.len() == b.path.len() && rule.allow)
+                }
+            };
+            if better {
+                best = Some(rule);
+            }
+        }
+        best.map_or(true, |rule| rule.allow)
+    }
+
+    /// The group applying to lowercase `user_agent`.
+    fn group(&self, user_agent: &str) -> Option<&Group> {
+        let mut best: Option<(usize, &Group)> = None;
+        for group in &self.groups {
+            for agent in &group.agents {
+                if agent != "*" && user_agent.contains(agent.as_str())
+                    && best.map_or(true, |(len, _)| agent.len() > len)
+                {
+                    best = Some((agent.len(), group));
+                }
+            }
+        }
+        best.map(|(_, group)| group).or_else(|| {
+            self.groups
+                .iter()
+                .find(|group| group.agents.iter().any(|agent| agent == "*"))
+        })
+    }
+}
+
+/// Whether `path` starts with `pattern`, in which `*` matches any string
+/// and a trailing `$` matches the end of `path`.
+fn path_matches(pattern: &str, path: &str) -> bool {
+    if pattern.is_empty() {
+        return true;
+    }
+    if pattern == "$" {
+        return path.is_empty();
+    }
+
+    match pattern.find('*') {
+        Some(i) => {
+            let (prefix, rest) = pattern.split_at(i);
+            let rest = &rest[1..];
+            path.starts_with(prefix)
+                && (prefix.len()..path.len() + 1)
+                    .filter(|&j| path.is_char_boundary(j))
+                    .any(|j| path_matches(rest, &path[j..]))
+        }
+        None => {
+            if pattern.ends_with('$') {
+                let (prefix, _) = pattern.split_at(pattern.len() - 1);
+                path == prefix
+            } else {
+                path.starts_with(pattern)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn robots_txt_test() {
+        let robots = RobotsTxt::parse(
+            "# comment\n\
+             User-agent: *\n\
+             Disallow: /scholar\n\
+             Allow: /scholar?q= # search\n\
+             Disallow: /citations?*hl=\n\
+             \n\
+             User-agent: my-crawler\n\
+             User-agent: other-crawler\n\
+             Disallow:\n\
+             \n\
+             User-agent: my-crawler-beta\n\
+             Disallow: /\n\
+             Allow: /scholar$\n",
+        );
+
+        assert!(!robots.is_allowed("Mozilla/5.0", "/scholar?cites=42"));
+        assert!(robots.is_allowed("Mozilla/5.0", "/scholar?q=foo"));
+        assert!(!robots.is_allowed("Mozilla/5.0", "/citations?user=foo&hl=en"));
+        assert!(robots.is_allowed("Mozilla/5.0", "/citations?user=foo"));
+
+        // An empty `Disallow` allows everything
+        assert!(robots.is_allowed("My-Crawler/0.1", "/scholar?cites=42"));
+        assert!(robots.is_allowed("other-crawler", "/citations?user=foo&hl=en"));
+
+        // The longest user agent name wins
+        assert!(!robots.is_allowed("my-crawler-beta/0.1", "/scholar?q=foo"));
+        assert!(robots.is_allowed("my-crawler-beta/0.1", "/scholar"));
+
+        assert!(RobotsTxt::parse("").is_allowed("Mozilla/5.0", "/scholar"));
+    }
+
+    #[test]
+    fn path_matches_test() {
+        assert!(path_matches("/scholar", "/scholar?q=foo"));
+        assert!(!path_matches("/scholar", "/citations"));
+        assert!(path_matches("/scholar$", "/scholar"));
+        assert!(!path_matches("/scholar$", "/scholar?q=foo"));
+        assert!(path_matches("/*?q=", "/scholar?q=foo"));
+        assert!(path_matches("/*.pdf$", "/files/foo.pdf"));
+        assert!(!path_matches("/*.pdf$", "/files/foo.pdf?dl=1"));
+        assert!(path_matches("*", "/"));
+    }
+}
@@ -0,0 +1,13 @@
+//! `SavedSearch` struct.
+
+/// A search query saved by a signed-in user, e.g. as an alert.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-derive", derive(Serialize))]
+pub struct SavedSearch {
+    /// ID of the saved search, found in its edit and cancel links.
+    pub id: String,
+    /// Saved query, e.g. "quantum theory".
+    pub query: String,
+    /// Label given by the user.
+    pub label: Option<String>,
+}
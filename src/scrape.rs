@@ -1,61 +1,378 @@
 //! Scrape HTML document to get information of papers.
 
+use std::collections::BTreeMap;
+use std::fmt;
 use std::io;
 use std::ops::Deref;
+use std::time::Duration;
 
 use select::document::Document;
 use select::node::Node;
-use select::predicate::{Attr, Class, Name, Predicate, Text};
+use select::predicate::{Attr, Class, Name, Not, Predicate, Text};
 
-use paper::Paper;
+use case_law::CaseLaw;
+use cite::{ExportLink, FormattedCitation};
+use id::{parse_arxiv_id, parse_doi, parse_id_from_url, ClusterId};
+use library::{LibraryArticle, LibraryLabel};
+use metrics::{self, AuthorMetrics, VenueMetrics};
+use paper::{Access, CitationCount, DocType, Paper};
+use profile::{AuthorProfile, AuthorSummary, Coauthor, ProfileMetric};
+use request::SortOrder;
+use saved_search::SavedSearch;
+use selector::SelectorProfile;
+use settings::{BibliographyManager, LibraryLink, Settings};
+use text::TextCleaner;
 use errors::*;
 
 pub trait PapersDocument {
     /// Scrape listed papers.
+    ///
+    /// Scraping does not mutate the document,
+    /// so calling this multiple times on the same document yields identical results.
+    ///
+    /// Returns `ErrorKind::Blocked` if Google Scholar blocked your request (see `is_blocked()`),
+    /// and `ErrorKind::NoResultsContainer` if the page has no container of results,
+    /// rather than an empty `Vec` which means no paper is found.
     fn scrape_papers(&self) -> Result<Vec<Paper>>;
 
-    /// Determine whether Google Scholar blocked your request.
-    fn is_blocked(&self) -> bool;
+    /// Determine whether Google Scholar blocked your request,
+    /// showing "unusual traffic" page or CAPTCHA instead of the result.
+    fn is_blocked(&self) -> bool {
+        self.block_kind().is_some()
+    }
+
+    /// Determine how Google Scholar blocked your request, if it did.
+    fn block_kind(&self) -> Option<BlockKind>;
+}
+
+/// Options of scraping documents.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ScrapeOptions {
+    /// How to clean up text scraped from documents.
+    pub text_cleaner: TextCleaner,
+    /// Collapse papers sharing a cluster ID on a single page, keeping the higher-ranked one.
+    ///
+    /// This only looks at one page at a time.
+    /// Duplicates across pages are left for callers to handle.
+    pub dedup_within_page: bool,
+    /// Keep the raw HTML of each paper in `Paper::raw_html`.
+    ///
+    /// This is useful to re-parse papers later, but costs memory.
+    pub keep_raw_html: bool,
+    /// IDs and class names of elements to scrape, which may be patched
+    /// when Google Scholar changes its markup.
+    pub selectors: SelectorProfile,
 }
 
 impl PapersDocument for Document {
     fn scrape_papers(&self) -> Result<Vec<Paper>> {
-        // <div id="gs_res_ccl_mid">
-        //   <div class="gs_ri">
-        //     each paper
-        //   </div>
-        //   <div class="gs_ri">
-        //     each paper
-        //   </div>
-        //   ...
-        // </div>
+        scrape_papers(self, &ScrapeOptions::default())
+    }
 
-        let paper_nodes = {
-            let pos = Attr("id", "gs_res_ccl_mid").descendant(Class("gs_ri"));
-            self.find(pos)
-        };
+    fn block_kind(&self) -> Option<BlockKind> {
+        block_kind(self)
+    }
+}
+
+fn scrape_papers(doc: &Document, options: &ScrapeOptions) -> Result<Vec<Paper>> {
+    papers(doc, options).collect()
+}
+
+fn papers<'a>(
+    doc: &'a Document,
+    options: &'a ScrapeOptions,
+) -> impl Iterator<Item = Result<Paper>> + 'a {
+    // A blocked page, or a page without the container yields the error only.
+    let error = page_error(doc, &options.selectors);
+    let failed = error.is_some();
+
+    let paper_nodes = layout_paper_nodes(doc, &options.selectors).filter(move |_| !failed);
+    error
+        .map(|e| Err(e.into()))
+        .into_iter()
+        .chain(paper_node_results(paper_nodes, options))
+}
+
+fn scrape_papers_with<T, F>(
+    doc: &Document,
+    options: &ScrapeOptions,
+    mut extract: F,
+) -> Result<Vec<(Paper, T)>>
+where
+    F: FnMut(&Paper, &Node) -> T,
+{
+    if let Some(e) = page_error(doc, &options.selectors) {
+        return Err(e.into());
+    }
+
+    let paper_nodes = layout_paper_nodes(doc, &options.selectors);
+    paper_node_pairs(paper_nodes, options)
+        .map(|result| {
+            let (paper, node) = result?;
+            let extra = extract(&paper, &result_block(&node, &options.selectors));
+            Ok((paper, extra))
+        })
+        .collect()
+}
+
+fn scrape_papers_lossy(
+    doc: &Document,
+    options: &ScrapeOptions,
+) -> Result<(Vec<Paper>, Vec<Error>)> {
+    if let Some(e) = page_error(doc, &options.selectors) {
+        return Err(e.into());
+    }
+
+    let mut scraped = Vec::new();
+    let mut errors = Vec::new();
+    for result in papers(doc, options) {
+        match result {
+            Ok(paper) => scraped.push(paper),
+            Err(e) => {
+                warn!("Skipped a paper failed to be scraped: {}", e);
+                errors.push(e);
+            }
+        }
+    }
+    Ok((scraped, errors))
+}
+
+/// HTML layout of a page listing papers.
+///
+/// Google Scholar serves different markup to the mobile site and to older browsers,
+/// which is scraped into the same `Paper`s with alternate selectors.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Layout {
+    /// Layout of the desktop site.
+    Desktop,
+    /// Layout of the mobile site `m.scholar.google.com`, also served to older browsers.
+    Mobile,
+}
 
-        let mut papers = Vec::with_capacity(10);
-        for n in paper_nodes {
-            papers.push(scrape_paper_one(&n)?);
+impl Layout {
+    /// ID of the container of results.
+    fn container_id(self, selectors: &SelectorProfile) -> &str {
+        match self {
+            Layout::Desktop => &selectors.container_id_desktop,
+            Layout::Mobile => &selectors.container_id_mobile,
         }
+    }
 
-        Ok(papers)
+    /// Class of each paper in the container.
+    fn paper_class(self, selectors: &SelectorProfile) -> &str {
+        match self {
+            Layout::Desktop => &selectors.paper_class_desktop,
+            Layout::Mobile => &selectors.paper_class_mobile,
+        }
     }
+}
 
-    fn is_blocked(&self) -> bool {
-        let pos = Name("div").child(Name("div")).child(Text);
-        let blocked_node = self.find(pos).filter(|n: &Node| {
-            n.as_text()
-                .and_then(|s| {
-                    Some(s.contains(
-                        "Our systems have detected unusual traffic from your computer network.",
-                    ))
-                })
-                .unwrap_or(false)
-        });
-        blocked_node.count() > 0
+/// Detect the layout of `doc` by its container of results,
+/// returning `None` if it has no container.
+///
+/// The containers are looked for with the default `SelectorProfile`.
+pub fn detect_layout(doc: &Document) -> Option<Layout> {
+    layout_with(doc, &SelectorProfile::default())
+}
+
+/// `detect_layout()` with `selectors`.
+fn layout_with(doc: &Document, selectors: &SelectorProfile) -> Option<Layout> {
+    // Desktop:
+    //
+    // <div id="gs_res_ccl_mid">
+    //   <div class="gs_r gs_or gs_scl" data-rp="0">
+    //     <div class="gs_ggs gs_fl">full text links</div>
+    //     <div class="gs_ri">
+    //       each paper
+    //     </div>
+    //   </div>
+    //   ...
+    // </div>
+    //
+    // Mobile, without the wrapper of each paper:
+    //
+    // <div id="gs_ccl">
+    //   <div class="gs_r" data-rp="0">
+    //     each paper, and full text links
+    //   </div>
+    //   ...
+    // </div>
+
+    [Layout::Desktop, Layout::Mobile]
+        .iter()
+        .cloned()
+        .find(|layout| {
+            doc.find(Attr("id", layout.container_id(selectors)))
+                .next()
+                .is_some()
+        })
+}
+
+/// Nodes of listed papers in `doc`, selected for its layout.
+fn layout_paper_nodes<'a>(
+    doc: &'a Document,
+    selectors: &'a SelectorProfile,
+) -> impl Iterator<Item = Node<'a>> + 'a {
+    let layout = layout_with(doc, selectors).unwrap_or(Layout::Desktop);
+    let pos = Attr("id", layout.container_id(selectors))
+        .descendant(Class(layout.paper_class(selectors)));
+    doc.find(pos)
+}
+
+/// Block of the result `node` of a paper, holding attributes like `data-rp`
+/// and full text links as well as the paper.
+fn result_block<'a>(node: &Node<'a>, selectors: &SelectorProfile) -> Node<'a> {
+    if Class(selectors.paper_class_mobile.as_str()).matches(node) {
+        // Mobile layout
+        *node
+    } else {
+        node.parent().unwrap_or(*node)
+    }
+}
+
+/// Footer of a paper, excluding full text links beside it,
+/// which share the footer class in the mobile layout.
+fn footer<'a>(selectors: &'a SelectorProfile) -> impl Predicate + 'a {
+    Class(selectors.footer_class.as_str()).and(Not(Class(selectors.full_text_class.as_str())))
+}
+
+/// Error of the whole page listing papers, if any.
+fn page_error(doc: &Document, selectors: &SelectorProfile) -> Option<ErrorKind> {
+    // The container holds no paper if the search matches nothing.
+    if let Some(kind) = block_kind(doc) {
+        Some(ErrorKind::Blocked(kind))
+    } else if layout_with(doc, selectors).is_none() {
+        Some(ErrorKind::NoResultsContainer)
+    } else {
+        None
+    }
+}
+
+fn scrape_paper_nodes<'a, I>(paper_nodes: I, options: &'a ScrapeOptions) -> Result<Vec<Paper>>
+where
+    I: Iterator<Item = Node<'a>> + 'a,
+{
+    paper_node_results(paper_nodes, options).collect()
+}
+
+fn paper_node_results<'a, I>(
+    paper_nodes: I,
+    options: &'a ScrapeOptions,
+) -> impl Iterator<Item = Result<Paper>> + 'a
+where
+    I: Iterator<Item = Node<'a>> + 'a,
+{
+    paper_node_pairs(paper_nodes, options).map(|result| result.map(|(paper, _)| paper))
+}
+
+/// Papers scraped from `paper_nodes`, each with its node.
+fn paper_node_pairs<'a, I>(
+    paper_nodes: I,
+    options: &'a ScrapeOptions,
+) -> impl Iterator<Item = Result<(Paper, Node<'a>)>> + 'a
+where
+    I: Iterator<Item = Node<'a>> + 'a,
+{
+    // Cluster IDs of papers yielded so far, used only if `dedup_within_page` is set
+    let mut cluster_ids = Vec::with_capacity(10);
+
+    paper_nodes
+        .enumerate()
+        .map(move |(i, n)| {
+            let mut paper = scrape_paper_one(&n, options)?;
+            paper.result_position = paper.result_position.or(Some(i as u32));
+            Ok((paper, n))
+        })
+        .filter(move |result: &Result<(Paper, Node)>| match *result {
+            Ok((ref paper, _)) if options.dedup_within_page => match paper.cluster_id {
+                Some(id) if cluster_ids.contains(&id) => false,
+                Some(id) => {
+                    cluster_ids.push(id);
+                    true
+                }
+                None => true,
+            },
+            _ => true,
+        })
+}
+
+/// Kind of the page Google Scholar shows instead of the result when it blocks your request.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BlockKind {
+    /// A CAPTCHA to be solved before continuing.
+    Captcha,
+    /// "Our systems have detected unusual traffic" notice without a CAPTCHA.
+    UnusualTraffic,
+}
+
+impl fmt::Display for BlockKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            BlockKind::Captcha => write!(f, "CAPTCHA"),
+            BlockKind::UnusualTraffic => write!(f, "unusual traffic notice"),
+        }
+    }
+}
+
+fn is_blocked(doc: &Document) -> bool {
+    block_kind(doc).is_some()
+}
+
+fn block_kind(doc: &Document) -> Option<BlockKind> {
+    // Either CAPTCHA form:
+    //
+    // <form action="/sorry/index"> ... </form>
+    // <form id="gs_captcha_f"> ... </form>
+    // <form id="captcha-form"> ... </form>
+    // <form action="index"><div class="g-recaptcha"></div></form>
+    //
+    // or "unusual traffic" page:
+    //
+    // <div>
+    //   <div>Our systems have detected unusual traffic from your computer network. ...</div>
+    // </div>
+    //
+    // The "unusual traffic" page may also have a CAPTCHA form.
+
+    let is_captcha_form = |n: &Node| {
+        n.attr("action").map_or(false, |a| a.contains("/sorry/"))
+            || n.attr("id").map_or(false, |id| id == "gs_captcha_f" || id == "captcha-form")
+            || n.find(Class("g-recaptcha")).next().is_some()
+    };
+    if doc.find(Name("form")).any(|n| is_captcha_form(&n)) {
+        return Some(BlockKind::Captcha);
+    }
+
+    let pos = Name("div").child(Name("div")).child(Text);
+    let mut blocked_nodes = doc.find(pos).filter(|n: &Node| {
+        n.as_text().map_or(false, |s| {
+            s.contains("Our systems have detected unusual traffic from your computer network.")
+        })
+    });
+    if blocked_nodes.next().is_some() {
+        return Some(BlockKind::UnusualTraffic);
+    }
+
+    None
+}
+
+fn is_scholar_page(doc: &Document) -> bool {
+    // Every page has containers whose ID or class starts with "gs_",
+    // except for the page shown when blocked.
+
+    fn is_scholar_container(n: &Node) -> bool {
+        n.attr("id").map_or(false, |id| id.starts_with("gs_"))
+            || n.attr("class")
+                .map_or(false, |c| c.split_whitespace().any(|c| c.starts_with("gs_")))
     }
+
+    let has_body = doc.find(Name("body")).next().is_some();
+    has_body && (doc.find(is_scholar_container).next().is_some() || is_blocked(doc))
+}
+
+/// HTML of the whole `doc`, to be reported when a section of the page is missing.
+fn document_html(doc: &Document) -> String {
+    doc.nth(0).map_or_else(String::new, |n| n.html())
 }
 
 macro_rules! impl_from_to_document {
@@ -71,39 +388,453 @@ macro_rules! impl_from_to_document {
         impl<'a> From<&'a str> for $struct {
             fn from(s: &str) -> Self {
                 let doc = Document::from(s);
-                $struct(doc)
+                Self::new(doc)
+            }
+        }
+
+        impl PapersDocument for $struct {
+            fn scrape_papers(&self) -> Result<Vec<Paper>> {
+                scrape_papers(&self.0, &self.1)
+            }
+
+            fn block_kind(&self) -> Option<BlockKind> {
+                block_kind(&self.0)
             }
         }
 
         impl $struct {
             pub fn new(doc: Document) -> Self {
-                $struct(doc)
+                $struct(doc, ScrapeOptions::default())
+            }
+
+            /// Detect the layout of this document. See `scrape::detect_layout()`.
+            /// Containers are looked for with `ScrapeOptions::selectors`.
+            pub fn layout(&self) -> Option<Layout> {
+                layout_with(&self.0, &self.1.selectors)
+            }
+
+            /// Lazily scrape listed papers one by one.
+            ///
+            /// Unlike `scrape_papers()`, a paper failed to be scraped does not abort the others,
+            /// and papers after the one you stop at are not scraped at all.
+            /// If Google Scholar blocked your request,
+            /// this yields `ErrorKind::Blocked` only.
+            pub fn papers<'a>(&'a self) -> impl Iterator<Item = Result<Paper>> + 'a {
+                papers(&self.0, &self.1)
+            }
+
+            /// Scrape listed papers, skipping ones failed to be scraped.
+            ///
+            /// Unlike `scrape_papers()`, a malformed result does not abort the whole page.
+            /// This returns the papers scraped, and errors of the others in the order listed.
+            /// Errors of the page itself, e.g. `ErrorKind::Blocked`, are still returned as `Err`.
+            pub fn scrape_papers_lossy(&self) -> Result<(Vec<Paper>, Vec<Error>)> {
+                scrape_papers_lossy(&self.0, &self.1)
             }
 
-            // like Document::from_read()
+            /// Scrape listed papers as `scrape_papers()` does, handing each paper with the node
+            /// of its result block to `extract`, to scrape extras this crate does not scrape,
+            /// e.g. badges of publishers. `Node::html()` gives the raw HTML of the result.
+            ///
+            /// Returns each paper with what `extract` returned for it, in the order listed.
+            /// Errors are reported as in `scrape_papers()`.
+            pub fn scrape_papers_with<T, F>(&self, extract: F) -> Result<Vec<(Paper, T)>>
+            where
+                F: FnMut(&Paper, &Node) -> T,
+            {
+                scrape_papers_with(&self.0, &self.1, extract)
+            }
+
+            /// Like `Document::from_read()`, but returns `ErrorKind::BadHtml`
+            /// if the document does not look like a page of Google Scholar.
             pub fn from_read<R: io::Read>(readable: R) -> Result<Self> {
                 let doc = Document::from_read(readable)?;
+                if !is_scholar_page(&doc) {
+                    return Err(ErrorKind::bad_html("page", &document_html(&doc)).into());
+                }
                 Ok(Self::new(doc))
             }
+
+            /// Like `from_read()`, but decompresses gzip-encoded `readable`.
+            #[cfg(feature = "gzip")]
+            pub fn from_read_gzip<R: io::Read>(readable: R) -> Result<Self> {
+                use flate2::read::GzDecoder;
+                Self::from_read(GzDecoder::new(readable))
+            }
+
+            /// Like `from_read()`, but decompresses `readable` if it starts with
+            /// the gzip magic bytes.
+            #[cfg(feature = "gzip")]
+            pub fn from_read_auto<R: io::Read>(readable: R) -> Result<Self> {
+                use std::io::BufRead;
+
+                let mut readable = io::BufReader::new(readable);
+                if readable.fill_buf()?.starts_with(&[0x1f, 0x8b]) {
+                    Self::from_read_gzip(readable)
+                } else {
+                    Self::from_read(readable)
+                }
+            }
+
+            /// Set options of scraping this document.
+            pub fn set_options(&mut self, options: ScrapeOptions) {
+                self.1 = options;
+            }
+
+            pub fn get_options(&self) -> &ScrapeOptions {
+                &self.1
+            }
         }
     }
 }
 
+/// Unwrap `$a`, or return `ErrorKind::BadHtml` reporting `$section` with a snippet of `$html`,
+/// which is evaluated only on failure.
 macro_rules! try_html_bad {
-    ($a: expr) => { $a.ok_or(ErrorKind::BadHtml)? }
+    ($a: expr, $section: expr, $html: expr) => {
+        $a.ok_or_else(|| ErrorKind::bad_html($section, $html))?
+    }
 }
 
 macro_rules! try_html_found {
     ($a: expr) => { $a.ok_or(ErrorKind::ResultNotFount)? }
 }
 
-pub struct SearchDocument(Document);
+/// Index searched by a search page, selected with the "Articles" / "Case law" toggle.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SearchScope {
+    Articles,
+    CaseLaw,
+    /// The page does not indicate the scope.
+    Unknown,
+}
+
+impl Default for SearchScope {
+    fn default() -> Self {
+        SearchScope::Unknown
+    }
+}
+
+/// Summary of search results shown above them, scraped by `SearchDocument::scrape_result_stats()`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ResultStats {
+    /// Total number of results, which Google Scholar may estimate.
+    pub total_count: u64,
+    /// Whether `total_count` is an estimate, e.g. "About 1,960,000 results".
+    pub approximate: bool,
+    /// Time the search took, if shown.
+    pub query_time: Option<Duration>,
+}
+
+pub struct SearchDocument(Document, ScrapeOptions);
 impl_from_to_document!(SearchDocument);
 
-pub struct CitationDocument(Document);
+impl SearchDocument {
+    /// Scrape the range of results listed in this page,
+    /// e.g. `Some((11, 20))` for "Showing results 11–20 of about 1,960,000".
+    ///
+    /// Returns `None` if the page does not indicate the range (e.g. single-page results).
+    pub fn scrape_result_range(&self) -> Option<(u32, u32)> {
+        // <div id="gs_ab_md">
+        //   <div class="gs_ab_mdw">
+        //     Showing results 11–20 of about 1,960,000 (<b>0.05</b> sec)
+        //   </div>
+        // </div>
+
+        let header_node = self.result_header_node()?;
+        parse_result_range(&header_node.text()).ok()
+    }
+
+    /// Scrape the total number of results,
+    /// e.g. `Some(1960000)` for "About 1,960,000 results (0.05 sec)".
+    ///
+    /// Returns `Ok(None)` if the page has no header of results,
+    /// and `ErrorKind::BadHtml` if the header has no number.
+    pub fn result_count(&self) -> Result<Option<u64>> {
+        // Same header as `scrape_result_range()`:
+        //
+        // <div id="gs_ab_md">
+        //   <div class="gs_ab_mdw">
+        //     About 1,960,000 results (<b>0.05</b> sec)
+        //   </div>
+        // </div>
+
+        match self.result_header_node() {
+            Some(n) => parse_result_count(&n.text()).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Scrape the total number of results and the time the search took,
+    /// e.g. about 1,960,000 results in 0.05 seconds for "About 1,960,000 results (0.05 sec)".
+    ///
+    /// Returns `Ok(None)` if the page has no header of results,
+    /// and `ErrorKind::BadHtml` if the header has no number.
+    pub fn scrape_result_stats(&self) -> Result<Option<ResultStats>> {
+        // Same header as `scrape_result_range()`:
+        //
+        // <div id="gs_ab_md">
+        //   <div class="gs_ab_mdw">
+        //     About 1,960,000 results (<b>0.05</b> sec)
+        //   </div>
+        // </div>
+
+        let text = match self.result_header_node() {
+            Some(n) => n.text(),
+            None => return Ok(None),
+        };
+        Ok(Some(ResultStats {
+            total_count: parse_result_count(&text)?,
+            approximate: is_approximate_result_count(&text),
+            query_time: parse_query_time(&text),
+        }))
+    }
+
+    fn result_header_node<'a>(&'a self) -> Option<Node<'a>> {
+        let pos = Attr("id", "gs_ab_md").child(Class("gs_ab_mdw"));
+        self.find(pos).nth(0)
+    }
+
+    /// Scrape the URL of the next page from the pagination bar, as written in the page.
+    ///
+    /// The URL is usually relative, e.g. `/scholar?start=10&q=quantum+theory`,
+    /// so that callers can choose the base URL.
+    /// Returns `None` on the last page, or if the page has no pagination bar.
+    pub fn next_page_url(&self) -> Option<String> {
+        next_page_url(&self.0)
+    }
+
+    /// Scrape `start` offsets of pages linked from the numbered pagination bar,
+    /// in ascending order, e.g. `[10, 20, ..., 90]` on the first page.
+    ///
+    /// The current page is not linked, so its offset is not included.
+    /// Returns an empty `Vec` if the page has no pagination bar.
+    pub fn scrape_pagination_offsets(&self) -> Vec<u32> {
+        pagination_offsets(&self.0)
+    }
+
+    /// Scrape the query echoed in the search box of this page.
+    ///
+    /// Returns `None` if the search box is not found.
+    pub fn scrape_query(&self) -> Option<String> {
+        // <input name="q" value="quantum theory" id="gs_hdr_tsi" ...>
+
+        let pos = Name("input").and(Attr("id", "gs_hdr_tsi"));
+        self.find(pos)
+            .nth(0)
+            .and_then(|n| n.attr("value"))
+            .map(ToOwned::to_owned)
+    }
+
+    /// Determine whether this page is a result of `expected` query.
+    ///
+    /// Both the query echoed in the page (see `scrape_query()`) and `expected` are normalized
+    /// before comparison: leading and trailing whitespaces are trimmed, letters are lowercased,
+    /// and each run of whitespaces is collapsed into one space.
+    /// Returns `false` if the page does not echo any query.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use scholar::scrape::SearchDocument;
+    ///
+    /// let doc = SearchDocument::from(r#"<input name="q" value="quantum theory" id="gs_hdr_tsi">"#);
+    /// assert!(doc.matches_query("  Quantum   Theory "));
+    /// assert!(!doc.matches_query("quantum"));
+    /// ```
+    pub fn matches_query(&self, expected: &str) -> bool {
+        fn normalize(query: &str) -> String {
+            query
+                .split_whitespace()
+                .map(str::to_lowercase)
+                .collect::<Vec<_>>()
+                .join(" ")
+        }
+
+        match self.scrape_query() {
+            Some(query) => normalize(&query) == normalize(expected),
+            None => false,
+        }
+    }
+
+    /// Scrape queries suggested in "Related searches" below the results.
+    ///
+    /// Returns an empty `Vec` if no related searches are shown.
+    pub fn scrape_related_searches(&self) -> Vec<String> {
+        // <div id="gs_qsuggest">
+        //   <h2>Related searches</h2>
+        //   <ul>
+        //     <li><a href="/scholar?q=radiation+quantum+theory"><b>radiation </b>quantum theory</a></li>
+        //     ...
+        //   </ul>
+        // </div>
+
+        let pos = Attr("id", "gs_qsuggest").descendant(Name("li").child(Name("a")));
+        self.find(pos)
+            .map(|n| self.1.text_cleaner.clean(&n.text()))
+            .filter(|q| !q.is_empty())
+            .collect()
+    }
+
+    /// Scrape papers from every result block in this document, preserving order.
+    ///
+    /// This is for a file in which several saved pages are concatenated.
+    /// Unlike `scrape_papers()`, each result block is treated as a separate page,
+    /// so `ScrapeOptions::dedup_within_page` and fallback result positions apply per block.
+    pub fn scrape_all_result_blocks(&self) -> Result<Vec<Paper>> {
+        // <div id="gs_res_ccl_mid">
+        //   papers in page 1
+        // </div>
+        // ...
+        // <div id="gs_res_ccl_mid">
+        //   papers in page 2
+        // </div>
+
+        if let Some(kind) = self.block_kind() {
+            return Err(ErrorKind::Blocked(kind).into());
+        }
+
+        let selectors = &self.1.selectors;
+        let blocks = self.find(Attr("id", selectors.container_id_desktop.as_str()))
+            .collect::<Vec<_>>();
+        if blocks.is_empty() {
+            return Err(ErrorKind::NoResultsContainer.into());
+        }
+
+        let mut papers = Vec::new();
+        for block in blocks {
+            let paper_nodes = block.find(Class(selectors.paper_class_desktop.as_str()));
+            papers.extend(scrape_paper_nodes(paper_nodes, &self.1)?);
+        }
+
+        Ok(papers)
+    }
+
+    /// Scrape the notice shown when Google Scholar omitted results very similar to
+    /// the ones already displayed, e.g. "In order to show you the most relevant results,
+    /// we have omitted some entries ...".
+    ///
+    /// Returns `None` if no results are omitted.
+    /// See also `scrape_omitted_results_url()`.
+    pub fn scrape_omitted_results_notice(&self) -> Option<String> {
+        // <div id="gs_res_ccl_bot">
+        //   <div class="gs_r">
+        //     <p>
+        //       In order to show you the most relevant results, we have omitted some entries ...
+        //       <a href="/scholar?q=quantum+theory&filter=0">
+        //         repeat the search with the omitted results included
+        //       </a>.
+        //     </p>
+        //   </div>
+        // </div>
+
+        let notice = self.omitted_results_node()
+            .map(|n| self.1.text_cleaner.clean(&n.text()))?;
+        if notice.contains("omitted") {
+            Some(notice)
+        } else {
+            None
+        }
+    }
+
+    /// Scrape URL to repeat the search with the omitted results included.
+    ///
+    /// Returns `None` if no results are omitted.
+    /// See also `scrape_omitted_results_notice()`.
+    pub fn scrape_omitted_results_url(&self) -> Option<String> {
+        self.scrape_omitted_results_notice()?;
+
+        self.omitted_results_node()?
+            .find(Name("a"))
+            .filter_map(|n| n.attr("href"))
+            .find(|url| url.contains("filter=0"))
+            .map(ToOwned::to_owned)
+    }
+
+    fn omitted_results_node<'a>(&'a self) -> Option<Node<'a>> {
+        let pos = Attr("id", "gs_res_ccl_bot").descendant(Name("p"));
+        self.find(pos).nth(0)
+    }
+
+    /// Scrape which index this page searched, articles or case law.
+    ///
+    /// Returns `SearchScope::Unknown` if the scope is indeterminate.
+    pub fn scrape_search_scope(&self) -> SearchScope {
+        // <form id="gs_hdr_frm" action="/scholar">
+        //   <input name="as_sdt" value="0,5" type="hidden">
+        //   ...
+        // </form>
+
+        let pos = Attr("id", "gs_hdr_frm").descendant(Name("input").and(Attr("name", "as_sdt")));
+        self.find(pos)
+            .nth(0)
+            .and_then(|n| n.attr("value"))
+            .map(parse_search_scope)
+            .unwrap_or_default()
+    }
+
+    /// Scrape the order of results, echoed in the search form of this page.
+    ///
+    /// Returns `SortOrder::Relevance` if the form is not found.
+    pub fn scrape_sort_order(&self) -> SortOrder {
+        // <form id="gs_hdr_frm" action="/scholar">
+        //   <input name="scisbd" value="1" type="hidden">
+        //   ...
+        // </form>
+        //
+        // Results sorted by relevance have no `scisbd`.
+
+        match self.search_form_value("scisbd") {
+            Some(ref value) if value != "0" => SortOrder::Date,
+            _ => SortOrder::Relevance,
+        }
+    }
+
+    /// Scrape the range of publication years results are restricted to,
+    /// echoed in the search form of this page, e.g. `(Some(2017), None)` for "Since 2017".
+    ///
+    /// `None` means the bound is open, as in `ScholarQuery::year_range()`.
+    pub fn scrape_year_range(&self) -> (Option<u32>, Option<u32>) {
+        // <form id="gs_hdr_frm" action="/scholar">
+        //   <input name="as_ylo" value="2017" type="hidden">
+        //   <input name="as_yhi" value="2018" type="hidden">
+        //   ...
+        // </form>
+
+        let year = |name| self.search_form_value(name).and_then(|y| y.parse().ok());
+        (year("as_ylo"), year("as_yhi"))
+    }
+
+    fn search_form_value(&self, name: &str) -> Option<String> {
+        let pos = Attr("id", "gs_hdr_frm").descendant(Name("input").and(Attr("name", name)));
+        self.find(pos)
+            .nth(0)
+            .and_then(|n| n.attr("value"))
+            .map(|value| value.trim().to_owned())
+    }
+}
+
+pub struct CitationDocument(Document, ScrapeOptions);
 impl_from_to_document!(CitationDocument);
 
 impl CitationDocument {
+    /// Scrape the URL of the next page of citers. See `SearchDocument::next_page_url()`.
+    pub fn next_page_url(&self) -> Option<String> {
+        next_page_url(&self.0)
+    }
+
+    /// Scrape the `start` offset of the next page of citers,
+    /// to be passed to `ScholarQuery::start()`, or `None` on the last page.
+    pub fn next_page_start(&self) -> Option<u32> {
+        self.next_page_url().and_then(|url| parse_start_offset(&url))
+    }
+
+    /// Scrape `start` offsets of pages of citers linked from the pagination bar.
+    /// See `SearchDocument::scrape_pagination_offsets()`.
+    pub fn scrape_pagination_offsets(&self) -> Vec<u32> {
+        pagination_offsets(&self.0)
+    }
+
     pub fn scrape_target_paper_with_citers(&self) -> Result<Paper> {
         let target_paper = self.scrape_target_paper()?;
         let citers = self.scrape_papers()?;
@@ -120,8 +851,13 @@ impl CitationDocument {
         //       title
         //     </a>
         //   </h2>
+        //   <div class="gs_a">
+        //     author - journal etc., year - journal etc.
+        //   </div>
         //   something
         // </div>
+        //
+        // 'gs_a' may not exist.
 
         let target_paper_node = {
             let pos = Attr("id", "gs_rt_hdr")
@@ -130,354 +866,4036 @@ impl CitationDocument {
             try_html_found!(self.find(pos).nth(0))
         };
 
-        let title = target_paper_node.text();
+        let title = self.1.text_cleaner.node_text(&target_paper_node);
         let cluster_id = {
-            let id_url = try_html_bad!(target_paper_node.attr("href"));
+            let id_url = try_html_bad!(
+                target_paper_node.attr("href"),
+                "target paper link",
+                &target_paper_node.html()
+            );
             parse_cluster_id(id_url)?
         };
 
-        Ok(Paper::new(&title, cluster_id))
+        let mut paper = Paper::new(&title, cluster_id.as_u64());
+
+        let header_node = {
+            let pos = Attr("id", "gs_rt_hdr");
+            self.find(pos).nth(0)
+        };
+        if let Some(n) = header_node {
+            let ArticleHeader {
+                authors,
+                authors_truncated,
+                venue,
+                year,
+            } = scrape_article_header(&n, &self.1.text_cleaner, &self.1.selectors);
+            paper.authors = authors;
+            paper.authors_truncated = authors_truncated;
+            paper.venue = venue;
+            paper.year = year;
+        }
+
+        Ok(paper)
     }
 }
 
-pub struct ClusterDocument(Document);
+/// Papers related to a paper, fetched from `/scholar?q=related:ID:scholar.google.com/`.
+/// See `Paper::related_id()`.
+///
+/// The page lists papers in the same way as search results, which `scrape_papers()` scrapes.
+pub struct RelatedDocument(Document, ScrapeOptions);
+impl_from_to_document!(RelatedDocument);
+
+pub struct ClusterDocument(Document, ScrapeOptions);
 impl_from_to_document!(ClusterDocument);
 
 impl ClusterDocument {
     pub fn scrape_target_paper(&self) -> Result<Paper> {
-        let paper_node = {
-            let pos = Attr("id", "gs_res_ccl_mid").descendant(Class("gs_ri"));
-            try_html_found!(self.find(pos).nth(0))
+        let paper_node = try_html_found!(layout_paper_nodes(self, &self.1.selectors).nth(0));
+        let mut paper = scrape_paper_one(&paper_node, &self.1)?;
+        paper.result_position = paper.result_position.or(Some(0));
+        Ok(paper)
+    }
+
+    /// Scrape every version of the paper listed in this page, in the order shown.
+    ///
+    /// Versions are hosted at different sites, in different formats,
+    /// which are told by `Paper::link`, `Paper::pdf_link`, `Paper::access`, and `Paper::doc_type`.
+    /// Unlike `scrape_papers()`, versions are never collapsed
+    /// even if `ScrapeOptions::dedup_within_page` is set, since they often share a cluster ID.
+    pub fn scrape_versions(&self) -> Result<Vec<Paper>> {
+        let options = ScrapeOptions {
+            dedup_within_page: false,
+            ..self.1.clone()
         };
-        scrape_paper_one(&paper_node)
+        scrape_papers(&self.0, &options)
     }
 }
 
-struct ArticleTitle {
-    title: String,
-    link: Option<String>,
-}
+pub struct CaseLawDocument(Document, ScrapeOptions);
+impl_from_to_document!(CaseLawDocument);
 
-struct ArticleHeader {
-    year: Option<u32>,
-}
+impl CaseLawDocument {
+    /// Scrape listed legal documents (case law).
+    pub fn scrape_cases(&self) -> Result<Vec<CaseLaw>> {
+        // Same structure as papers:
+        //
+        // <div id="gs_res_ccl_mid">
+        //   <div class="gs_ri">
+        //     each case
+        //   </div>
+        //   ...
+        // </div>
 
-struct ArticleFooter {
-    cluster_id: u64,
-    citation_count: u32,
+        let case_nodes = layout_paper_nodes(self, &self.1.selectors);
+
+        let mut cases = Vec::with_capacity(10);
+        for n in case_nodes {
+            cases.push(scrape_case_one(&n, &self.1.text_cleaner, &self.1.selectors));
+        }
+
+        Ok(cases)
+    }
 }
 
-fn scrape_paper_one(node: &Node) -> Result<Paper> {
-    let ArticleTitle { title, link } = scrape_article_title(node);
-    let ArticleHeader { year } = scrape_article_header(node);
-    let ArticleFooter {
-        cluster_id,
-        citation_count,
-    } = scrape_article_footer(node)?;
+/// Page listing searches saved by a signed-in user.
+/// Fetching this page requires the user's cookies.
+pub struct SavedSearchesDocument(Document, ScrapeOptions);
+impl_from_to_document!(SavedSearchesDocument);
 
-    let mut paper = Paper::new(&title, cluster_id);
-    paper.link = link;
-    paper.year = year;
-    paper.citation_count = Some(citation_count);
+impl SavedSearchesDocument {
+    /// Scrape listed saved searches.
+    pub fn scrape_saved_searches(&self) -> Result<Vec<SavedSearch>> {
+        // <table id="gs_asl_t">
+        //   <tr>
+        //     <td class="gs_asl_q"><a href="/scholar?q=quantum+theory">quantum theory</a></td>
+        //     <td class="gs_asl_l">label</td>
+        //     <td class="gs_asl_a">
+        //       <a href="/scholar_alerts?view_op=edit_alert&alert_id=abc">Edit</a>
+        //       <a href="/scholar_alerts?view_op=cancel_alert&alert_id=abc">Cancel</a>
+        //     </td>
+        //   </tr>
+        //   ...
+        // </table>
+        //
+        // Label may be empty.
 
-    Ok(paper)
+        let row_nodes = {
+            let pos = Attr("id", "gs_asl_t").descendant(Name("tr"));
+            self.find(pos)
+        };
+
+        let cleaner = &self.1.text_cleaner;
+        let mut searches = Vec::new();
+        for n in row_nodes {
+            let query = {
+                let query_node = try_html_bad!(
+                    n.find(Class("gs_asl_q")).nth(0),
+                    "saved search query",
+                    &n.html()
+                );
+                cleaner.node_text(&query_node)
+            };
+            let label = n.find(Class("gs_asl_l"))
+                .nth(0)
+                .and_then(|l| non_empty(&cleaner.node_text(&l)));
+            let id = {
+                let pos = Class("gs_asl_a").descendant(Name("a"));
+                let id = n.find(pos)
+                    .filter_map(|a| a.attr("href"))
+                    .filter_map(|url| parse_alert_id(url).ok())
+                    .nth(0);
+                try_html_bad!(id, "saved search alert ID", &n.html())
+            };
+
+            searches.push(SavedSearch { id, query, label });
+        }
+
+        Ok(searches)
+    }
 }
 
-fn scrape_article_title(node: &Node) -> ArticleTitle {
-    // There are (at least) two formats.
-    //
-    // 1. Link to a paper or something:
-    //
-    // <h3 class="gs_rt">
-    //   <span>
-    //     something
-    //   </span>
-    //   <a href="http://paper.pdf">
-    //     title of paper or something
-    //   </a>
-    // </h3>
-    //
-    // 'span' may not exists.
-    //
-    // 2. Not a link:
-    //
-    // <h3 class="gs_rt">
-    //   <span>
-    //     something
-    //   </span>
-    //   title of paper or something
-    // </h3>
+/// Settings page of Google Scholar.
+/// Fetching settings of a signed-in user requires the user's cookies.
+pub struct SettingsDocument(Document, ScrapeOptions);
+impl_from_to_document!(SettingsDocument);
 
-    if let Some(n) = {
-        let pos = Class("gs_rt").child(Name("a"));
-        node.find(pos).nth(0)
-    } {
-        // 1. Link to a paper or something
-        ArticleTitle {
-            title: n.text(),
-            link: n.attr("href").map(ToOwned::to_owned),
+impl SettingsDocument {
+    /// Scrape current preferences.
+    pub fn scrape_settings(&self) -> Result<Settings> {
+        // <form id="gs_settings_form">
+        //   <select name="num">
+        //     <option value="10">10</option>
+        //     <option value="20" selected>20</option>
+        //   </select>
+        //
+        //   <input type="radio" name="scis" value="no">
+        //   <input type="radio" name="scis" value="yes" checked>
+        //   <select name="scisf">
+        //     <option value="4" selected>BibTeX</option>
+        //     ...
+        //   </select>
+        //
+        //   <label><input type="checkbox" name="inst" value="123" checked> library</label>
+        //   ...
+        // </form>
+
+        let form = try_html_bad!(
+            self.find(Attr("id", "gs_settings_form")).nth(0),
+            "settings form",
+            &document_html(self)
+        );
+
+        let results_per_page = selected_option(&form, "num").and_then(|n| n.parse().ok());
+
+        let bibliography_manager = {
+            let enabled = form.find(Attr("name", "scis"))
+                .any(|n| n.attr("checked").is_some() && n.attr("value") == Some("yes"));
+            if enabled {
+                selected_option(&form, "scisf").map(|f| match f {
+                    "1" => BibliographyManager::RefWorks,
+                    "2" => BibliographyManager::EndNote,
+                    "3" => BibliographyManager::RefMan,
+                    "4" => BibliographyManager::BibTeX,
+                    f => BibliographyManager::Other(f.to_owned()),
+                })
+            } else {
+                None
+            }
+        };
+
+        let cleaner = &self.1.text_cleaner;
+        let library_links = form.find(Name("input").and(Attr("name", "inst")))
+            .filter(|n| n.attr("checked").is_some())
+            .filter_map(|n| {
+                let id = n.attr("value")?.to_owned();
+                let name = cleaner.node_text(&n.parent()?);
+                Some(LibraryLink { id, name })
+            })
+            .collect();
+
+        Ok(Settings {
+            results_per_page,
+            bibliography_manager,
+            library_links,
+        })
+    }
+}
+
+/// Author profile page, e.g. `/citations?user=qc6CJjYAAAAJ`.
+pub struct ProfileDocument(Document, ScrapeOptions);
+impl_from_to_document!(ProfileDocument);
+
+impl ProfileDocument {
+    /// Whether the profile is private, showing a message instead of the author and articles.
+    ///
+    /// Scrapers return `ErrorKind::ProfilePrivate` for a private profile.
+    pub fn is_private(&self) -> bool {
+        // <div id="gs_alrt" class="gs_alrt">
+        //   <div id="gs_alrt_m">This profile is private.</div>
+        // </div>
+
+        self.find(Attr("id", "gsc_prf_in")).nth(0).is_none()
+            && self.find(Class("gs_alrt")).any(|n| {
+                let message = n.text().to_lowercase();
+                message.contains("private") || message.contains("not public")
+            })
+    }
+
+    /// Scrape the name of the author.
+    pub fn scrape_author_name(&self) -> Result<String> {
+        // <div id="gsc_prf_in">Michael Berry</div>
+
+        if self.is_private() {
+            bail!(ErrorKind::ProfilePrivate);
         }
-    } else {
-        // 2. Not a link
-        let children = {
-            let pos = Class("gs_rt");
-            node.find(pos).into_selection().children()
+        let name_node = try_html_bad!(
+            self.find(Attr("id", "gsc_prf_in")).nth(0),
+            "author name",
+            &document_html(self)
+        );
+        let name = self.1.text_cleaner.node_text(&name_node);
+        Ok(try_html_bad!(non_empty(&name), "author name", &name_node.html()))
+    }
+
+    /// Scrape the summary of the author, including the table of citation metrics.
+    pub fn scrape_profile(&self) -> Result<AuthorProfile> {
+        // <div id="gsc_prf_i">
+        //   <div id="gsc_prf_in">Michael Berry</div>
+        //   <div class="gsc_prf_il">Physics, <a class="gsc_prf_ila">University of Bristol</a></div>
+        //   <div class="gsc_prf_il" id="gsc_prf_ivh">
+        //     Verified email at bristol.ac.uk - <a class="gsc_prf_ila">Homepage</a>
+        //   </div>
+        //   (or "Verified email at <a href="http://www.bristol.ac.uk/">bristol.ac.uk</a>")
+        //   <div class="gsc_prf_il" id="gsc_prf_int">
+        //     <a class="gsc_prf_inta">Physics</a>
+        //     <a class="gsc_prf_inta">Optics</a>
+        //   </div>
+        // </div>
+        //
+        // Each line except the name may be missing.
+
+        let cleaner = &self.1.text_cleaner;
+        let name = self.scrape_author_name()?;
+
+        let affiliation = {
+            let pos = Attr("id", "gsc_prf_i").child(Class("gsc_prf_il"));
+            self.find(pos)
+                .find(|n| n.attr("id").is_none())
+                .and_then(|n| non_empty(&cleaner.node_text(&n)))
         };
-        let text_nodes = children.filter(|n: &Node| {
-            if let Some(name) = n.name() {
-                name != "span"
-            } else {
-                true
+        let verification_node = self.find(Attr("id", "gsc_prf_ivh")).nth(0);
+        let email_domain = verification_node
+            .as_ref()
+            .and_then(|n| parse_email_domain(&n.text()));
+        // Unlike the link to the homepage of the author, the link is labeled with the domain
+        let verified_institution_url = match (verification_node, email_domain.as_ref()) {
+            (Some(n), Some(domain)) => n.find(Name("a"))
+                .find(|a| a.text().trim() == domain)
+                .and_then(|a| a.attr("href"))
+                .map(scholar_absolute_url),
+            _ => None,
+        };
+        let interests = {
+            let pos = Attr("id", "gsc_prf_int").descendant(Class("gsc_prf_inta"));
+            self.find(pos)
+                .filter_map(|n| non_empty(&cleaner.node_text(&n)))
+                .collect()
+        };
+
+        let mut profile = AuthorProfile {
+            name,
+            affiliation,
+            email_domain,
+            verified_institution_url,
+            interests,
+            citations: None,
+            h_index: None,
+            i10_index: None,
+            recent_since: None,
+        };
+        self.scrape_metrics(&mut profile);
+
+        Ok(profile)
+    }
+
+    fn scrape_metrics(&self, profile: &mut AuthorProfile) {
+        // <table id="gsc_rsb_st">
+        //   <thead>
+        //     <tr><th></th><th>All</th><th>Since 2013</th></tr>
+        //   </thead>
+        //   <tbody>
+        //     <tr>
+        //       <td class="gsc_rsb_sc1"><a>Citations</a></td>
+        //       <td class="gsc_rsb_std">81,234</td>
+        //       <td class="gsc_rsb_std">20,123</td>
+        //     </tr>
+        //     ... h-index and i10-index
+        //   </tbody>
+        // </table>
+
+        fn parse_metric(text: &str) -> Option<u32> {
+            text.trim().replace(',', "").parse().ok()
+        }
+
+        profile.recent_since = self.find(Attr("id", "gsc_rsb_st").descendant(Name("th")))
+            .filter_map(|n| {
+                // "Since 2013"
+                let text = n.text();
+                let year = text.split_whitespace().last()?;
+                year.parse().ok()
+            })
+            .nth(0);
+
+        for row in self.find(Attr("id", "gsc_rsb_st").descendant(Name("tr"))) {
+            let label = match row.find(Class("gsc_rsb_sc1")).nth(0) {
+                Some(n) => n.text(),
+                None => continue,
+            };
+            let values = row.find(Class("gsc_rsb_std"))
+                .map(|n| parse_metric(&n.text()))
+                .collect::<Option<Vec<_>>>();
+            let metric = match values {
+                Some(ref v) if v.len() == 2 => ProfileMetric {
+                    all: v[0],
+                    recent: v[1],
+                },
+                _ => continue,
+            };
+
+            match label.trim() {
+                "Citations" => profile.citations = Some(metric),
+                "h-index" => profile.h_index = Some(metric),
+                "i10-index" => profile.i10_index = Some(metric),
+                _ => {}
             }
-        });
-        let concated_text = text_nodes
-            .into_iter()
-            .map(|n| n.text())
-            .collect::<String>()
-            .trim()
-            .to_string();
-        ArticleTitle {
-            title: concated_text,
-            link: None,
         }
     }
+
+    /// Scrape the absolute URL of the image of the citations per year chart,
+    /// whose query encodes the numbers in the chart.
+    ///
+    /// Returns `None` if the chart is not rendered, e.g. for authors not cited yet.
+    pub fn scrape_citation_chart_url(&self) -> Option<String> {
+        // <div id="gsc_g">
+        //   <img src="/citations?view_op=citations_chart&user=qc6CJjYAAAAJ&ys=2016&ye=2018&cd=...">
+        // </div>
+
+        self.find(Attr("id", "gsc_g").descendant(Name("img")))
+            .filter_map(|img| img.attr("src"))
+            .find(|src| !src.trim().is_empty())
+            .map(scholar_absolute_url)
+    }
+
+    /// Scrape co-authors listed in the sidebar, in the order shown.
+    pub fn scrape_coauthors(&self) -> Result<Vec<Coauthor>> {
+        // <ul class="gsc_rsb_a">
+        //   <li>
+        //     <div class="gsc_rsb_aa">
+        //       <span class="gsc_rsb_a_desc">
+        //         <a href="/citations?user=AbC-dEf_012J">Jonathan P Keating</a>
+        //         <span class="gsc_rsb_a_ext">Professor of Mathematics, Oxford</span>
+        //         <span class="gsc_rsb_a_ext gsc_rsb_a_ext2">Verified email at ox.ac.uk</span>
+        //         <span class="gsc_rsb_a_ext">Cited by 21,345</span>
+        //       </span>
+        //     </div>
+        //   </li>
+        //   ...
+        // </ul>
+        //
+        // Lines except the name may be missing.
+
+        let cleaner = &self.1.text_cleaner;
+        let mut coauthors = Vec::new();
+        for n in self.find(Class("gsc_rsb_a").descendant(Class("gsc_rsb_a_desc"))) {
+            let name_node = try_html_bad!(n.find(Name("a")).nth(0), "co-author name", &n.html());
+            let name = try_html_bad!(
+                non_empty(&cleaner.node_text(&name_node)),
+                "co-author name",
+                &n.html()
+            );
+            let user_id = parse_user_id(try_html_bad!(
+                name_node.attr("href"),
+                "co-author link",
+                &name_node.html()
+            ))?;
+
+            // The email line is of class `gsc_rsb_a_ext2` as well
+            let lines = n.find(Class("gsc_rsb_a_ext"))
+                .filter(|l| !l.is(Class("gsc_rsb_a_ext2")))
+                .filter_map(|l| non_empty(&cleaner.node_text(&l)))
+                .collect::<Vec<_>>();
+            let title = lines.first().cloned();
+            let citation_count = lines
+                .iter()
+                .skip(1)
+                .filter_map(|l| parse_citation_count(l).ok())
+                .map(|c| c.value)
+                .nth(0);
+
+            coauthors.push(Coauthor {
+                name,
+                user_id,
+                title,
+                citation_count,
+            });
+        }
+
+        Ok(coauthors)
+    }
+
+    /// Whether the author has more articles than listed in this page.
+    ///
+    /// If so, fetch the next page with `cstart` advanced by the number of listed articles;
+    /// see `request::profile_url()`.
+    pub fn has_more_articles(&self) -> bool {
+        // <button id="gsc_bpf_more">Show more</button>
+        //
+        // The button is disabled on the last page.
+
+        self.find(Attr("id", "gsc_bpf_more"))
+            .nth(0)
+            .map_or(false, |n| n.attr("disabled").is_none())
+    }
+
+    /// Scrape the number of articles of the author, to know how many pages to fetch
+    /// with `cstart` advanced; see `has_more_articles()`.
+    ///
+    /// Google Scholar shows the total along the range of listed articles, e.g. "1–20 of 345",
+    /// only sometimes, but the last article listed tells the total in the last page.
+    /// Returns `None` if neither is shown, i.e. only the listed articles are countable.
+    pub fn scrape_publication_count(&self) -> Option<u32> {
+        // <div id="gsc_a_sp">1&ndash;20 of 345</div>
+        //
+        // "of 345" may be missing.
+
+        use regex::Regex;
+
+        lazy_static! {
+            static ref RE: Regex = Regex::new(r"\d[\d,]*").unwrap();
+        }
+
+        let range = self.find(Attr("id", "gsc_a_sp")).nth(0)?.text();
+        let numbers = RE.find_iter(&range)
+            .map(|m| m.as_str().replace(',', "").parse::<u32>().ok())
+            .collect::<Option<Vec<_>>>()?;
+        match numbers.len() {
+            3 => Some(numbers[2]),
+            2 if !self.has_more_articles() => Some(numbers[1]),
+            _ => None,
+        }
+    }
+
+    /// Scrape articles listed in the profile.
+    ///
+    /// `Paper::profile_article_id` is set from the link to each article.
+    /// `Paper::cluster_id` is set from the "Cited by" link, which is missing if not cited yet.
+    pub fn scrape_articles(&self) -> Result<Vec<Paper>> {
+        // <tbody id="gsc_a_b">
+        //   <tr class="gsc_a_tr">
+        //     <td class="gsc_a_t">
+        //       <a href="/citations?view_op=view_citation&citation_for_view=USER:ARTICLE"
+        //          class="gsc_a_at">title</a>
+        //       <div class="gs_gray">authors</div>
+        //       <div class="gs_gray">venue<span class="gs_oph">, 1984</span></div>
+        //     </td>
+        //     <td class="gsc_a_c">
+        //       <a href="/scholar?oi=bibs&cites=000000" class="gsc_a_ac">999</a>
+        //     </td>
+        //     <td class="gsc_a_y"><span class="gsc_a_h">1984</span></td>
+        //   </tr>
+        //   ...
+        // </tbody>
+        //
+        // The article link may be in `data-href` instead, with `href` being "javascript:void(0)".
+
+        if self.is_private() {
+            bail!(ErrorKind::ProfilePrivate);
+        }
+        let cleaner = &self.1.text_cleaner;
+        let mut articles = Vec::new();
+        for n in self.find(Class("gsc_a_tr")) {
+            let title_node = try_html_bad!(
+                n.find(Class("gsc_a_at")).nth(0),
+                "profile article title",
+                &n.html()
+            );
+            let mut paper = Paper::new(&cleaner.node_text(&title_node), 0);
+
+            paper.profile_article_id = title_node
+                .attr("data-href")
+                .into_iter()
+                .chain(title_node.attr("href"))
+                .filter_map(|url| parse_citation_for_view(url).ok())
+                .nth(0);
+
+            let gray_nodes = n.find(Class("gs_gray")).collect::<Vec<_>>();
+            if let Some(authors) = gray_nodes.first() {
+                let (authors, truncated) = parse_truncated_authors(&cleaner.node_text(authors));
+                paper.authors = authors;
+                paper.authors_truncated = truncated;
+            }
+            paper.venue = gray_nodes.get(1).and_then(|v| {
+                let venue = v.children()
+                    .filter(|c| !c.is(Class("gs_oph")))
+                    .map(|c| cleaner.raw_text(&c))
+                    .collect::<String>();
+                non_empty(&cleaner.clean(&venue))
+            });
+
+            let cited_by_node = n.find(Class("gsc_a_ac")).nth(0);
+            paper.set_cluster_id(
+                cited_by_node
+                    .and_then(|c| c.attr("href"))
+                    .and_then(|url| parse_cites_id(url).ok()),
+            );
+            paper.citation_count = cited_by_node
+                .and_then(|c| c.text().trim().parse::<u32>().ok())
+                .map(Into::into);
+
+            paper.year = n.find(Class("gsc_a_y"))
+                .nth(0)
+                .and_then(|y| y.text().trim().parse().ok());
+
+            articles.push(paper);
+        }
+
+        Ok(articles)
+    }
+
+    /// Compute citation metrics from the articles listed in this page,
+    /// e.g. to check them against `scrape_profile()`. See `metrics::author_metrics()`.
+    ///
+    /// They equal the metrics shown over all years only if this page lists every article.
+    pub fn compute_metrics(&self) -> Result<AuthorMetrics> {
+        Ok(metrics::author_metrics(&self.scrape_articles()?))
+    }
 }
 
-fn scrape_article_header(node: &Node) -> ArticleHeader {
-    // There are (at least) two formats for publishment information:
-    //
-    // 1. with journal etc. at the third part:
-    //
-    // <div class="gs_a">
-    //   author - journal etc., year - journal etc.
+/// Page of an article in an author profile, linked from `ProfileDocument::scrape_articles()`,
+/// e.g. `/citations?view_op=view_citation&citation_for_view=USER:ARTICLE`.
+pub struct CitationDetailDocument(Document, ScrapeOptions);
+impl_from_to_document!(CitationDetailDocument);
+
+impl CitationDetailDocument {
+    /// Scrape the title of the article.
+    pub fn scrape_title(&self) -> Result<String> {
+        // <div id="gsc_oci_title">
+        //   <a class="gsc_oci_title_link" href="...">Quantal phase factors ...</a>
+        // </div>
+        //
+        // The title is not linked if the article has no link.
+
+        let title_node = try_html_bad!(
+            self.find(Attr("id", "gsc_oci_title")).nth(0),
+            "citation detail title",
+            &document_html(self)
+        );
+        let title = self.1.text_cleaner.node_text(&title_node);
+        Ok(try_html_bad!(non_empty(&title), "citation detail title", &title_node.html()))
+    }
+
+    /// Scrape the number of citations per year from the "Total citations" histogram.
+    ///
+    /// Years shown without a bar have no citations, and are mapped to 0.
+    /// Returns an empty map if the article is not cited yet, which shows no histogram.
+    pub fn scrape_citations_per_year(&self) -> Result<BTreeMap<u16, u32>> {
+        // <div id="gsc_oci_graph_bars">
+        //   <span class="gsc_oci_g_t" style="left:8px">2012</span>
+        //   ...
+        //   <a href="/scholar?oi=bibs&cites=000000&as_sdt=5&as_ylo=2012&as_yhi=2012"
+        //      class="gsc_oci_g_a" style="left:14px;height:32px;z-index:7">
+        //     <span class="gsc_oci_g_al">612</span>
+        //   </a>
+        //   ...
+        // </div>
+        //
+        // Bars are not in the order of years, so each is taken the year from its link.
+
+        let graph = match self.find(Attr("id", "gsc_oci_graph_bars")).nth(0) {
+            Some(graph) => graph,
+            None => return Ok(BTreeMap::new()),
+        };
+
+        let mut citations = graph
+            .find(Class("gsc_oci_g_t"))
+            .filter_map(|t| t.text().trim().parse().ok())
+            .map(|year| (year, 0))
+            .collect::<BTreeMap<_, _>>();
+
+        for bar in graph.find(Class("gsc_oci_g_a")) {
+            let year = {
+                let url = try_html_bad!(bar.attr("href"), "citation histogram bar", &bar.html());
+                parse_year_low(url)?
+            };
+            let count = {
+                let label = try_html_bad!(
+                    bar.find(Class("gsc_oci_g_al")).nth(0),
+                    "citation histogram count",
+                    &bar.html()
+                );
+                let count = label.text().trim().replace(',', "").parse();
+                try_html_bad!(count.ok(), "citation histogram count", &label.html())
+            };
+            citations.insert(year, count);
+        }
+
+        Ok(citations)
+    }
+}
+
+/// Result of searching authors, fetched from `/citations?view_op=search_authors&mauthors=NAME`.
+pub struct AuthorSearchDocument(Document, ScrapeOptions);
+impl_from_to_document!(AuthorSearchDocument);
+
+impl AuthorSearchDocument {
+    /// Scrape listed authors.
+    pub fn scrape_authors(&self) -> Result<Vec<AuthorSummary>> {
+        // <div class="gsc_1usr">
+        //   <div class="gs_ai">
+        //     <a href="/citations?user=qc6CJjYAAAAJ" class="gs_ai_pho">
+        //       <span><img src="https://scholar.googleusercontent.com/citations?..."></span>
+        //     </a>
+        //     <div class="gs_ai_t">
+        //       <h3 class="gs_ai_name">
+        //         <a href="/citations?user=qc6CJjYAAAAJ">Michael <span>Berry</span></a>
+        //       </h3>
+        //       <div class="gs_ai_aff">Physics, University of Bristol</div>
+        //       <div class="gs_ai_eml">Verified email at bristol.ac.uk</div>
+        //       <div class="gs_ai_cby">Cited by 81234</div>
+        //       ...
+        //     </div>
+        //   </div>
+        // </div>
+        //
+        // Affiliation, email, and citations may be empty.
+
+        let cleaner = &self.1.text_cleaner;
+        let line = |n: &Node, class: &str| {
+            n.find(Class(class))
+                .nth(0)
+                .and_then(|l| non_empty(&cleaner.node_text(&l)))
+        };
+
+        let mut authors = Vec::new();
+        for n in self.find(Class("gsc_1usr")) {
+            let name_node = {
+                let pos = Class("gs_ai_name").descendant(Name("a"));
+                try_html_bad!(n.find(pos).nth(0), "author name", &n.html())
+            };
+            let name = try_html_bad!(
+                non_empty(&cleaner.node_text(&name_node)),
+                "author name",
+                &n.html()
+            );
+            let user_id = parse_user_id(try_html_bad!(
+                name_node.attr("href"),
+                "author link",
+                &name_node.html()
+            ))?;
+
+            let thumbnail_url = n.find(Class("gs_ai_pho").descendant(Name("img")))
+                .nth(0)
+                .and_then(|img| img.attr("src"))
+                .map(scholar_absolute_url);
+
+            authors.push(AuthorSummary {
+                name,
+                user_id,
+                affiliation: line(&n, "gs_ai_aff"),
+                email_domain: line(&n, "gs_ai_eml").and_then(|e| parse_email_domain(&e)),
+                cited_by: line(&n, "gs_ai_cby")
+                    .and_then(|c| parse_citation_count(&c).ok())
+                    .map(|c| c.value),
+                thumbnail_url,
+            });
+        }
+
+        Ok(authors)
+    }
+}
+
+/// The "Cite" popup of a paper, fetched from `/scholar?q=info:ID:scholar.google.com/&output=cite`.
+/// See `Paper::info_id`.
+pub struct CitePopupDocument(Document, ScrapeOptions);
+impl_from_to_document!(CitePopupDocument);
+
+impl CitePopupDocument {
+    /// Scrape the citation formatted in each style, e.g. MLA, APA, Chicago, Harvard, and Vancouver,
+    /// in the order shown.
+    pub fn scrape_formatted_citations(&self) -> Result<Vec<FormattedCitation>> {
+        // <div id="gs_citt">
+        //   <table>
+        //     <tr>
+        //       <th class="gs_cith">MLA</th>
+        //       <td><div class="gs_citr">Aharonov, Yakir, ... <i>Physical Review</i> ...</div></td>
+        //     </tr>
+        //     ...
+        //   </table>
+        // </div>
+
+        let pos = Attr("id", "gs_citt").descendant(Name("tr"));
+        let cleaner = &self.1.text_cleaner;
+        let mut citations = Vec::new();
+        for n in self.find(pos) {
+            let style = {
+                let style_node =
+                    try_html_bad!(n.find(Class("gs_cith")).nth(0), "citation style", &n.html());
+                try_html_bad!(
+                    non_empty(&cleaner.node_text(&style_node)),
+                    "citation style",
+                    &n.html()
+                )
+            };
+            let text = {
+                let text_node =
+                    try_html_bad!(n.find(Class("gs_citr")).nth(0), "citation text", &n.html());
+                try_html_bad!(
+                    non_empty(&cleaner.node_text(&text_node)),
+                    "citation text",
+                    &n.html()
+                )
+            };
+            citations.push(FormattedCitation { style, text });
+        }
+
+        Ok(citations)
+    }
+
+    /// Scrape links to export the paper for bibliography managers.
+    ///
+    /// Returns an empty `Vec` if the popup shows no export link.
+    pub fn scrape_export_links(&self) -> Result<Vec<ExportLink>> {
+        // <div id="gs_citi">
+        //   <a class="gs_citi" href="https://scholar.googleusercontent.com/scholar.bib?q=...">
+        //     BibTeX
+        //   </a>
+        //   <a class="gs_citi" href="https://scholar.googleusercontent.com/scholar.enw?q=...">
+        //     EndNote
+        //   </a>
+        //   ...
+        // </div>
+
+        let pos = Attr("id", "gs_citi").descendant(Name("a"));
+        let mut links = Vec::new();
+        for n in self.find(pos) {
+            let format = try_html_bad!(
+                non_empty(&self.1.text_cleaner.node_text(&n)),
+                "export link",
+                &n.html()
+            );
+            let url = try_html_bad!(n.attr("href"), "export link", &n.html());
+            links.push(ExportLink {
+                format,
+                url: url.to_owned(),
+            });
+        }
+
+        Ok(links)
+    }
+}
+
+/// Top publications page of Google Scholar Metrics, e.g. `/citations?view_op=top_venues`.
+/// See `request::top_venues_url()`.
+pub struct MetricsDocument(Document, ScrapeOptions);
+impl_from_to_document!(MetricsDocument);
+
+impl MetricsDocument {
+    /// Scrape ranked venues, in the order listed.
+    pub fn scrape_venue_metrics(&self) -> Result<Vec<VenueMetrics>> {
+        // <div id="gs_md_cat-d">
+        //   <a class="gs_md_li gs_sel" href="...&vq=phy">Physics &amp; Mathematics</a>
+        //   ...
+        // </div>
+        // <div id="gs_md_sub-d">
+        //   <a class="gs_md_li gs_sel" href="...&vq=phy_quantumphysics">Quantum Physics</a>
+        //   ...
+        // </div>
+        //
+        // <table id="gsc_mvt_table">
+        //   <tr>
+        //     <th class="gsc_mvt_p"></th>
+        //     <th class="gsc_mvt_t">Publication</th>
+        //     ...
+        //   </tr>
+        //   <tr>
+        //     <td class="gsc_mvt_p">1.</td>
+        //     <td class="gsc_mvt_t">Physical Review Letters</td>
+        //     <td class="gsc_mvt_n">
+        //       <a href="/citations?view_op=list_hcore&venue=..." class="gsc_mp_anchor">175</a>
+        //     </td>
+        //     <td class="gsc_mvt_n"><span class="gsc_mp_anchor">232</span></td>
+        //   </tr>
+        //   ...
+        // </table>
+        //
+        // No item is selected in the menus of categories for the overall list.
+
+        let cleaner = &self.1.text_cleaner;
+        let selected = |menu_id| {
+            let pos = Attr("id", menu_id).descendant(Class("gs_sel"));
+            self.find(pos)
+                .nth(0)
+                .and_then(|n| non_empty(&cleaner.node_text(&n)))
+        };
+        let category = selected("gs_md_cat-d");
+        let subcategory = selected("gs_md_sub-d");
+
+        let pos = Attr("id", "gsc_mvt_table").descendant(Name("tr"));
+        let mut venues = Vec::new();
+        // The header row has no `td`
+        for n in self.find(pos).filter(|n| n.find(Name("td")).next().is_some()) {
+            let cell = |class| n.find(Class(class)).nth(0);
+
+            let rank = {
+                let rank_node = try_html_bad!(cell("gsc_mvt_p"), "venue rank", &n.html());
+                cleaner.node_text(&rank_node).replace('.', "").parse()?
+            };
+            let name = {
+                let name_node = try_html_bad!(cell("gsc_mvt_t"), "venue name", &n.html());
+                try_html_bad!(
+                    non_empty(&cleaner.node_text(&name_node)),
+                    "venue name",
+                    &n.html()
+                )
+            };
+
+            let metric_nodes = n.find(Class("gsc_mvt_n")).collect::<Vec<_>>();
+            let metric = |i: usize, section| -> Result<u32> {
+                let node = try_html_bad!(metric_nodes.get(i), section, &n.html());
+                Ok(parse_citation_count(&node.text())?.value)
+            };
+            let h5_index = metric(0, "h5-index")?;
+            let h5_median = metric(1, "h5-median")?;
+            let h5_core_url = metric_nodes[0]
+                .find(Name("a"))
+                .nth(0)
+                .and_then(|a| a.attr("href"))
+                .map(scholar_absolute_url);
+
+            venues.push(VenueMetrics {
+                rank,
+                name,
+                h5_index,
+                h5_median,
+                h5_core_url,
+                category: category.clone(),
+                subcategory: subcategory.clone(),
+            });
+        }
+
+        Ok(venues)
+    }
+}
+
+/// "My library" page listing articles saved by a signed-in user.
+/// Fetching this page requires the user's cookies.
+///
+/// Saved articles are listed like search results, so `scrape_papers()` scrapes them as well,
+/// without their labels.
+pub struct LibraryDocument(Document, ScrapeOptions);
+impl_from_to_document!(LibraryDocument);
+
+impl LibraryDocument {
+    /// Scrape listed articles with their labels.
+    ///
+    /// Errors are reported as in `PapersDocument::scrape_papers()`.
+    pub fn scrape_articles(&self) -> Result<Vec<LibraryArticle>> {
+        // <div id="gs_res_ccl_mid">
+        //   <div class="gs_r gs_or gs_scl" data-cid="TYWvRVbXFdgJ" data-lid="Xy9LmQ3aTbMJ">
+        //     <div class="gs_ri">
+        //       <h3 class="gs_rt">...</h3>
+        //       ...
+        //       <div class="gs_or_lbls">
+        //         <a class="gs_or_lbl" href="/scholar?scilib=1&scilu=...">Thesis</a>
+        //         ...
+        //       </div>
+        //     </div>
+        //   </div>
+        //   ...
+        // </div>
+        //
+        // Articles without labels have no `gs_or_lbls`.
+
+        if let Some(e) = page_error(&self.0, &self.1.selectors) {
+            return Err(e.into());
+        }
+
+        let cleaner = &self.1.text_cleaner;
+        let mut articles = Vec::new();
+        for (i, n) in layout_paper_nodes(&self.0, &self.1.selectors).enumerate() {
+            let mut paper = scrape_paper_one(&n, &self.1)?;
+            paper.result_position = paper.result_position.or(Some(i as u32));
+
+            let block = result_block(&n, &self.1.selectors);
+            let library_id = block.attr("data-lid").and_then(non_empty);
+            let labels = {
+                let pos = Class("gs_or_lbls").descendant(Class("gs_or_lbl"));
+                block
+                    .find(pos)
+                    .filter_map(|l| non_empty(&cleaner.node_text(&l)))
+                    .collect()
+            };
+
+            articles.push(LibraryArticle {
+                paper,
+                library_id,
+                labels,
+            });
+        }
+
+        Ok(articles)
+    }
+
+    /// Scrape labels listed in the sidebar, in the order shown.
+    ///
+    /// Returns an empty `Vec` if the user has no labels.
+    pub fn scrape_labels(&self) -> Result<Vec<LibraryLabel>> {
+        // <div id="gs_lbl_lst">
+        //   <a class="gs_lbl_a" href="/scholar?scilib=1&scilu=...&hl=en">
+        //     <span class="gs_lbl_n">Thesis</span> <span class="gs_lbl_c">12</span>
+        //   </a>
+        //   ...
+        // </div>
+        //
+        // Count may be missing.
+
+        let label_nodes = {
+            let pos = Attr("id", "gs_lbl_lst").descendant(Class("gs_lbl_a"));
+            self.find(pos)
+        };
+
+        let cleaner = &self.1.text_cleaner;
+        let mut labels = Vec::new();
+        for n in label_nodes {
+            let name = {
+                let name_node = try_html_bad!(
+                    n.find(Class("gs_lbl_n")).nth(0),
+                    "library label name",
+                    &n.html()
+                );
+                cleaner.node_text(&name_node)
+            };
+            let count = n.find(Class("gs_lbl_c"))
+                .nth(0)
+                .and_then(|c| c.text().trim().parse().ok());
+            let url = try_html_bad!(n.attr("href"), "library label link", &n.html()).to_owned();
+
+            labels.push(LibraryLabel { name, count, url });
+        }
+
+        Ok(labels)
+    }
+
+    /// Scrape the URL of the next page of the library. See `SearchDocument::next_page_url()`.
+    pub fn next_page_url(&self) -> Option<String> {
+        next_page_url(&self.0)
+    }
+
+    /// Scrape `start` offsets of pages of the library linked from the pagination bar.
+    /// See `SearchDocument::scrape_pagination_offsets()`.
+    pub fn scrape_pagination_offsets(&self) -> Vec<u32> {
+        pagination_offsets(&self.0)
+    }
+}
+
+/// URL of the next page from the pagination bar of `doc`. See `SearchDocument::next_page_url()`.
+fn next_page_url(doc: &Document) -> Option<String> {
+    // <div id="gs_n">
+    //   ...
+    //   <td>
+    //     <a href="/scholar?start=10&q=quantum+theory">
+    //       <span class="gs_ico gs_ico_nav_next"></span><b>Next</b>
+    //     </a>
+    //   </td>
     // </div>
     //
-    // 'journal etc.' at the second part may be ommited.
-    //
-    // 2. only two parts:
-    //
-    // <div class="gs_a">
-    //   author - journal etc., year
+    // The last page shows "Next" without a link.
+
+    let pos = Attr("id", "gs_n")
+        .descendant(Name("a"))
+        .child(Class("gs_ico_nav_next"));
+    doc.find(pos)
+        .nth(0)
+        .and_then(|n| n.parent())
+        .and_then(|a| a.attr("href"))
+        .map(ToOwned::to_owned)
+}
+
+/// Offsets of pages linked from the pagination bar of `doc`.
+/// See `SearchDocument::scrape_pagination_offsets()`.
+fn pagination_offsets(doc: &Document) -> Vec<u32> {
+    // <div id="gs_n">
+    //   <td><b>Previous</b></td>
+    //   <td><b>1</b></td>
+    //   <td><a href="/scholar?start=10&q=quantum+theory">2</a></td>
+    //   ...
+    //   <td><a href="/scholar?start=10&q=quantum+theory"><b>Next</b></a></td>
     // </div>
     //
-    // 'journal etc.' at the second part may be ommited.
-    //
-    //
-    // Author and 'journal etc.' can be a link:
-    //
-    // <div class="gs_a">
-    //   <a href="/citations?user=0">author</a> - journal etc., year - journal etc.
-    // </div>
-
-    let year_node = {
-        let pos = Class("gs_a").descendant(Text);
-        node.find(pos)
-            .into_selection()
-            .filter(|n: &Node| parse_year(&n.text()).is_ok())
-            .first()
-    };
-    let year = year_node.map(|n| parse_year(&n.text()).unwrap());
+    // The link to the first page has no `start`.
 
-    ArticleHeader { year }
+    let pos = Attr("id", "gs_n").descendant(Name("a"));
+    let mut offsets = doc.find(pos)
+        .filter(|n| n.text().trim().parse::<u32>().is_ok())
+        .filter_map(|n| n.attr("href"))
+        .map(|url| parse_start_offset(url).unwrap_or(0))
+        .collect::<Vec<_>>();
+    offsets.sort();
+    offsets.dedup();
+    offsets
 }
 
-fn parse_year(text: &str) -> Result<u32> {
+fn parse_user_id(url: &str) -> Result<String> {
     use regex::Regex;
 
     lazy_static! {
-        static ref RE: Regex = Regex::new(r".*\s-\s.*((18|19|20)(\d{2}))(\s-\s.+)?").unwrap();
+        static ref RE: Regex = Regex::new(r"[?&]user=([\w-]+)").unwrap();
     }
 
-    let year = {
-        let caps = try_html_bad!(RE.captures(text));
-        let year = try_html_bad!(caps.get(1));
-        year.as_str().parse().unwrap()
-    };
+    let caps = try_html_bad!(RE.captures(url), "user ID URL", url);
+    let id = try_html_bad!(caps.get(1), "user ID URL", url);
+    Ok(id.as_str().to_owned())
+}
 
-    Ok(year)
+fn parse_email_domain(text: &str) -> Option<String> {
+    use regex::Regex;
+
+    lazy_static! {
+        static ref RE: Regex = Regex::new(r"Verified email at\s+([\w.-]+\w)").unwrap();
+    }
+
+    RE.captures(text)
+        .and_then(|caps| caps.get(1))
+        .map(|m| m.as_str().to_owned())
 }
 
-fn scrape_article_footer(node: &Node) -> Result<ArticleFooter> {
-    // Footer format:
+fn parse_citation_for_view(url: &str) -> Result<String> {
+    use regex::Regex;
+
+    lazy_static! {
+        static ref RE: Regex = Regex::new(r"[?&]citation_for_view=([\w-]+:[\w-]+)").unwrap();
+    }
+
+    let caps = try_html_bad!(RE.captures(url), "profile article URL", url);
+    let id = try_html_bad!(caps.get(1), "profile article URL", url);
+    Ok(id.as_str().to_owned())
+}
+
+/// Parse `as_ylo`, the first year of a search restricted to years, out of `url`.
+fn parse_year_low(url: &str) -> Result<u16> {
+    use regex::Regex;
+
+    lazy_static! {
+        static ref RE: Regex = Regex::new(r"[?&]as_ylo=(\d{4})").unwrap();
+    }
+
+    let caps = try_html_bad!(RE.captures(url), "year range URL", url);
+    let year = try_html_bad!(caps.get(1), "year range URL", url);
+    Ok(year.as_str().parse()?)
+}
+
+fn selected_option<'a>(form: &Node<'a>, name: &str) -> Option<&'a str> {
+    let select = form.find(Name("select").and(Attr("name", name))).nth(0)?;
+    let selected = select
+        .find(Name("option"))
+        .find(|n| n.attr("selected").is_some());
+    selected.and_then(|n| n.attr("value"))
+}
+
+fn parse_start_offset(url: &str) -> Option<u32> {
+    use regex::Regex;
+
+    lazy_static! {
+        static ref RE: Regex = Regex::new(r"[?&]start=(\d+)").unwrap();
+    }
+
+    RE.captures(url)
+        .and_then(|caps| caps.get(1))
+        .and_then(|start| start.as_str().parse().ok())
+}
+
+fn parse_alert_id(url: &str) -> Result<String> {
+    use regex::Regex;
+
+    lazy_static! {
+        static ref RE: Regex = Regex::new(r"[?&]alert_id=([\w-]+)").unwrap();
+    }
+
+    let caps = try_html_bad!(RE.captures(url), "alert ID URL", url);
+    let id = try_html_bad!(caps.get(1), "alert ID URL", url);
+    Ok(id.as_str().to_owned())
+}
+
+fn scrape_case_one(node: &Node, cleaner: &TextCleaner, selectors: &SelectorProfile) -> CaseLaw {
+    let ArticleTitle { title, link } = scrape_article_title(node, cleaner, selectors);
+
+    let mut case = CaseLaw::new(&title);
+    case.case_id = link.as_ref().and_then(|l| parse_case_id(l).ok());
+    case.link = link;
+
+    if let Some(n) = node.find(Class(selectors.byline_class.as_str())).nth(0) {
+        let CaseHeader {
+            citation,
+            court,
+            year,
+        } = parse_case_header(&cleaner.node_text(&n));
+        case.citation = citation;
+        case.court = court;
+        case.year = year;
+    }
+
+    // Footer is the same as papers
+    case.citation_count = scrape_article_footer(node, selectors).citation_count;
+
+    case
+}
+
+struct CaseHeader {
+    citation: Option<String>,
+    court: Option<String>,
+    year: Option<u32>,
+}
+
+fn parse_case_header(text: &str) -> CaseHeader {
+    // <div class="gs_a">
+    //   347 US 483, 74 S. Ct. 686 - Supreme Court, 1954 - Google Scholar
+    // </div>
+    //
+    // Citation or court may be omitted.
+
+    let text = text.replace('\u{a0}', " ");
+    let mut parts = text.split(" - ");
+
+    let citation = parts.next().and_then(non_empty);
+    let (court, year) = match parts.next() {
+        Some(court_year) => {
+            let mut court_year = court_year.rsplitn(2, ',');
+            let last = court_year.next().unwrap_or("").trim();
+            match last.parse::<u32>() {
+                Ok(year) => (court_year.next().and_then(non_empty), Some(year)),
+                Err(_) => (non_empty(last), None),
+            }
+        }
+        None => (None, None),
+    };
+
+    CaseHeader {
+        citation,
+        court,
+        year,
+    }
+}
+
+fn non_empty(s: &str) -> Option<String> {
+    let s = s.trim();
+    if s.is_empty() {
+        None
+    } else {
+        Some(s.to_owned())
+    }
+}
+
+fn parse_case_id(url: &str) -> Result<u64> {
+    use regex::Regex;
+
+    lazy_static! {
+        static ref RE: Regex = Regex::new(r"case=(\d+)").unwrap();
+    }
+
+    let case_id = {
+        let caps = try_html_bad!(RE.captures(url), "case ID URL", url);
+        let id = try_html_bad!(caps.get(1), "case ID URL", url);
+        id.as_str().parse()?
+    };
+
+    Ok(case_id)
+}
+
+struct ArticleTitle {
+    title: String,
+    link: Option<String>,
+}
+
+struct ArticleHeader {
+    authors: Option<String>,
+    authors_truncated: bool,
+    venue: Option<String>,
+    year: Option<u32>,
+}
+
+struct ArticleFooter {
+    cluster_id: Option<ClusterId>,
+    citation_count: Option<CitationCount>,
+    versions_cluster_id: Option<ClusterId>,
+    versions_count: Option<u32>,
+    versions_url: Option<String>,
+    related_url: Option<String>,
+}
+
+fn scrape_paper_one(node: &Node, options: &ScrapeOptions) -> Result<Paper> {
+    let cleaner = &options.text_cleaner;
+    let selectors = &options.selectors;
+
+    // A result without title header is not a paper we can make sense of
+    try_html_bad!(
+        node.find(Class(selectors.title_class.as_str())).nth(0),
+        "title",
+        &node.html()
+    );
+
+    let ArticleTitle { title, link } = scrape_article_title(node, cleaner, selectors);
+    let ArticleHeader {
+        authors,
+        authors_truncated,
+        venue,
+        year,
+    } = scrape_article_header(node, cleaner, selectors);
+    let ArticleFooter {
+        cluster_id,
+        citation_count,
+        versions_cluster_id,
+        versions_count,
+        versions_url,
+        related_url,
+    } = scrape_article_footer(node, selectors);
+
+    // A result not cited yet has no "Cited by" link, and may have no footer link
+    // bearing the cluster ID at all. Its title may link to the cluster instead.
+    let cluster_id = cluster_id.or_else(|| {
+        link.as_ref()
+            .and_then(|l| parse_versions_cluster_id(l).ok())
+    });
+
+    let mut paper = Paper::new(&title, 0);
+    paper.set_cluster_id(cluster_id);
+    paper.authors = authors;
+    paper.authors_truncated = authors_truncated;
+    paper.venue = venue;
+    paper.link = link;
+    paper.year = year;
+    paper.citation_count = citation_count;
+    paper.versions_cluster_id = versions_cluster_id;
+    paper.versions_count = versions_count;
+    paper.versions_url = versions_url;
+    paper.related_url = related_url;
+    paper.info_id = scrape_info_id(node, selectors);
+    paper.snippet = scrape_snippet(node, cleaner, selectors);
+    paper.age_days = scrape_age_days(node, selectors);
+    paper.article_type = scrape_article_type(node, cleaner);
+    paper.doc_type = scrape_doc_type(node, selectors);
+    paper.result_position = scrape_result_position(node, selectors);
+    paper.access = scrape_access(node, selectors);
+    paper.pdf_link = scrape_pdf_link(node, selectors);
+    {
+        let urls = paper.link.iter().chain(paper.pdf_link.iter());
+        paper.doi = urls.clone().filter_map(|url| parse_doi(url)).nth(0);
+        paper.arxiv_id = urls.filter_map(|url| parse_arxiv_id(url)).nth(0);
+    }
+    paper.save_token = scrape_save_token(node);
+    if options.keep_raw_html {
+        // Keep the whole result block, which includes `data-rp` and full text links
+        paper.raw_html = Some(result_block(node, selectors).html());
+    }
+
+    Ok(paper)
+}
+
+fn scrape_article_title(
+    node: &Node,
+    cleaner: &TextCleaner,
+    selectors: &SelectorProfile,
+) -> ArticleTitle {
+    // There are (at least) two formats.
+    //
+    // 1. Link to a paper or something:
+    //
+    // <h3 class="gs_rt">
+    //   <span>
+    //     something
+    //   </span>
+    //   <a href="http://paper.pdf">
+    //     title of paper or something
+    //   </a>
+    // </h3>
+    //
+    // 'span' may not exists.
+    //
+    // The link may be nested in wrappers, and preceded by a tracking link:
+    //
+    // <h3 class="gs_rt">
+    //   <a href="/scholar_url?url=..." rel="nofollow">
+    //     <span>tracking something</span>
+    //   </a>
+    //   <span>
+    //     <a href="http://paper.pdf">
+    //       title of paper or something
+    //     </a>
+    //   </span>
+    // </h3>
+    //
+    // The deepest link bearing text by itself is taken.
+    //
+    // 2. Not a link:
+    //
+    // <h3 class="gs_rt">
+    //   <span>
+    //     something
+    //   </span>
+    //   title of paper or something
+    // </h3>
+
+    if let Some(n) = {
+        let pos = Class(selectors.title_class.as_str()).descendant(Name("a"));
+        let links = node.find(pos).collect::<Vec<_>>();
+        let text_links = links
+            .iter()
+            .filter(|n| has_own_text(n))
+            .cloned()
+            .collect::<Vec<_>>();
+        deepest_node(if text_links.is_empty() {
+            &links
+        } else {
+            &text_links
+        })
+    } {
+        // 1. Link to a paper or something
+        ArticleTitle {
+            title: cleaner.node_text(&n),
+            link: n.attr("href").map(ToOwned::to_owned),
+        }
+    } else {
+        // 2. Not a link
+        let children = {
+            let pos = Class(selectors.title_class.as_str());
+            node.find(pos).into_selection().children()
+        };
+        let text_nodes = children.filter(|n: &Node| {
+            if let Some(name) = n.name() {
+                name != "span"
+            } else {
+                true
+            }
+        });
+        let concated_text = text_nodes
+            .into_iter()
+            .map(|n| cleaner.raw_text(&n))
+            .collect::<String>();
+        ArticleTitle {
+            title: cleaner.clean(&concated_text).trim().to_string(),
+            link: None,
+        }
+    }
+}
+
+/// Whether `node` has non-empty text by itself or in inline formatting like `<b>`,
+/// not in nested elements like `<span>`.
+fn has_own_text(node: &Node) -> bool {
+    const INLINE_FORMATS: &[&str] = &["b", "i", "em", "strong", "sub", "sup"];
+
+    node.children().any(|n| match n.as_text() {
+        Some(t) => !t.trim().is_empty(),
+        None => n.name().map_or(false, |name| INLINE_FORMATS.contains(&name)) && has_own_text(&n),
+    })
+}
+
+/// The first of the deepest nodes among `nodes`.
+fn deepest_node<'a>(nodes: &[Node<'a>]) -> Option<Node<'a>> {
+    fn depth(node: &Node) -> usize {
+        let mut depth = 0;
+        let mut n = node.parent();
+        while let Some(p) = n {
+            depth += 1;
+            n = p.parent();
+        }
+        depth
+    }
+
+    let mut deepest: Option<(usize, Node<'a>)> = None;
+    for &n in nodes {
+        let d = depth(&n);
+        if deepest.map_or(true, |(max, _)| d > max) {
+            deepest = Some((d, n));
+        }
+    }
+    deepest.map(|(_, n)| n)
+}
+
+fn scrape_snippet(
+    node: &Node,
+    cleaner: &TextCleaner,
+    selectors: &SelectorProfile,
+) -> Option<String> {
+    // Snippet follows the byline, with matched words highlighted:
+    //
+    // <div class="gs_ri">
+    //   <div class="gs_a">byline</div>
+    //   <div class="gs_rs">snippet with <b>highlighted</b> words <br></div>
+    //   ...
+    // </div>
+    //
+    // In results sorted by date, the snippet starts with the age of the paper:
+    //
+    // <div class="gs_rs"><span class="gs_age">3 days ago - </span>snippet ...</div>
+
+    let snippet_node = node.find(Class(selectors.snippet_class.as_str())).nth(0)?;
+    let text = cleaner.node_text(&snippet_node);
+    let age = snippet_node
+        .find(Class("gs_age"))
+        .nth(0)
+        .map(|n| cleaner.node_text(&n));
+    let text = match age {
+        Some(ref age) if text.starts_with(age.as_str()) => text[age.len()..].trim().to_owned(),
+        _ => text,
+    };
+    Some(text).filter(|t| !t.is_empty())
+}
+
+fn scrape_age_days(node: &Node, selectors: &SelectorProfile) -> Option<u32> {
+    // See `scrape_snippet()`.
+
+    let pos = Class(selectors.snippet_class.as_str()).descendant(Class("gs_age"));
+    node.find(pos).nth(0).and_then(|n| parse_age_days(&n.text()))
+}
+
+/// Parse the age of a paper shown in results sorted by date, e.g. `3` for "3 days ago - ".
+fn parse_age_days(text: &str) -> Option<u32> {
+    use regex::Regex;
+
+    lazy_static! {
+        static ref RE: Regex = Regex::new(r"^\s*(\d+)\s+days?\s+ago").unwrap();
+    }
+
+    RE.captures(text)
+        .and_then(|caps| caps.get(1))
+        .and_then(|days| days.as_str().parse().ok())
+}
+
+fn scrape_article_type(node: &Node, cleaner: &TextCleaner) -> Option<String> {
+    // Most results have no badge. Editorial types are shown as:
+    //
+    // <div class="gs_ri">
+    //   <h3 class="gs_rt">title</h3>
+    //   <span class="gs_ctp">Review article</span>
+    //   ...
+    // </div>
+
+    let pos = Class("gs_ctp");
+    node.find(pos)
+        .nth(0)
+        .map(|n| cleaner.node_text(&n).trim().to_string())
+        .filter(|t| !t.is_empty())
+}
+
+fn scrape_doc_type(node: &Node, selectors: &SelectorProfile) -> Option<DocType> {
+    // Some titles are prefixed with a label and its abbreviation:
+    //
+    // <h3 class="gs_rt">
+    //   <span class="gs_ct1">[BOOK]</span>
+    //   <span class="gs_ct2">[B]</span>
+    //   title
+    // </h3>
+    //
+    // The label may be in `gs_ctg2` instead, e.g. "[PDF]".
+
+    let label = |class| {
+        let pos = Class(selectors.title_class.as_str()).descendant(Name("span").and(Class(class)));
+        node.find(pos)
+            .filter_map(|n| DocType::from_label(&n.text()))
+            .nth(0)
+    };
+    label("gs_ct1")
+        .or_else(|| label("gs_ctg2"))
+        .or_else(|| label("gs_ct2"))
+}
+
+fn scrape_result_position(node: &Node, selectors: &SelectorProfile) -> Option<u32> {
+    // <div class="gs_r gs_or gs_scl" data-cid="n-S3Szmx-uQJ" data-rp="0">
+    //   <div class="gs_ri">
+    //     each paper
+    //   </div>
+    // </div>
+
+    result_block(node, selectors)
+        .attr("data-rp")
+        .and_then(|rp| rp.parse().ok())
+}
+
+fn scrape_access(node: &Node, selectors: &SelectorProfile) -> Access {
+    // Links to full text or library resolvers are placed beside the paper:
+    //
+    // <div class="gs_r gs_or gs_scl">
+    //   <div class="gs_ggs gs_fl">
+    //     <div class="gs_ggsd">
+    //       <div class="gs_or_ggsm">
+    //         <a href="http://paper.pdf"><span class="gs_ctg2">[PDF]</span> example.com</a>
+    //       </div>
+    //     </div>
+    //   </div>
+    //   <div class="gs_ri">
+    //     each paper
+    //   </div>
+    // </div>
+    //
+    // Resolver links have no "[PDF]" or "[HTML]" tag.
+    // Some papers state "No full text available" explicitly instead.
+
+    const NO_FULL_TEXT: &str = "No full text available";
+
+    let result_node = result_block(node, selectors);
+
+    let links = result_node
+        .find(Class(selectors.full_text_class.as_str()).descendant(Name("a")))
+        .collect::<Vec<_>>();
+    if links.iter().any(is_full_text_link) {
+        return Access::FullText;
+    }
+    if !links.is_empty() {
+        return Access::Resolver;
+    }
+
+    if result_node.text().contains(NO_FULL_TEXT) {
+        Access::ExplicitlyUnavailable
+    } else {
+        Access::Unknown
+    }
+}
+
+fn scrape_pdf_link(node: &Node, selectors: &SelectorProfile) -> Option<String> {
+    // The first full text link beside the paper (see `scrape_access()`)
+
+    let result_node = result_block(node, selectors);
+    result_node
+        .find(Class(selectors.full_text_class.as_str()).descendant(Name("a")))
+        .find(is_full_text_link)
+        .and_then(|n| n.attr("href"))
+        .map(ToOwned::to_owned)
+}
+
+fn is_full_text_link(link: &Node) -> bool {
+    // Full text links are tagged with "[PDF]", "[HTML]", etc.
+    link.find(Class("gs_ctg2")).next().is_some()
+}
+
+fn scrape_save_token(node: &Node) -> Option<String> {
+    // On pages fetched while signed in:
     //
     // <div class="gs_fl">
-    //   something
-    //   <a href="/scholar?cites=000000>Cited by 999</a>
-    //   something
+    //   <a href="javascript:void(0)" class="gs_or_sav gs_or_btn" data-xsrf="AMstHGQ...">Save</a>
+    //   ...
+    // </div>
+    //
+    // The token is missing on anonymous pages and for papers already saved.
+
+    node.find(Class("gs_or_sav"))
+        .nth(0)
+        .and_then(|n| n.attr("data-xsrf"))
+        .and_then(non_empty)
+}
+
+fn scrape_article_header(
+    node: &Node,
+    cleaner: &TextCleaner,
+    selectors: &SelectorProfile,
+) -> ArticleHeader {
+    // There are (at least) two formats for publishment information:
+    //
+    // 1. with journal etc. at the third part:
+    //
+    // <div class="gs_a">
+    //   author - journal etc., year - journal etc.
+    // </div>
+    //
+    // 'journal etc.' at the second part may be ommited.
+    //
+    // 2. only two parts:
+    //
+    // <div class="gs_a">
+    //   author - journal etc., year
+    // </div>
+    //
+    // 'journal etc.' at the second part may be ommited.
+    //
+    //
+    // Author and 'journal etc.' can be a link:
+    //
+    // <div class="gs_a">
+    //   <a href="/citations?user=0">author</a> - journal etc., year - journal etc.
     // </div>
 
-    let footer_nodes = {
-        let pos = Class("gs_fl");
-        try_html_bad!(node.find(pos).nth(0))
-            .children()
-            .into_selection()
-    };
+    let year_node = {
+        let pos = Class(selectors.byline_class.as_str()).descendant(Text);
+        node.find(pos)
+            .into_selection()
+            .filter(|n: &Node| parse_year(&n.text()).is_ok())
+            .first()
+    };
+    let year = year_node.map(|n| parse_year(&n.text()).unwrap());
+
+    let (authors, venue) = match node.find(Class(selectors.byline_class.as_str())).nth(0) {
+        Some(n) => parse_authors_venue(&cleaner.node_text(&n)),
+        None => (None, None),
+    };
+    let (authors, authors_truncated) = match authors {
+        Some(authors) => parse_truncated_authors(&authors),
+        None => (None, false),
+    };
+
+    ArticleHeader {
+        authors,
+        authors_truncated,
+        venue,
+        year,
+    }
+}
+
+fn parse_authors_venue(text: &str) -> (Option<String>, Option<String>) {
+    // author - journal etc., year - journal etc.
+    //
+    // 'journal etc.' or year at the second part may be ommited.
+
+    fn is_year(s: &str) -> bool {
+        s.len() == 4 && s.chars().all(|c| c.is_ascii_digit())
+    }
+
+    let text = text.replace('\u{a0}', " ");
+    let mut parts = text.split(" - ");
+
+    let authors = parts.next().and_then(non_empty);
+    let venue = parts.next().and_then(|venue_year| {
+        let venue_year = venue_year.trim();
+        let (venue, year) = match venue_year.rfind(',') {
+            Some(i) => (&venue_year[..i], venue_year[i + 1..].trim()),
+            None => ("", venue_year),
+        };
+        if is_year(year) {
+            non_empty(venue)
+        } else {
+            non_empty(venue_year)
+        }
+    });
+
+    (authors, venue)
+}
+
+fn parse_truncated_authors(authors: &str) -> (Option<String>, bool) {
+    // Long author lists end with "…", or with "+N more" on some layouts:
+    //
+    // A Foo, B Bar, C Baz…
+    // A Foo, B Bar, C Baz, ...
+    // A Foo, B Bar, +3 more
+
+    use regex::Regex;
+
+    lazy_static! {
+        static ref RE: Regex = Regex::new(r",?\s*(…|\.\.\.|\+\s*\d+\s+more)\s*$").unwrap();
+    }
+
+    match RE.find(authors) {
+        Some(m) => (non_empty(&authors[..m.start()]), true),
+        None => (non_empty(authors), false),
+    }
+}
+
+fn parse_year(text: &str) -> Result<u32> {
+    // author - journal etc., year - journal etc.
+    //
+    // The year is the last four-digit word in the second part. Identifiers
+    // such as 'arXiv:2103.12345' are not words by themselves, so they are
+    // never taken as a year.
+    use regex::Regex;
+
+    lazy_static! {
+        static ref SEP: Regex = Regex::new(r"\s-\s").unwrap();
+    }
+
+    const YEAR_MIN: u32 = 1500;
+    const YEAR_MAX: u32 = 2100;
+
+    let middle = try_html_bad!(SEP.split(text).nth(1), "year", text);
+    let year = try_html_bad!(
+        middle
+            .split(|c: char| c.is_whitespace() || c == ',')
+            .rev()
+            .find(|w| w.len() == 4 && w.chars().all(|c| c.is_ascii_digit())),
+        "year",
+        text
+    );
+    let year: u32 = year.parse().unwrap();
+
+    if !(YEAR_MIN..=YEAR_MAX).contains(&year) {
+        bail!(ErrorKind::bad_html("year", text));
+    }
+
+    Ok(year)
+}
+
+fn scrape_article_footer(node: &Node, selectors: &SelectorProfile) -> ArticleFooter {
+    // Footer format:
+    //
+    // <div class="gs_fl">
+    //   something
+    //   <a href="/scholar?cites=000000>Cited by 999</a>
+    //   something
+    //   <a href="/scholar?cluster=000000>All 9 versions</a>
+    //   <a href="/scholar?q=related:abcDEF012-_J:scholar.google.com/">Related articles</a>
+    // </div>
+    //
+    // Any link may be missing, e.g. "Cited by" for a paper not cited yet.
+
+    // Full text links beside the paper are also "gs_fl" in the mobile layout.
+    let footer_nodes = match node.find(footer(selectors)).nth(0) {
+        Some(n) => n.children().collect::<Vec<_>>(),
+        None => Vec::new(),
+    };
+    let footer_urls = footer_nodes.iter().filter_map(|n| n.attr("href"));
+
+    let cluster_id = footer_urls
+        .clone()
+        .filter_map(|id_url| parse_cluster_id(id_url).ok())
+        .nth(0);
+
+    let citation_count = footer_nodes
+        .iter()
+        .find(|n| n.attr("href").map_or(false, |url| parse_cites_id(url).is_ok()))
+        .and_then(|n| parse_citation_count(&n.text()).ok());
+
+    let versions_node = footer_nodes.iter().find(|n| {
+        n.attr("href")
+            .map_or(false, |url| parse_versions_cluster_id(url).is_ok())
+    });
+    let versions_cluster_id = versions_node
+        .and_then(|n| n.attr("href"))
+        .and_then(|url| parse_versions_cluster_id(url).ok());
+    let versions_count = versions_node.and_then(|n| parse_versions_count(&n.text()).ok());
+    let versions_url = versions_node
+        .and_then(|n| n.attr("href"))
+        .map(scholar_absolute_url);
+
+    let related_url = footer_nodes
+        .iter()
+        .filter_map(|n| n.attr("href"))
+        .find(|url| parse_related_from_url(url).is_ok())
+        .map(ToOwned::to_owned);
+
+    ArticleFooter {
+        cluster_id,
+        citation_count,
+        versions_cluster_id,
+        versions_count,
+        versions_url,
+        related_url,
+    }
+}
+
+fn scholar_absolute_url(href: &str) -> String {
+    if href.starts_with('/') {
+        format!("https://scholar.google.com{}", href)
+    } else {
+        href.to_owned()
+    }
+}
+
+fn parse_cluster_id(url: &str) -> Result<ClusterId> {
+    parse_id_from_url(url).map(|(_, id)| id)
+}
+
+fn parse_cites_id(url: &str) -> Result<ClusterId> {
+    use regex::Regex;
+
+    lazy_static! {
+        static ref RE: Regex = Regex::new(r"[?&]cites=(\d+)").unwrap();
+    }
+
+    let cluster_id = {
+        let caps = try_html_bad!(RE.captures(url), "cites ID URL", url);
+        let id = try_html_bad!(caps.get(1), "cites ID URL", url);
+        id.as_str().parse()?
+    };
+
+    Ok(cluster_id)
+}
+
+fn parse_versions_cluster_id(url: &str) -> Result<ClusterId> {
+    use regex::Regex;
+
+    lazy_static! {
+        static ref RE: Regex = Regex::new(r"[?&]cluster=(\d+)").unwrap();
+    }
+
+    let cluster_id = {
+        let caps = try_html_bad!(RE.captures(url), "versions URL", url);
+        let id = try_html_bad!(caps.get(1), "versions URL", url);
+        id.as_str().parse()?
+    };
+
+    Ok(cluster_id)
+}
+
+fn scrape_info_id(node: &Node, selectors: &SelectorProfile) -> Option<String> {
+    // The "Cite" link opens a popup with the ID in older layouts:
+    //
+    // <div class="gs_fl">
+    //   <a href="#" onclick="return gs_ocit(event,'n-S3Szmx-uQJ','0')">Cite</a>
+    //   <a href="/scholar?q=info:n-S3Szmx-uQJ:scholar.google.com/&output=cite">Import</a>
+    // </div>
+    //
+    // and the result block holds it in newer layouts:
+    //
+    // <div class="gs_r gs_or gs_scl" data-cid="n-S3Szmx-uQJ">
+    //   <div class="gs_ri">each paper</div>
+    // </div>
+
+    let footer_id = node.find(footer(selectors).descendant(Name("a")))
+        .flat_map(|n| n.attr("onclick").into_iter().chain(n.attr("href")))
+        .filter_map(parse_info_id)
+        .nth(0);
+
+    footer_id.or_else(|| {
+        result_block(node, selectors).attr("data-cid").and_then(non_empty)
+    })
+}
+
+/// Parse an ID of formatted citations from a `gs_ocit()` call of the "Cite" link,
+/// or from an `info:` query.
+fn parse_info_id(s: &str) -> Option<String> {
+    use regex::Regex;
+
+    lazy_static! {
+        static ref OCIT_RE: Regex = Regex::new(r"gs_ocit\([^)]*?'([\w-]{8,})'").unwrap();
+        static ref INFO_RE: Regex = Regex::new(r"info:([\w-]+):").unwrap();
+    }
+
+    OCIT_RE
+        .captures(s)
+        .or_else(|| INFO_RE.captures(s))
+        .and_then(|caps| caps.get(1))
+        .map(|id| id.as_str().to_owned())
+}
+
+/// Parse the key of related articles, e.g. "abcDEF012-_J" for `q=related:abcDEF012-_J:...`.
+pub(crate) fn parse_related_from_url(url: &str) -> Result<String> {
+    use regex::Regex;
+
+    lazy_static! {
+        static ref RE: Regex = Regex::new(r"[?&]q=related:([\w-]+)").unwrap();
+    }
+
+    let caps = try_html_bad!(RE.captures(url), "related articles URL", url);
+    let key = try_html_bad!(caps.get(1), "related articles URL", url);
+    Ok(key.as_str().to_owned())
+}
+
+/// Rewrite numbers in `text` localized for the interface language into ASCII,
+/// so that regexes with `\d` and the separators below can parse them.
+///
+/// Arabic-Indic and fullwidth digits become ASCII digits, Arabic thousands and decimal
+/// separators become ',' and '.', and bidirectional marks around RTL text are removed.
+fn ascii_number_text(text: &str) -> String {
+    text.chars()
+        .filter_map(|c| match c {
+            '\u{660}'..='\u{669}' => Some(ascii_digit(c as u32 - 0x660)),
+            '\u{6f0}'..='\u{6f9}' => Some(ascii_digit(c as u32 - 0x6f0)),
+            '\u{ff10}'..='\u{ff19}' => Some(ascii_digit(c as u32 - 0xff10)),
+            '\u{66c}' => Some(','),
+            '\u{66b}' => Some('.'),
+            '\u{200e}' | '\u{200f}' | '\u{61c}' | '\u{202a}'..='\u{202e}'
+            | '\u{2066}'..='\u{2069}' => None,
+            c => Some(c),
+        })
+        .collect()
+}
+
+fn ascii_digit(d: u32) -> char {
+    (b'0' + d as u8) as char
+}
+
+fn parse_versions_count(text: &str) -> Result<u32> {
+    // "All 12 versions", or localized one like "全 12 バージョン"
+
+    use regex::Regex;
+
+    lazy_static! {
+        static ref RE: Regex =
+            Regex::new(r"\d{1,3}(?:[,. \x{a0}\x{2009}\x{202f}]\d{3})+|\d+").unwrap();
+    }
+
+    let text = &ascii_number_text(text);
+    let count = try_html_bad!(RE.find(text), "versions count", text)
+        .as_str()
+        .chars()
+        .filter(char::is_ascii_digit)
+        .collect::<String>();
+
+    Ok(count.parse()?)
+}
+
+fn parse_citation_count(text: &str) -> Result<CitationCount> {
+    // "Cited by 999", or an estimate like "Cited by about 1,000", "Cited by 1000+",
+    // or an abbreviated one like "Cited by 1.5K".
+    // The count may precede the words in some locales, e.g. "1234회 인용".
+    //
+    // Digits may be grouped with commas, periods, or (thin, non-breaking) spaces
+    // depending on the locale, e.g. "1,234", "1.234", and "1 234",
+    // and may be Arabic-Indic ones, e.g. "١٬٢٣٤".
+
+    use std::u32;
+    use regex::Regex;
+
+    lazy_static! {
+        static ref RE: Regex = Regex::new(
+            r"(\d{1,3}(?:[,. \x{a0}\x{2009}\x{202f}]\d{3})+|\d+)(\.\d+)?((?i:k|m)|\+)?"
+        ).unwrap();
+    }
+
+    let text = &ascii_number_text(text);
+    let caps = try_html_bad!(RE.captures(text), "citation count", text);
+    let integer = try_html_bad!(caps.get(1), "citation count", text)
+        .as_str()
+        .chars()
+        .filter(char::is_ascii_digit)
+        .collect::<String>();
+    let suffix = caps.get(3).map(|s| s.as_str());
+
+    let multiplier = match suffix {
+        Some("k") | Some("K") => Some(1_000.0),
+        Some("m") | Some("M") => Some(1_000_000.0),
+        _ => None,
+    };
+    let value = match multiplier {
+        Some(multiplier) => {
+            let fraction = caps.get(2).map_or("", |f| f.as_str());
+            let number: f64 = format!("{}{}", integer, fraction).parse().unwrap();
+            let value = (number * multiplier).round();
+            if value > f64::from(u32::MAX) {
+                return Err(ErrorKind::bad_html("citation count", text).into());
+            }
+            value as u32
+        }
+        None => {
+            // Parse into u64 first, since pathological pages may show counts beyond u32
+            let value: u64 = integer.parse()?;
+            if value > u64::from(u32::MAX) {
+                return Err(ErrorKind::bad_html("citation count", text).into());
+            }
+            value as u32
+        }
+    };
+
+    let approximate = {
+        let text = text.to_lowercase();
+        suffix.is_some() || text.contains("about") || text.contains("approx")
+            || text.contains('~')
+    };
+
+    Ok(CitationCount { value, approximate })
+}
+
+fn parse_search_scope(as_sdt: &str) -> SearchScope {
+    // `as_sdt` is e.g. "0,5" or "2005" for articles, and "2006" or "4,33" for case law.
+    match as_sdt {
+        "2005" => SearchScope::Articles,
+        "2006" => SearchScope::CaseLaw,
+        _ => match as_sdt.split(',').next() {
+            Some("0") | Some("1") => SearchScope::Articles,
+            Some("2") | Some("3") | Some("4") => SearchScope::CaseLaw,
+            _ => SearchScope::Unknown,
+        },
+    }
+}
+
+fn parse_result_range(text: &str) -> Result<(u32, u32)> {
+    use regex::Regex;
+
+    lazy_static! {
+        static ref RE: Regex = Regex::new(r"(\d[\d,]*)\s*[-\x{2013}]\s*(\d[\d,]*)").unwrap();
+    }
+
+    let caps = try_html_bad!(RE.captures(text), "result range", text);
+    let first = try_html_bad!(caps.get(1), "result range", text)
+        .as_str()
+        .replace(',', "")
+        .parse()?;
+    let last = try_html_bad!(caps.get(2), "result range", text)
+        .as_str()
+        .replace(',', "")
+        .parse()?;
+
+    Ok((first, last))
+}
+
+fn is_approximate_result_count(text: &str) -> bool {
+    // "About 1,960,000 results", or localized one like "Ungefähr 1.960.000 Ergebnisse"
+    // or "約 1,960,000 件"
+
+    const ESTIMATES: &[&str] = &[
+        "about", "ungefähr", "environ", "aproximadamente", "circa", "около", "約", "约",
+        "حوالي",
+    ];
+
+    let text = text.split('(').next().unwrap_or("").to_lowercase();
+    ESTIMATES.iter().any(|e| text.contains(e))
+}
+
+fn parse_query_time(text: &str) -> Option<Duration> {
+    // "(0.05 sec)", or localized one like "(0,05 Sek.)"
+
+    use regex::Regex;
+
+    lazy_static! {
+        static ref RE: Regex = Regex::new(r"\((\d+)(?:[.,](\d+))?").unwrap();
+    }
+
+    let text = ascii_number_text(text);
+    let caps = RE.captures_iter(&text).last()?;
+    let secs = caps.get(1)?.as_str().parse().ok()?;
+    let nanos = match caps.get(2) {
+        Some(fraction) => {
+            // Up to nanoseconds, e.g. "05" -> 50_000_000
+            let digits = fraction.as_str().chars().take(9).collect::<String>();
+            digits.parse::<u32>().ok()? * 10u32.pow(9 - digits.len() as u32)
+        }
+        None => 0,
+    };
+    Some(Duration::new(secs, nanos))
+}
+
+fn parse_result_count(text: &str) -> Result<u64> {
+    // "About 1,960,000 results (0.05 sec)", "Page 100 of about 1,960,000 results",
+    // "Showing results 11–20 of about 1,960,000", or "6 results (0.01 sec)"
+    //
+    // The first digit-grouped number is the count.
+    // Otherwise the last number before search time is taken,
+    // since page numbers or ranges may precede.
+
+    use regex::Regex;
+
+    lazy_static! {
+        static ref GROUPED: Regex =
+            Regex::new(r"\d{1,3}(?:[,. \x{a0}\x{2009}\x{202f}]\d{3})+").unwrap();
+        static ref PLAIN: Regex = Regex::new(r"\d+").unwrap();
+    }
+
+    let text = ascii_number_text(text);
+    let text = text.split('(').next().unwrap_or("");
+    let count = GROUPED
+        .find(text)
+        .or_else(|| PLAIN.find_iter(text).last());
+    let count = try_html_bad!(count, "result count", text)
+        .as_str()
+        .chars()
+        .filter(char::is_ascii_digit)
+        .collect::<String>();
+
+    Ok(count.parse()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_year_pass() {
+        assert_eq!(parse_year("foo - journal, 2000 - bar").unwrap(), 2000);
+        assert_eq!(parse_year("foo bar - 1999 - baz, qrux").unwrap(), 1999);
+        assert_eq!(parse_year("foo - journal, 1998").unwrap(), 1998);
+        assert_eq!(parse_year("foo - 1899").unwrap(), 1899);
+        assert_eq!(parse_year(" - journal, 1898").unwrap(), 1898);
+        assert_eq!(parse_year(" - 1800").unwrap(), 1800);
+        assert_eq!(
+            parse_year("Y Aharonov, D Bohm\u{a0}- Physical Review, 1959 - APS").unwrap(),
+            1959
+        );
+        assert_eq!(parse_year("foo - Proc. 1998 Symposium, 2001 - bar").unwrap(), 2001);
+    }
+
+    #[test]
+    fn parse_year_fail() {
+        assert!(parse_year("foo - journal - bar").is_err());
+        assert!(parse_year("foo - journal").is_err());
+        assert!(parse_year("- journal, 1898").is_err());
+        assert!(parse_year("- 1800").is_err());
+        assert!(parse_year("foo - arXiv preprint arXiv:2103.12345").is_err());
+        assert!(parse_year("foo - journal, 1234 - bar").is_err());
+        assert!(parse_year("foo - journal, 2345 - bar").is_err());
+    }
+
+    #[test]
+    fn parse_authors_venue_test() {
+        assert_eq!(
+            parse_authors_venue("Y Aharonov, D Bohm\u{a0}- Physical Review, 1959 - APS"),
+            (
+                Some(String::from("Y Aharonov, D Bohm")),
+                Some(String::from("Physical Review"))
+            )
+        );
+        assert_eq!(
+            parse_authors_venue("C Kittel, C Fong - 1963 - Wiley New York"),
+            (Some(String::from("C Kittel, C Fong")), None)
+        );
+        assert_eq!(
+            parse_authors_venue(" - Nature materials"),
+            (None, Some(String::from("Nature materials")))
+        );
+        assert_eq!(parse_authors_venue(""), (None, None));
+    }
+
+    #[test]
+    fn parse_truncated_authors_test() {
+        assert_eq!(
+            parse_truncated_authors("A Foo, B Bar"),
+            (Some(String::from("A Foo, B Bar")), false)
+        );
+        assert_eq!(
+            parse_truncated_authors("A Foo, B Bar…"),
+            (Some(String::from("A Foo, B Bar")), true)
+        );
+        assert_eq!(
+            parse_truncated_authors("A Foo, B Bar..."),
+            (Some(String::from("A Foo, B Bar")), true)
+        );
+        assert_eq!(
+            parse_truncated_authors("A Foo, B Bar, ..."),
+            (Some(String::from("A Foo, B Bar")), true)
+        );
+        assert_eq!(
+            parse_truncated_authors("A Foo, B Bar, +3 more"),
+            (Some(String::from("A Foo, B Bar")), true)
+        );
+    }
+
+    #[test]
+    fn parse_cluster_id_pass() {
+        assert_eq!(parse_cluster_id("cluster=123456").unwrap(), ClusterId(123456));
+        assert_eq!(parse_cluster_id("scholar?cluster=654321").unwrap(), ClusterId(654321));
+        assert_eq!(
+            parse_cluster_id("scholar?cluster=222222&foo=bar").unwrap(),
+            ClusterId(222222)
+        );
+    }
+
+    #[test]
+    fn parse_cluster_id_fail() {
+        assert!(parse_cluster_id("foo").is_err());
+        assert!(parse_cluster_id("claster=000000").is_err());
+        assert!(parse_cluster_id("cluster=aaaaaa").is_err());
+    }
+
+    #[test]
+    fn parse_info_id_test() {
+        assert_eq!(
+            parse_info_id("return gs_ocit(event,'n-S3Szmx-uQJ','0')"),
+            Some(String::from("n-S3Szmx-uQJ"))
+        );
+        assert_eq!(
+            parse_info_id("return gs_ocit(event, 'KAyT5n6OsHYJ', '1', 'A')"),
+            Some(String::from("KAyT5n6OsHYJ"))
+        );
+        assert_eq!(
+            parse_info_id("/scholar?q=info:0_CUMSNp9kwJ:scholar.google.com/&output=cite"),
+            Some(String::from("0_CUMSNp9kwJ"))
+        );
+        assert_eq!(parse_info_id("return gs_ocit(event,'0')"), None);
+        assert_eq!(parse_info_id("javascript:void(0)"), None);
+    }
+
+    #[test]
+    fn parse_related_from_url_test() {
+        assert_eq!(
+            parse_related_from_url("/scholar?q=related:L2PFmFi6uNMJ:scholar.google.com/&hl=en")
+                .unwrap(),
+            "L2PFmFi6uNMJ"
+        );
+        assert_eq!(
+            parse_related_from_url("/scholar?hl=en&q=related:pO-BQTvdAmsJ:scholar.google.com/")
+                .unwrap(),
+            "pO-BQTvdAmsJ"
+        );
+        assert!(parse_related_from_url("/scholar?q=related").is_err());
+        assert!(parse_related_from_url("/scholar?q=related:").is_err());
+        assert!(parse_related_from_url("/scholar?q=unrelated:foo").is_err());
+    }
+
+    #[test]
+    fn parse_versions_cluster_id_test() {
+        assert_eq!(
+            parse_versions_cluster_id("scholar?cluster=222222&foo=bar").unwrap(),
+            ClusterId(222222)
+        );
+        assert!(parse_versions_cluster_id("scholar?cites=111111").is_err());
+        assert!(parse_versions_cluster_id("scholar?q=related:foo&subcluster=1").is_err());
+    }
+
+    #[test]
+    fn search_document_work_id_test() {
+        let html = r#"<div id="gs_res_ccl_mid"><div class="gs_r">
+            <div class="gs_ri">
+              <h3 class="gs_rt">foo</h3>
+              <div class="gs_fl">
+                <a href="/scholar?cites=111">Cited by 1</a>
+                <a href="/scholar?cluster=222">All 2 versions</a>
+              </div>
+            </div>
+          </div></div>"#;
+        let papers = SearchDocument::from(html).scrape_papers().unwrap();
+
+        assert_eq!(papers[0].cluster_id, Some(ClusterId(111)));
+        assert_eq!(papers[0].versions_cluster_id, Some(ClusterId(222)));
+        assert_eq!(papers[0].work_id(), Some(ClusterId(222)));
+        assert_eq!(papers[0].versions_count, Some(2));
+        assert_eq!(
+            papers[0].versions_url,
+            Some(String::from("https://scholar.google.com/scholar?cluster=222"))
+        );
+    }
+
+    #[test]
+    fn search_document_title_cleaned_test() {
+        let html = r#"<div id="gs_res_ccl_mid">
+            <div class="gs_r"><div class="gs_ri">
+              <h3 class="gs_rt"><a href="http://example.com/">Quantum field theory
+                  &amp;amp;   critical phenomena </a></h3>
+            </div></div>
+            <div class="gs_r"><div class="gs_ri">
+              <h3 class="gs_rt"><span>[CITATION]</span>
+                Berry&amp;#39;s   phase
+              </h3>
+            </div></div>
+          </div>"#;
+        let papers = SearchDocument::from(html).scrape_papers().unwrap();
+
+        assert_eq!(papers[0].title, "Quantum field theory & critical phenomena");
+        assert_eq!(papers[1].title, "Berry's phase");
+    }
+
+    #[test]
+    fn search_document_doc_type_test() {
+        let html = r#"<div id="gs_res_ccl_mid">
+            <div class="gs_r"><div class="gs_ri">
+              <h3 class="gs_rt">
+                <span class="gs_ct1">[BOOK]</span><span class="gs_ct2">[B]</span>
+                <a href="http://example.com/book">Quantum field theory</a>
+              </h3>
+              <div class="gs_fl"><a href="/scholar?cites=111">Cited by 1</a></div>
+            </div></div>
+            <div class="gs_r"><div class="gs_ri">
+              <h3 class="gs_rt"><span class="gs_ctg2">[PDF]</span> foo</h3>
+            </div></div>
+            <div class="gs_r"><div class="gs_ri">
+              <h3 class="gs_rt"><span class="gs_ct2">[C]</span> bar</h3>
+            </div></div>
+            <div class="gs_r"><div class="gs_ri">
+              <h3 class="gs_rt"><span class="gs_trk">&#8203;</span> baz</h3>
+            </div></div>
+          </div>"#;
+        let papers = SearchDocument::from(html).scrape_papers().unwrap();
+
+        assert_eq!(papers[0].title, "Quantum field theory");
+        assert_eq!(papers[0].doc_type, Some(DocType::Book));
+        assert_eq!(papers[0].cluster_id, Some(ClusterId(111)));
+        assert_eq!(papers[1].doc_type, Some(DocType::Pdf));
+        assert_eq!(papers[2].doc_type, Some(DocType::Citation));
+        assert_eq!(papers[2].cluster_id, None);
+        assert_eq!(papers[3].doc_type, None);
+    }
+
+    #[cfg(feature = "gzip")]
+    #[test]
+    fn search_document_from_read_gzip_test() {
+        use std::fs;
+        use std::io::{Read, Write};
+        use flate2::Compression;
+        use flate2::write::GzEncoder;
+
+        const PATH: &str = "src/test_html/search/quantum_theory.html";
+
+        let mut html = Vec::new();
+        fs::File::open(PATH).unwrap().read_to_end(&mut html).unwrap();
+        let gzipped = {
+            let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+            encoder.write_all(&html).unwrap();
+            encoder.finish().unwrap()
+        };
+
+        let papers = SearchDocument::from_read(&html[..])
+            .unwrap()
+            .scrape_papers()
+            .unwrap();
+        assert_eq!(papers.len(), 10);
+
+        let doc = SearchDocument::from_read_gzip(&gzipped[..]).unwrap();
+        assert_eq!(doc.scrape_papers().unwrap(), papers);
+        let doc = SearchDocument::from_read_auto(&gzipped[..]).unwrap();
+        assert_eq!(doc.scrape_papers().unwrap(), papers);
+        let doc = SearchDocument::from_read_auto(&html[..]).unwrap();
+        assert_eq!(doc.scrape_papers().unwrap(), papers);
+
+        assert!(SearchDocument::from_read_gzip(&html[..]).is_err());
+    }
+
+    #[test]
+    fn search_document_papers_test() {
+        let results = (0..10)
+            .map(|i| {
+                let title = if i == 4 {
+                    String::new()
+                } else {
+                    format!(r#"<h3 class="gs_rt">paper {}</h3>"#, i)
+                };
+                format!(r#"<div class="gs_r"><div class="gs_ri">{}</div></div>"#, title)
+            })
+            .collect::<String>();
+        let doc = SearchDocument::from(&*format!(r#"<div id="gs_res_ccl_mid">{}</div>"#, results));
+
+        let papers = doc.papers().collect::<Vec<_>>();
+        assert_eq!(papers.len(), 10);
+        assert_eq!(papers.iter().filter(|p| p.is_ok()).count(), 9);
+        match papers[4] {
+            Err(Error(ErrorKind::BadHtml(ref section, ref snippet), _)) => {
+                assert_eq!(section, "title");
+                assert_eq!(snippet, r#"<div class="gs_ri"></div>"#);
+            }
+            _ => panic!("a result without title should be rejected"),
+        }
+        assert_eq!(papers[5].as_ref().unwrap().title, "paper 5");
+        assert!(doc.scrape_papers().is_err());
+
+        let first = doc.papers().take(2).collect::<Result<Vec<_>>>().unwrap();
+        assert_eq!(first.len(), 2);
+    }
+
+    #[test]
+    fn search_document_scrape_papers_with_test() {
+        let results = (0..3)
+            .map(|i| {
+                let badge = if i == 1 {
+                    r#"<span class="publisher-badge">Open Access</span>"#
+                } else {
+                    ""
+                };
+                let title = format!(r#"<h3 class="gs_rt">paper {}</h3>"#, i);
+                format!(
+                    r#"<div class="gs_r"><div class="gs_ri">{}</div>{}</div>"#,
+                    title, badge
+                )
+            })
+            .collect::<String>();
+        let doc = SearchDocument::from(&*format!(r#"<div id="gs_res_ccl_mid">{}</div>"#, results));
+
+        let scraped = doc.scrape_papers_with(|paper, node| {
+            assert!(node.html().contains(&paper.title));
+            node.find(Class("publisher-badge")).nth(0).map(|b| b.text())
+        }).unwrap();
+        assert_eq!(
+            scraped
+                .iter()
+                .map(|(paper, badge)| (&paper.title[..], badge.as_ref().map(|b| &b[..])))
+                .collect::<Vec<_>>(),
+            vec![("paper 0", None), ("paper 1", Some("Open Access")), ("paper 2", None)]
+        );
+        assert_eq!(
+            scraped.into_iter().map(|(paper, _)| paper).collect::<Vec<_>>(),
+            doc.scrape_papers().unwrap()
+        );
+
+        let doc = SearchDocument::from(
+            r#"<div id="gs_res_ccl_mid"><div class="gs_r"><div class="gs_ri"></div></div></div>"#,
+        );
+        assert!(doc.scrape_papers_with(|_, _| ()).is_err());
+        let doc = SearchDocument::from("<html></html>");
+        match doc.scrape_papers_with(|_, _| ()) {
+            Err(Error(ErrorKind::NoResultsContainer, _)) => {}
+            result => panic!("unexpected result: {:?}", result.map(|r| r.len())),
+        }
+    }
+
+    #[test]
+    fn search_document_scrape_papers_lossy_test() {
+        let results = (0..3)
+            .map(|i| {
+                let title = if i == 1 {
+                    String::new()
+                } else {
+                    format!(r#"<h3 class="gs_rt">paper {}</h3>"#, i)
+                };
+                format!(r#"<div class="gs_r"><div class="gs_ri">{}</div></div>"#, title)
+            })
+            .collect::<String>();
+        let doc = SearchDocument::from(&*format!(r#"<div id="gs_res_ccl_mid">{}</div>"#, results));
+
+        let (papers, errors) = doc.scrape_papers_lossy().unwrap();
+        assert_eq!(
+            papers.iter().map(|p| &p.title[..]).collect::<Vec<_>>(),
+            vec!["paper 0", "paper 2"]
+        );
+        assert_eq!(papers[1].result_position, Some(2));
+        assert_eq!(errors.len(), 1);
+        match errors[0] {
+            Error(ErrorKind::BadHtml(ref section, _), _) => assert_eq!(section, "title"),
+            _ => panic!("a result without title should be reported"),
+        }
+
+        let doc = SearchDocument::from("<div></div>");
+        match doc.scrape_papers_lossy() {
+            Err(Error(ErrorKind::NoResultsContainer, _)) => {}
+            _ => panic!("a page without results container should be rejected"),
+        }
+    }
+
+    #[test]
+    fn search_document_layout_test() {
+        use std::fs;
+
+        let open = |path| SearchDocument::from_read(fs::File::open(path).unwrap()).unwrap();
+
+        let desktop = open("src/test_html/search/truncated_authors.html");
+        assert_eq!(desktop.layout(), Some(Layout::Desktop));
+        let mobile = open("src/test_html/search/mobile.html");
+        assert_eq!(mobile.layout(), Some(Layout::Mobile));
+        let no_container = open("src/test_html/search/no_results_container.html");
+        assert_eq!(no_container.layout(), None);
+
+        let papers = mobile.scrape_papers().unwrap();
+        assert_eq!(papers.len(), 2);
+
+        let berry = &papers[0];
+        assert_eq!(berry.title, "Quantal phase factors accompanying adiabatic changes");
+        assert_eq!(berry.cluster_id, Some(ClusterId(12_598_128_798_084_929_708)));
+        assert_eq!(berry.authors, Some(String::from("MV Berry")));
+        assert_eq!(berry.year, Some(1984));
+        assert_eq!(berry.citation_count, Some(10208.into()));
+        assert_eq!(berry.versions_count, Some(10));
+        assert_eq!(berry.info_id, Some(String::from("rPiFOXbS5a4J")));
+        assert_eq!(berry.result_position, Some(0));
+        assert_eq!(berry.access, Access::FullText);
+        assert_eq!(
+            berry.pdf_link,
+            Some(String::from("http://www.physics.iitm.ac.in/~arul/berry_phase.pdf"))
+        );
+
+        assert_eq!(papers[1].cluster_id, Some(ClusterId(10_590_291_297_760_672_718)));
+        assert_eq!(papers[1].result_position, Some(1));
+        assert_eq!(papers[1].access, Access::Unknown);
+    }
+
+    #[test]
+    fn search_document_selectors_test() {
+        // Renamed by a hypothetical change of the markup
+        let html = r#"<div id="gs_res_new"><div class="gs_r">
+            <div class="gs_ri_new">
+              <h3 class="gs_rt_new"><a href="https://example.com/foo">foo</a></h3>
+              <div class="gs_a_new">A Foo - Journal, 2001 - example.com</div>
+              <div class="gs_fl_new"><a href="/scholar?cites=111">Cited by 1</a></div>
+            </div>
+          </div></div>"#;
+
+        let mut doc = SearchDocument::from(html);
+        assert!(doc.scrape_papers().is_err());
+
+        let selectors = SelectorProfile {
+            container_id_desktop: String::from("gs_res_new"),
+            paper_class_desktop: String::from("gs_ri_new"),
+            title_class: String::from("gs_rt_new"),
+            byline_class: String::from("gs_a_new"),
+            footer_class: String::from("gs_fl_new"),
+            ..SelectorProfile::default()
+        };
+        doc.set_options(ScrapeOptions {
+            selectors,
+            ..ScrapeOptions::default()
+        });
+
+        assert_eq!(doc.layout(), Some(Layout::Desktop));
+        let papers = doc.scrape_papers().unwrap();
+        assert_eq!(papers.len(), 1);
+        assert_eq!(papers[0].title, "foo");
+        assert_eq!(papers[0].authors, Some(String::from("A Foo")));
+        assert_eq!(papers[0].year, Some(2001));
+        assert_eq!(papers[0].cluster_id, Some(ClusterId(111)));
+    }
+
+    #[test]
+    fn search_document_no_versions_test() {
+        let html = r#"<div id="gs_res_ccl_mid"><div class="gs_r">
+            <div class="gs_ri">
+              <h3 class="gs_rt">foo</h3>
+              <div class="gs_fl"><a href="/scholar?cites=111">Cited by 1</a></div>
+            </div>
+          </div></div>"#;
+        let papers = SearchDocument::from(html).scrape_papers().unwrap();
+
+        assert_eq!(papers[0].versions_cluster_id, None);
+        assert_eq!(papers[0].versions_count, None);
+        assert_eq!(papers[0].versions_url, None);
+        assert_eq!(papers[0].related_url, None);
+    }
+
+    #[test]
+    fn parse_versions_count_test() {
+        assert_eq!(parse_versions_count("All 12 versions").unwrap(), 12);
+        assert_eq!(parse_versions_count("全 12 バージョン").unwrap(), 12);
+        assert_eq!(parse_versions_count("Todas las 1.234 versiones").unwrap(), 1234);
+        assert_eq!(parse_versions_count("كل الإصدارات البالغ عددها ١٢").unwrap(), 12);
+        assert!(parse_versions_count("All versions").is_err());
+    }
+
+    #[test]
+    fn parse_citation_count_pass() {
+        assert_eq!(parse_citation_count("Cited by 111").unwrap(), 111.into());
+        assert_eq!(parse_citation_count("引用元 222").unwrap(), 222.into());
+        assert_eq!(parse_citation_count("Cited by 1,234").unwrap(), 1234.into());
+        assert_eq!(parse_citation_count("Cited by 12 345").unwrap(), 12345.into());
+        assert_eq!(parse_citation_count("引用元 1,234").unwrap(), 1234.into());
+        assert_eq!(
+            parse_citation_count("Zitiert von: 1.234.567").unwrap(),
+            1234567.into()
+        );
+        assert_eq!(
+            parse_citation_count("Cité 12\u{a0}345 fois").unwrap(),
+            12345.into()
+        );
+        assert_eq!(
+            parse_citation_count("Cited by 12\u{202f}345").unwrap(),
+            12345.into()
+        );
+        assert_eq!(parse_citation_count("1234회 인용").unwrap(), 1234.into());
+        assert_eq!(parse_citation_count("Cited by 0").unwrap(), 0.into());
+    }
+
+    #[test]
+    fn parse_citation_count_localized() {
+        // French, German, Chinese (simplified and traditional), and Arabic footers
+        assert_eq!(parse_citation_count("Cité 1 234 fois").unwrap(), 1234.into());
+        assert_eq!(
+            parse_citation_count("Cité 12\u{202f}345\u{a0}fois").unwrap(),
+            12345.into()
+        );
+        assert_eq!(parse_citation_count("Zitiert von: 1.234").unwrap(), 1234.into());
+        assert_eq!(parse_citation_count("Zitiert von: 56").unwrap(), 56.into());
+        assert_eq!(parse_citation_count("被引用次数：1,234").unwrap(), 1234.into());
+        assert_eq!(parse_citation_count("被引用次數：１２３").unwrap(), 123.into());
+        assert_eq!(
+            parse_citation_count("\u{200f}تمت الإشارة إليه ١٬٢٣٤ مرة").unwrap(),
+            1234.into()
+        );
+        assert_eq!(
+            parse_citation_count("\u{200f}تمت الإشارة إليه \u{200e}1234\u{200e} مرة").unwrap(),
+            1234.into()
+        );
+        assert_eq!(
+            parse_citation_count("تمت الإشارة إليه ۱۲۳ مرة").unwrap(),
+            123.into()
+        );
+    }
+
+    #[test]
+    fn parse_citation_count_approximate() {
+        let approximate = |value| CitationCount {
+            value,
+            approximate: true,
+        };
+        assert_eq!(
+            parse_citation_count("Cited by about 1,000").unwrap(),
+            approximate(1000)
+        );
+        assert_eq!(
+            parse_citation_count("Cited by 10000+").unwrap(),
+            approximate(10000)
+        );
+        assert_eq!(
+            parse_citation_count("Cited by ~500").unwrap(),
+            approximate(500)
+        );
+    }
+
+    #[test]
+    fn parse_citation_count_abbreviated() {
+        let approximate = |value| CitationCount {
+            value,
+            approximate: true,
+        };
+        assert_eq!(
+            parse_citation_count("Cited by 2K").unwrap(),
+            approximate(2000)
+        );
+        assert_eq!(
+            parse_citation_count("Cited by 1.5K").unwrap(),
+            approximate(1500)
+        );
+        assert_eq!(
+            parse_citation_count("Cited by 3M").unwrap(),
+            approximate(3_000_000)
+        );
+        assert_eq!(
+            parse_citation_count("Cited by 2k").unwrap(),
+            approximate(2000)
+        );
+        assert!(parse_citation_count("Cited by 5000M").is_err());
+    }
+
+    #[test]
+    fn parse_citation_count_fail() {
+        assert!(parse_citation_count("foo").is_err());
+        assert!(parse_citation_count("Cited by").is_err());
+        assert!(parse_citation_count("Cited by 9,999,999,999").is_err());
+        assert!(parse_citation_count("Cited by 4294967296").is_err());
+        assert!(parse_citation_count("Cited by 99999999999999999999999").is_err());
+    }
+
+    #[test]
+    fn parse_result_range_pass() {
+        assert_eq!(
+            parse_result_range("Showing results 11–20 of about 1,960,000").unwrap(),
+            (11, 20)
+        );
+        assert_eq!(parse_result_range("Results 991 - 1,000").unwrap(), (991, 1000));
+    }
+
+    #[test]
+    fn parse_result_range_fail() {
+        assert!(parse_result_range("About 1,960,000 results (0.32 sec)").is_err());
+        assert!(parse_result_range("foo").is_err());
+    }
+
+    #[test]
+    fn parse_result_count_test() {
+        assert_eq!(
+            parse_result_count("About 1,960,000 results (0.05 sec)").unwrap(),
+            1960000
+        );
+        assert_eq!(
+            parse_result_count("Page 100 of about 1,960,000 results (0.11 sec)").unwrap(),
+            1960000
+        );
+        assert_eq!(
+            parse_result_count("Showing results 11–20 of about 1,960,000 (0.08 sec)").unwrap(),
+            1960000
+        );
+        assert_eq!(parse_result_count("6 results (0.01 sec)").unwrap(), 6);
+        assert_eq!(parse_result_count("Page 2 of 16 results (0.01 sec)").unwrap(), 16);
+        assert_eq!(
+            parse_result_count("Ungefähr 1.960.000 Ergebnisse (0,05 Sek.)").unwrap(),
+            1960000
+        );
+        assert_eq!(parse_result_count("約 1,960,000 件 (0.05 秒)").unwrap(), 1960000);
+        assert_eq!(
+            parse_result_count("حوالي ١٬٩٦٠٬٠٠٠ نتيجة (٠٫٠٥ ثانية)").unwrap(),
+            1960000
+        );
+        assert!(parse_result_count("No results (0.01 sec)").is_err());
+    }
+
+    #[test]
+    fn parse_result_stats_test() {
+        assert!(is_approximate_result_count("About 1,960,000 results (0.05 sec)"));
+        assert!(is_approximate_result_count("Ungefähr 1.960.000 Ergebnisse (0,05 Sek.)"));
+        assert!(is_approximate_result_count("約 1,960,000 件 (0.05 秒)"));
+        assert!(!is_approximate_result_count("6 results (0.01 sec)"));
+        assert!(!is_approximate_result_count("Page 2 of 16 results (0.01 sec)"));
+
+        assert_eq!(
+            parse_query_time("About 1,960,000 results (0.05 sec)"),
+            Some(Duration::from_millis(50))
+        );
+        assert_eq!(
+            parse_query_time("Ungefähr 1.960.000 Ergebnisse (0,123 Sek.)"),
+            Some(Duration::from_millis(123))
+        );
+        assert_eq!(parse_query_time("6 results (1 sec)"), Some(Duration::from_secs(1)));
+        assert_eq!(parse_query_time("About 1,960,000 results"), None);
+    }
+
+    #[test]
+    fn parse_search_scope_test() {
+        assert_eq!(parse_search_scope("0,5"), SearchScope::Articles);
+        assert_eq!(parse_search_scope("2005"), SearchScope::Articles);
+        assert_eq!(parse_search_scope("2006"), SearchScope::CaseLaw);
+        assert_eq!(parse_search_scope("4,33"), SearchScope::CaseLaw);
+        assert_eq!(parse_search_scope("foo"), SearchScope::Unknown);
+    }
+
+    #[test]
+    fn parse_case_header_test() {
+        let header = parse_case_header("347 US 483\u{a0}- Supreme Court, 1954 - Google Scholar");
+        assert_eq!(header.citation, Some(String::from("347 US 483")));
+        assert_eq!(header.court, Some(String::from("Supreme Court")));
+        assert_eq!(header.year, Some(1954));
+
+        let header = parse_case_header(" - Court of Appeals - Google Scholar");
+        assert_eq!(header.citation, None);
+        assert_eq!(header.court, Some(String::from("Court of Appeals")));
+        assert_eq!(header.year, None);
+
+        let header = parse_case_header("347 US 483 - 1954 - Google Scholar");
+        assert_eq!(header.court, None);
+        assert_eq!(header.year, Some(1954));
+    }
+
+    #[test]
+    fn parse_case_id_test() {
+        assert_eq!(
+            parse_case_id("/scholar_case?case=15256069523383598895&hl=en").unwrap(),
+            15256069523383598895
+        );
+        assert!(parse_case_id("/scholar?cluster=0").is_err());
+    }
+
+    #[test]
+    fn from_read_sanity_check_test() {
+        use std::fs;
+
+        let file = fs::File::open("src/test_html/search/garbage.html").unwrap();
+        match SearchDocument::from_read(file) {
+            Err(Error(ErrorKind::BadHtml(..), _)) => {}
+            _ => panic!("garbage HTML should be rejected"),
+        }
+
+        let file = fs::File::open("src/test_html/search/blocked.html").unwrap();
+        assert!(SearchDocument::from_read(file).is_ok());
+    }
+
+    #[test]
+    fn is_blocked_test() {
+        use std::fs;
+
+        let blocked_doc = {
+            let file = fs::File::open("src/test_html/search/blocked.html").unwrap();
+            Document::from_read(file).unwrap()
+        };
+        assert!(blocked_doc.is_blocked());
+
+        let unblocked_doc = {
+            let file = fs::File::open("src/test_html/search/quantum_theory.html").unwrap();
+            Document::from_read(file).unwrap()
+        };
+
+        assert!(!unblocked_doc.is_blocked());
+
+        let captcha_doc = {
+            let file = fs::File::open("src/test_html/search/captcha.html").unwrap();
+            Document::from_read(file).unwrap()
+        };
+        assert!(captcha_doc.is_blocked());
+
+        let sorry_doc = Document::from(r#"<form action="/sorry/index" method="post"></form>"#);
+        assert!(sorry_doc.is_blocked());
+    }
+
+    #[test]
+    fn search_document_blocked_scrape_test() {
+        use std::fs;
+
+        for path in &[
+            "src/test_html/search/blocked.html",
+            "src/test_html/search/captcha.html",
+        ] {
+            let file = fs::File::open(path).unwrap();
+            let doc = SearchDocument::from_read(file).unwrap();
+            match doc.scrape_papers() {
+                Err(Error(ErrorKind::Blocked(kind), _)) => assert_eq!(doc.block_kind(), Some(kind)),
+                r => panic!("{}: expected Blocked, got {:?}", path, r),
+            }
+        }
+
+        let doc = SearchDocument::from(r#"<div id="gs_res_ccl_mid"></div>"#);
+        assert!(doc.scrape_papers().unwrap().is_empty());
+    }
+
+    #[test]
+    fn search_document_block_kind_test() {
+        use std::fs;
+
+        let block_kind = |path| {
+            let file = fs::File::open(path).unwrap();
+            SearchDocument::from_read(file).unwrap().block_kind()
+        };
+        assert_eq!(
+            block_kind("src/test_html/search/blocked.html"),
+            Some(BlockKind::Captcha)
+        );
+        assert_eq!(
+            block_kind("src/test_html/search/captcha.html"),
+            Some(BlockKind::Captcha)
+        );
+        assert_eq!(block_kind("src/test_html/search/quantum_theory.html"), None);
+
+        let doc = SearchDocument::from(
+            "<div><div>Our systems have detected unusual traffic from your computer network.\
+             </div></div>",
+        );
+        assert_eq!(doc.block_kind(), Some(BlockKind::UnusualTraffic));
+        assert!(doc.is_blocked());
+    }
+
+    #[test]
+    fn search_document_scrape_test() {
+        use std::fs;
+
+        let papers = {
+            let file = fs::File::open("src/test_html/search/quantum_theory.html").unwrap();
+            let doc = SearchDocument::from_read(file).unwrap();
+            doc.scrape_papers().unwrap()
+        };
+
+        assert_eq!(papers.len(), 10);
+
+        assert_eq!(papers[0], {
+            let mut paper = Paper::new(
+                "Quantum field theory and critical phenomena",
+                16499695044466828447,
+            );
+            paper.snippet = Some(String::from(
+                "Abstract Over the last twenty years quantum field theory has become not only the framework for the discussion of all fundamental interactions except gravity, but also for the understanding of second-order phase transitions in statistical mechanics. This advanced text",
+            ));
+            paper.info_id = Some(String::from("n-S3Szmx-uQJ"));
+            paper.related_url = Some(String::from(
+                "https://scholar.google.co.jp/scholar?q=related:n-S3Szmx-uQJ:scholar.google.com/&hl=en&as_sdt=0,5",
+            ));
+            paper.doc_type = Some(DocType::Book);
+            paper.link = Some(String::from("http://cds.cern.ch/record/2280881"));
+            paper.year = Some(1996);
+            paper.citation_count = Some(4821.into());
+            paper.result_position = Some(0);
+            paper.versions_cluster_id = Some(ClusterId(16499695044466828447));
+            paper.versions_count = Some(3);
+            paper.versions_url = Some(String::from(
+                "https://scholar.google.co.jp/scholar?cluster=16499695044466828447&hl=en&as_sdt=0,5",
+            ));
+            paper.authors = Some(String::from("J Zinn-Justin"));
+            paper
+        });
+
+        assert_eq!(papers[1], {
+            let mut paper = Paper::new("Quantum theory of solids", 8552492368061991976);
+            paper.info_id = Some(String::from("KAyT5n6OsHYJ"));
+            paper.related_url = Some(String::from(
+                "https://scholar.google.co.jp/scholar?q=related:KAyT5n6OsHYJ:scholar.google.com/&hl=en&as_sdt=0,5",
+            ));
+            paper.doc_type = Some(DocType::Citation);
+            paper.year = Some(1963);
+            paper.citation_count = Some(4190.into());
+            paper.result_position = Some(1);
+            paper.versions_cluster_id = Some(ClusterId(8552492368061991976));
+            paper.versions_count = Some(3);
+            paper.versions_url = Some(String::from(
+                "https://scholar.google.co.jp/scholar?cluster=8552492368061991976&hl=en&as_sdt=0,5",
+            ));
+            paper.authors = Some(String::from("C Kittel, C Fong"));
+            paper
+        });
+
+        assert_eq!(papers[2], {
+            let mut paper = Paper::new(
+                "Significance of electromagnetic potentials in the quantum theory",
+                5545735591029960915,
+            );
+            paper.doi = Some(String::from("10.1103/PhysRev.115.485"));
+            paper.snippet = Some(String::from(
+                "Abstract In this paper, we discuss some interesting properties of the electromagnetic potentials in the quantum domain. We shall show that, contrary to the conclusions of classical mechanics, there exist effects of potentials on charged particles, even in the region",
+            ));
+            paper.info_id = Some(String::from("0_CUMSNp9kwJ"));
+            paper.related_url = Some(String::from(
+                "https://scholar.google.co.jp/scholar?q=related:0_CUMSNp9kwJ:scholar.google.com/&hl=en&as_sdt=0,5",
+            ));
+            paper.link = Some(String::from(
+                "https://journals.aps.org/pr/abstract/10.1103/PhysRev.115.485",
+            ));
+            paper.year = Some(1959);
+            paper.citation_count = Some(6961.into());
+            paper.result_position = Some(2);
+            paper.versions_cluster_id = Some(ClusterId(5545735591029960915));
+            paper.versions_count = Some(21);
+            paper.versions_url = Some(String::from(
+                "https://scholar.google.co.jp/scholar?cluster=5545735591029960915&hl=en&as_sdt=0,5",
+            ));
+            paper.authors = Some(String::from("Y Aharonov, D Bohm"));
+            paper.venue = Some(String::from("Physical Review"));
+            paper.access = Access::FullText;
+            paper.pdf_link = Some(String::from("http://link.aps.org/pdf/10.1103/PhysRev.115.485"));
+            paper
+        });
+    }
+
+    #[test]
+    fn search_document_scrape_idempotent_test() {
+        use std::fs;
+
+        let doc = {
+            let file = fs::File::open("src/test_html/search/quantum_theory.html").unwrap();
+            SearchDocument::from_read(file).unwrap()
+        };
+
+        let papers = doc.scrape_papers().unwrap();
+        assert_eq!(papers.len(), 10);
+        assert_eq!(doc.scrape_papers().unwrap(), papers);
+        assert_eq!(doc.0.scrape_papers().unwrap(), papers);
+    }
+
+    #[test]
+    fn search_document_text_cleaner_test() {
+        use std::fs;
+
+        let mut doc = {
+            let file = fs::File::open("src/test_html/search/quantum_theory.html").unwrap();
+            SearchDocument::from_read(file).unwrap()
+        };
+
+        let options = ScrapeOptions {
+            text_cleaner: TextCleaner {
+                strip_highlights: false,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        doc.set_options(options.clone());
+        assert_eq!(doc.get_options(), &options);
+
+        let papers = doc.scrape_papers().unwrap();
+        assert_eq!(
+            papers[0].title,
+            "<b>Quantum </b>field <b>theory </b>and critical phenomena"
+        );
+        assert_eq!(papers[1].title, "<b>Quantum theory </b>of solids");
+        assert!(
+            papers[0]
+                .snippet
+                .as_ref()
+                .unwrap()
+                .starts_with("Abstract Over the last twenty years <b>quantum </b>field <b>theory")
+        );
+    }
+
+    #[test]
+    fn search_document_access_test() {
+        use std::fs;
+
+        let papers = {
+            let file = fs::File::open("src/test_html/search/access.html").unwrap();
+            let doc = SearchDocument::from_read(file).unwrap();
+            doc.scrape_papers().unwrap()
+        };
+
+        assert_eq!(
+            papers[0].pdf_link,
+            Some(String::from("http://link.aps.org/pdf/10.1103/PhysRev.115.485"))
+        );
+        assert!(papers[1..].iter().all(|p| p.pdf_link.is_none()));
+
+        let access = papers.iter().map(|p| p.access).collect::<Vec<_>>();
+        assert_eq!(
+            access,
+            vec![
+                Access::FullText,
+                Access::Resolver,
+                Access::ExplicitlyUnavailable,
+                Access::Unknown,
+            ]
+        );
+    }
+
+    #[test]
+    fn search_document_uncited_test() {
+        let doc = SearchDocument::from(
+            r#"<div id="gs_res_ccl_mid">
+                 <div class="gs_r"><div class="gs_ri">
+                   <h3 class="gs_rt"><a href="https://example.com/cited">cited</a></h3>
+                   <div class="gs_fl">
+                     <a href="/scholar?cites=111">Cited by 9</a>
+                     <a href="/scholar?cluster=111">All 2 versions</a>
+                   </div>
+                 </div></div>
+                 <div class="gs_r"><div class="gs_ri">
+                   <h3 class="gs_rt"><a href="https://example.com/uncited">uncited</a></h3>
+                   <div class="gs_fl">
+                     <a href="/scholar?cluster=222">All 3 versions</a>
+                   </div>
+                 </div></div>
+                 <div class="gs_r"><div class="gs_ri">
+                   <h3 class="gs_rt"><a href="https://example.com/new">new</a></h3>
+                   <div class="gs_fl"><a href="javascript:void(0)" class="gs_or_sav">Save</a></div>
+                 </div></div>
+                 <div class="gs_r"><div class="gs_ri">
+                   <h3 class="gs_rt"><a href="/scholar?cluster=333&hl=en">in cluster</a></h3>
+                   <div class="gs_fl"></div>
+                 </div></div>
+               </div>"#,
+        );
+
+        let papers = doc.scrape_papers().unwrap();
+        assert_eq!(papers.len(), 4);
+
+        assert_eq!(papers[0].cluster_id, Some(ClusterId(111)));
+        assert_eq!(papers[0].citation_count, Some(9.into()));
+
+        assert_eq!(papers[1].cluster_id, Some(ClusterId(222)));
+        assert_eq!(papers[1].citation_count, None);
+
+        assert_eq!(papers[2].title, "new");
+        assert_eq!(papers[2].cluster_id, None);
+        assert_eq!(papers[2].citation_count, None);
+        assert_eq!(papers[2].citation_url, None);
+
+        assert_eq!(papers[3].cluster_id, Some(ClusterId(333)));
+        assert_eq!(papers[3].citation_count, None);
+    }
+
+    #[test]
+    fn search_document_truncated_authors_test() {
+        use std::fs;
+
+        let papers = {
+            let file = fs::File::open("src/test_html/search/truncated_authors.html").unwrap();
+            let doc = SearchDocument::from_read(file).unwrap();
+            doc.scrape_papers().unwrap()
+        };
+
+        let authors = papers
+            .iter()
+            .map(|p| (p.authors.as_ref().unwrap().as_str(), p.authors_truncated))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            authors,
+            vec![
+                ("G Aad, T Abajyan, B Abbott, J Abdallah", true),
+                ("S Chatrchyan, V Khachatryan, AM Sirunyan", true),
+                ("PW Higgs", false),
+            ]
+        );
+    }
+
+    #[test]
+    fn search_document_save_token_test() {
+        use std::fs;
+
+        let papers = {
+            let file = fs::File::open("src/test_html/search/signed_in.html").unwrap();
+            let doc = SearchDocument::from_read(file).unwrap();
+            doc.scrape_papers().unwrap()
+        };
+        assert_eq!(
+            papers[0].save_token,
+            Some(String::from("AMstHGQAAAAAWimOQy8fR2kTtNo0bSvD3aYXzYl1Jj0a"))
+        );
+        assert_eq!(papers[1].save_token, None);
+
+        let papers = {
+            let file = fs::File::open("src/test_html/search/quantum_theory.html").unwrap();
+            let doc = SearchDocument::from_read(file).unwrap();
+            doc.scrape_papers().unwrap()
+        };
+        assert!(papers.iter().all(|p| p.save_token.is_none()));
+    }
+
+    #[test]
+    fn search_document_raw_html_test() {
+        use std::fs;
+
+        let mut doc = {
+            let file = fs::File::open("src/test_html/search/quantum_theory.html").unwrap();
+            SearchDocument::from_read(file).unwrap()
+        };
+        assert!(doc.scrape_papers().unwrap()[0].raw_html.is_none());
+
+        doc.set_options(ScrapeOptions {
+            keep_raw_html: true,
+            ..Default::default()
+        });
+        let papers = doc.scrape_papers().unwrap();
+
+        for paper in papers {
+            let raw_html = paper.raw_html.clone().unwrap();
+            let reparsed = {
+                let doc = Document::from(&*raw_html);
+                let node = doc.find(Class("gs_ri")).nth(0).unwrap();
+                let mut p = scrape_paper_one(&node, &ScrapeOptions::default()).unwrap();
+                p.raw_html = Some(raw_html);
+                p
+            };
+            assert_eq!(reparsed, paper);
+        }
+    }
+
+    #[test]
+    fn search_document_dedup_within_page_test() {
+        use std::fs;
+
+        let mut doc = {
+            let file = fs::File::open("src/test_html/search/duplicate_cluster.html").unwrap();
+            SearchDocument::from_read(file).unwrap()
+        };
+
+        let papers = doc.scrape_papers().unwrap();
+        assert_eq!(papers.len(), 3);
+        assert_eq!(papers[0].cluster_id, papers[2].cluster_id);
+
+        doc.set_options(ScrapeOptions {
+            dedup_within_page: true,
+            ..Default::default()
+        });
+        let papers = doc.scrape_papers().unwrap();
+        assert_eq!(papers.len(), 2);
+        assert_eq!(papers[0].cluster_id, Some(ClusterId(15570691018430890829)));
+        assert_eq!(
+            papers[0].link,
+            Some(String::from(
+                "http://rspa.royalsocietypublishing.org/content/royprsa/392/1802/45.full.pdf"
+            ))
+        );
+        assert_eq!(papers[0].result_position, Some(0));
+        assert_eq!(papers[1].cluster_id, Some(ClusterId(6757000624984442995)));
+        assert_eq!(papers[1].result_position, Some(1));
+    }
+
+    #[test]
+    fn search_document_all_result_blocks_test() {
+        use std::fs;
+
+        let mut doc = {
+            let file = fs::File::open("src/test_html/search/concatenated_pages.html").unwrap();
+            SearchDocument::from_read(file).unwrap()
+        };
+
+        let papers = doc.scrape_all_result_blocks().unwrap();
+        assert_eq!(papers.len(), 5);
+        assert_eq!(
+            papers.iter().map(|p| p.cluster_id.unwrap().as_u64()).collect::<Vec<_>>(),
+            vec![
+                15570691018430890829,
+                6757000624984442995,
+                15570691018430890829,
+                5541933039251955335,
+                15570691018430890829,
+            ]
+        );
+
+        // Duplicates across pages are kept
+        doc.set_options(ScrapeOptions {
+            dedup_within_page: true,
+            ..Default::default()
+        });
+        let papers = doc.scrape_all_result_blocks().unwrap();
+        assert_eq!(
+            papers.iter().map(|p| p.cluster_id.unwrap().as_u64()).collect::<Vec<_>>(),
+            vec![
+                15570691018430890829,
+                6757000624984442995,
+                5541933039251955335,
+                15570691018430890829,
+            ]
+        );
+    }
+
+    #[test]
+    fn search_document_result_range_test() {
+        use std::fs;
+
+        let doc = {
+            let file = fs::File::open("src/test_html/search/quantum_theory_page2.html").unwrap();
+            SearchDocument::from_read(file).unwrap()
+        };
+        assert_eq!(doc.scrape_result_range(), Some((11, 20)));
+
+        let doc = {
+            let file = fs::File::open("src/test_html/search/quantum_theory.html").unwrap();
+            SearchDocument::from_read(file).unwrap()
+        };
+        assert_eq!(doc.scrape_result_range(), None);
+    }
+
+    #[test]
+    fn search_document_result_position_test() {
+        use std::fs;
+
+        let papers = {
+            let file = fs::File::open("src/test_html/search/quantum_theory_page2.html").unwrap();
+            let doc = SearchDocument::from_read(file).unwrap();
+            doc.scrape_papers().unwrap()
+        };
+        assert_eq!(papers.len(), 2);
+        assert_eq!(papers[0].result_position, Some(10));
+        assert_eq!(papers[1].result_position, Some(11));
+
+        // Fall back to the position in the page
+        let papers = {
+            let html = r#"<div id="gs_res_ccl_mid"><div class="gs_r">
+                <div class="gs_ri">
+                  <h3 class="gs_rt">foo</h3>
+                  <div class="gs_fl"><a href="/scholar?cites=42">Cited by 1</a></div>
+                </div>
+              </div></div>"#;
+            let doc = SearchDocument::from(html);
+            doc.scrape_papers().unwrap()
+        };
+        assert_eq!(papers.len(), 1);
+        assert_eq!(papers[0].result_position, Some(0));
+    }
+
+    #[test]
+    fn case_law_document_scrape_test() {
+        use std::fs;
+
+        let cases = {
+            let file = fs::File::open("src/test_html/case_law/case_law.html").unwrap();
+            let doc = CaseLawDocument::from_read(file).unwrap();
+            doc.scrape_cases().unwrap()
+        };
+
+        assert_eq!(cases.len(), 2);
+
+        assert_eq!(cases[0], {
+            let mut case = CaseLaw::new("Brown v. Board of Education");
+            case.link = Some(String::from(
+                "https://scholar.google.co.jp/scholar_case?\
+                 case=15256069523383598895&q=separate+but+equal&hl=en&as_sdt=2006",
+            ));
+            case.case_id = Some(15256069523383598895);
+            case.citation = Some(String::from("347 US 483, 74 S. Ct. 686, 98 L. Ed. 873"));
+            case.court = Some(String::from("Supreme Court"));
+            case.year = Some(1954);
+            case.citation_count = Some(3702.into());
+            case
+        });
+
+        assert_eq!(cases[1].name, "Plessy v. Ferguson");
+        assert_eq!(cases[1].year, Some(1896));
+        assert_eq!(cases[1].citation_count, Some(2156.into()));
+    }
+
+    #[test]
+    fn saved_searches_document_scrape_test() {
+        use std::fs;
+
+        let searches = {
+            let file = fs::File::open("src/test_html/saved_searches/saved_searches.html").unwrap();
+            let doc = SavedSearchesDocument::from_read(file).unwrap();
+            doc.scrape_saved_searches().unwrap()
+        };
+
+        assert_eq!(
+            searches,
+            vec![
+                SavedSearch {
+                    id: String::from("abCDef012345XYZ"),
+                    query: String::from("quantum theory"),
+                    label: Some(String::from("Thesis")),
+                },
+                SavedSearch {
+                    id: String::from("9zyXWv876543abc"),
+                    query: String::from(r#""berry phase" author:berry"#),
+                    label: None,
+                },
+            ]
+        );
+
+        let doc = SavedSearchesDocument::from("<table id=\"gs_asl_t\"></table>");
+        assert!(doc.scrape_saved_searches().unwrap().is_empty());
+    }
+
+    #[test]
+    fn library_document_scrape_test() {
+        use std::fs;
+
+        let doc = {
+            let file = fs::File::open("src/test_html/library/library.html").unwrap();
+            LibraryDocument::from_read(file).unwrap()
+        };
+
+        let articles = doc.scrape_articles().unwrap();
+        assert_eq!(articles.len(), 2);
+        assert_eq!(
+            articles[0].paper.title,
+            "Quantal phase factors accompanying adiabatic changes"
+        );
+        assert_eq!(
+            articles[0].paper.cluster_id,
+            Some(ClusterId(15_570_691_018_430_890_829))
+        );
+        assert_eq!(articles[0].library_id, Some(String::from("Xy9LmQ3aTbMJ")));
+        assert_eq!(articles[0].labels, vec!["Thesis", "To read"]);
+        assert_eq!(articles[1].paper.result_position, Some(1));
+        assert_eq!(articles[1].library_id, Some(String::from("pV4nTs8cKe0J")));
+        assert!(articles[1].labels.is_empty());
+        assert_eq!(
+            doc.scrape_papers().unwrap(),
+            articles.into_iter().map(|a| a.paper).collect::<Vec<_>>()
+        );
+
+        assert_eq!(
+            doc.scrape_labels().unwrap(),
+            vec![
+                LibraryLabel {
+                    name: String::from("Thesis"),
+                    count: Some(12),
+                    url: String::from("/scholar?scilib=1&scilu=RmVA5XpuUYQJ&hl=en"),
+                },
+                LibraryLabel {
+                    name: String::from("To read"),
+                    count: None,
+                    url: String::from("/scholar?scilib=1&scilu=kQ2bGmC3RwQJ&hl=en"),
+                },
+            ]
+        );
+
+        assert_eq!(
+            doc.next_page_url(),
+            Some(String::from("/scholar?start=10&scilib=1&hl=en"))
+        );
+        assert_eq!(doc.scrape_pagination_offsets(), vec![10]);
+
+        let doc = LibraryDocument::from("<div id=\"gs_res_ccl_mid\"></div>");
+        assert!(doc.scrape_articles().unwrap().is_empty());
+        assert!(doc.scrape_labels().unwrap().is_empty());
+    }
+
+    #[test]
+    fn profile_document_scrape_test() {
+        use std::fs;
+
+        let doc = {
+            let file = fs::File::open("src/test_html/profile/profile.html").unwrap();
+            ProfileDocument::from_read(file).unwrap()
+        };
+        assert_eq!(doc.scrape_author_name().unwrap(), "Michael Berry");
+
+        let articles = doc.scrape_articles().unwrap();
+        assert_eq!(articles.len(), 2);
+
+        let mut paper = Paper::new(
+            "Quantal phase factors accompanying adiabatic changes",
+            15570691018430890829,
+        );
+        paper.authors = Some(String::from("MV Berry"));
+        paper.venue = Some(String::from(
+            "Proceedings of the Royal Society of London. \
+             A. Mathematical and Physical & Engineering Sciences 392 (1802), 45-57",
+        ));
+        paper.year = Some(1984);
+        paper.citation_count = Some(12345.into());
+        paper.profile_article_id = Some(String::from("qc6CJjYAAAAJ:u5HHmVD_uO8C"));
+        assert_eq!(articles[0], paper);
+
+        let mut paper = Paper::new("Regular and irregular semiclassical wavefunctions", 0);
+        paper.set_cluster_id(None);
+        paper.authors = Some(String::from("MV Berry, JP Keating, SD Prado"));
+        paper.authors_truncated = true;
+        paper.venue = Some(String::from(
+            "Journal of Physics A: Mathematical and General 10 (12), 2083",
+        ));
+        paper.year = Some(1977);
+        paper.profile_article_id = Some(String::from("qc6CJjYAAAAJ:d1gkVwhDpl0C"));
+        assert_eq!(articles[1], paper);
+
+        let metrics = doc.compute_metrics().unwrap();
+        assert_eq!(metrics.citations, 12345);
+        assert_eq!(metrics.h_index, 1);
+        assert_eq!(metrics.i10_index, 1);
+
+        let doc = ProfileDocument::from("<div id=\"gsc_prf_in\"> </div>");
+        assert!(doc.scrape_author_name().is_err());
+        assert!(doc.scrape_articles().unwrap().is_empty());
+    }
+
+    #[test]
+    fn profile_document_private_test() {
+        use std::fs;
+
+        let doc = {
+            let file = fs::File::open("src/test_html/profile/private.html").unwrap();
+            ProfileDocument::from_read(file).unwrap()
+        };
+        assert!(doc.is_private());
+        match doc.scrape_profile() {
+            Err(Error(ErrorKind::ProfilePrivate, _)) => {}
+            result => panic!("unexpected result: {:?}", result),
+        }
+        match doc.scrape_articles() {
+            Err(Error(ErrorKind::ProfilePrivate, _)) => {}
+            result => panic!("unexpected result: {:?}", result),
+        }
+
+        let doc = {
+            let file = fs::File::open("src/test_html/profile/profile.html").unwrap();
+            ProfileDocument::from_read(file).unwrap()
+        };
+        assert!(!doc.is_private());
+        assert!(!ProfileDocument::from("<div id=\"gsc_a_b\"></div>").is_private());
+    }
+
+    #[test]
+    fn profile_document_scrape_profile_test() {
+        use std::fs;
+
+        let doc = {
+            let file = fs::File::open("src/test_html/profile/profile.html").unwrap();
+            ProfileDocument::from_read(file).unwrap()
+        };
+        assert_eq!(
+            doc.scrape_profile().unwrap(),
+            AuthorProfile {
+                name: String::from("Michael Berry"),
+                affiliation: Some(String::from("Physics, University of Bristol")),
+                email_domain: Some(String::from("bristol.ac.uk")),
+                verified_institution_url: None,
+                interests: vec![
+                    String::from("Physics"),
+                    String::from("Quantum chaos"),
+                    String::from("Optics"),
+                ],
+                citations: Some(ProfileMetric {
+                    all: 81234,
+                    recent: 20123,
+                }),
+                h_index: Some(ProfileMetric { all: 112, recent: 58 }),
+                i10_index: Some(ProfileMetric {
+                    all: 401,
+                    recent: 250,
+                }),
+                recent_since: Some(2013),
+            }
+        );
+        assert!(doc.has_more_articles());
+        assert_eq!(doc.scrape_publication_count(), None);
+        assert_eq!(
+            doc.scrape_citation_chart_url(),
+            Some(String::from(
+                "https://scholar.google.com/citations?view_op=citations_chart&hl=en\
+                 &user=qc6CJjYAAAAJ&ys=2016&ye=2018&cd=3912,4120,4380",
+            ))
+        );
+        assert_eq!(
+            doc.scrape_coauthors().unwrap(),
+            vec![
+                Coauthor {
+                    name: String::from("Jonathan P Keating"),
+                    user_id: String::from("AbC-dEf_012J"),
+                    title: Some(String::from(
+                        "Professor of Mathematics, University of Oxford",
+                    )),
+                    citation_count: Some(21345),
+                },
+                Coauthor {
+                    name: String::from("Mark R Dennis"),
+                    user_id: String::from("Xyz0123_45AJ"),
+                    title: None,
+                    citation_count: None,
+                },
+            ]
+        );
+
+        let doc = ProfileDocument::from(
+            r#"<div id="gsc_prf_i"><div id="gsc_prf_in">Foo</div></div>
+               <button id="gsc_bpf_more" disabled>Show more</button>"#,
+        );
+        let profile = doc.scrape_profile().unwrap();
+        assert_eq!(profile.name, "Foo");
+        assert_eq!(profile.affiliation, None);
+        assert!(profile.interests.is_empty());
+        assert_eq!(profile.h_index, None);
+        assert!(!doc.has_more_articles());
+        assert!(doc.scrape_coauthors().unwrap().is_empty());
+        assert_eq!(doc.scrape_citation_chart_url(), None);
+        assert_eq!(doc.scrape_publication_count(), None);
+
+        let doc = ProfileDocument::from(
+            r#"<div id="gsc_a_sp">1&ndash;20 of 1,345</div><button id="gsc_bpf_more">"#,
+        );
+        assert_eq!(doc.scrape_publication_count(), Some(1345));
+        let doc = ProfileDocument::from(
+            r#"<div id="gsc_a_sp">101&ndash;127</div><button id="gsc_bpf_more" disabled>"#,
+        );
+        assert_eq!(doc.scrape_publication_count(), Some(127));
+
+        let doc = ProfileDocument::from(
+            r#"<div id="gsc_prf_i"><div id="gsc_prf_in">Foo</div>
+               <div class="gsc_prf_il" id="gsc_prf_ivh">Verified email at
+                 <a href="http://www.bristol.ac.uk/" class="gsc_prf_ila">bristol.ac.uk</a> -
+                 <a href="http://example.com/" class="gsc_prf_ila">Homepage</a></div></div>"#,
+        );
+        let profile = doc.scrape_profile().unwrap();
+        assert_eq!(profile.email_domain, Some(String::from("bristol.ac.uk")));
+        assert_eq!(
+            profile.verified_institution_url,
+            Some(String::from("http://www.bristol.ac.uk/"))
+        );
+    }
+
+    #[test]
+    fn related_document_scrape_test() {
+        use std::fs;
+
+        let papers = {
+            let file = fs::File::open("src/test_html/related/berry_phase_related.html").unwrap();
+            let doc = RelatedDocument::from_read(file).unwrap();
+            doc.scrape_papers().unwrap()
+        };
+
+        let titles = papers.iter().map(|p| p.title.as_str()).collect::<Vec<_>>();
+        assert_eq!(
+            titles,
+            vec![
+                "Quantal phase factors accompanying adiabatic changes",
+                "Holonomy, the quantum adiabatic theorem, and Berry's phase",
+            ]
+        );
+        assert_eq!(papers[1].related_id(), Some(String::from("lBmQ7X7vwHsJ")));
+    }
+
+    #[test]
+    fn citation_detail_document_scrape_test() {
+        use std::fs;
+
+        let doc = {
+            let file = fs::File::open("src/test_html/citation_detail/berry_phase.html").unwrap();
+            CitationDetailDocument::from_read(file).unwrap()
+        };
+
+        assert_eq!(
+            doc.scrape_title().unwrap(),
+            "Quantal phase factors accompanying adiabatic changes"
+        );
+
+        let citations = doc.scrape_citations_per_year().unwrap();
+        assert_eq!(
+            citations.into_iter().collect::<Vec<_>>(),
+            vec![
+                (2012, 612),
+                (2013, 668),
+                (2014, 0),
+                (2015, 1021),
+                (2016, 754),
+                (2017, 781),
+                (2018, 226),
+            ]
+        );
+
+        let doc = CitationDetailDocument::from(r#"<div id="gsc_oci_title">Not cited yet</div>"#);
+        assert!(doc.scrape_citations_per_year().unwrap().is_empty());
+    }
+
+    #[test]
+    fn metrics_document_scrape_test() {
+        use std::fs;
+
+        let venues = {
+            let file = fs::File::open("src/test_html/metrics/top_venues.html").unwrap();
+            let doc = MetricsDocument::from_read(file).unwrap();
+            doc.scrape_venue_metrics().unwrap()
+        };
+
+        assert_eq!(venues.len(), 3);
+        assert_eq!(
+            venues[0],
+            VenueMetrics {
+                rank: 1,
+                name: String::from("Physical Review Letters"),
+                h5_index: 175,
+                h5_median: 232,
+                h5_core_url: Some(String::from(
+                    "https://scholar.google.com/citations?hl=en&vq=phy_quantumphysics\
+                     &view_op=list_hcore&venue=qyRrD5RV8eIJ.2020",
+                )),
+                category: Some(String::from("Physics & Mathematics")),
+                subcategory: Some(String::from("Quantum Physics")),
+            }
+        );
+        assert_eq!(venues[2].rank, 3);
+        assert_eq!(venues[2].h5_index, 1024);
+        assert_eq!(venues[2].h5_median, 1536);
+
+        let doc = MetricsDocument::from(
+            r#"<table id="gsc_mvt_table">
+                 <tr><th>Publication</th></tr>
+                 <tr>
+                   <td class="gsc_mvt_p">1.</td>
+                   <td class="gsc_mvt_t">Nature</td>
+                   <td class="gsc_mvt_n"><a href="/citations?view_op=list_hcore">444</a></td>
+                   <td class="gsc_mvt_n"><span>667</span></td>
+                 </tr>
+                 <tr><td class="gsc_mvt_p">2.</td><td class="gsc_mvt_t">Science</td></tr>
+               </table>"#,
+        );
+        match doc.scrape_venue_metrics() {
+            Err(Error(ErrorKind::BadHtml(ref section, _), _)) => assert_eq!(section, "h5-index"),
+            _ => panic!("a row without metrics should be rejected"),
+        }
+    }
+
+    #[test]
+    fn author_search_document_scrape_test() {
+        use std::fs;
+
+        let authors = {
+            let file = fs::File::open("src/test_html/author_search/berry.html").unwrap();
+            let doc = AuthorSearchDocument::from_read(file).unwrap();
+            doc.scrape_authors().unwrap()
+        };
+
+        assert_eq!(
+            authors,
+            vec![
+                AuthorSummary {
+                    name: String::from("Michael Berry"),
+                    user_id: String::from("qc6CJjYAAAAJ"),
+                    affiliation: Some(String::from("Physics, University of Bristol")),
+                    email_domain: Some(String::from("bristol.ac.uk")),
+                    cited_by: Some(81234),
+                    thumbnail_url: Some(String::from(
+                        "https://scholar.googleusercontent.com/citations\
+                         ?view_op=small_photo&user=qc6CJjYAAAAJ&citpid=2",
+                    )),
+                },
+                AuthorSummary {
+                    name: String::from("Jane Berry"),
+                    user_id: String::from("AbC-dEf_012J"),
+                    affiliation: None,
+                    email_domain: None,
+                    cited_by: None,
+                    thumbnail_url: Some(String::from(
+                        "https://scholar.google.com/citations/images/avatar_scholar_56.png",
+                    )),
+                },
+            ]
+        );
+
+        let doc = AuthorSearchDocument::from(
+            r#"<div class="gsc_1usr"><h3 class="gs_ai_name"><a href="/citations">Foo</a></h3></div>"#,
+        );
+        assert!(doc.scrape_authors().is_err());
+    }
+
+    #[test]
+    fn cite_popup_document_export_links_test() {
+        use std::fs;
+
+        let links = {
+            let file = fs::File::open("src/test_html/cite/aharonov_bohm_cite.html").unwrap();
+            let doc = CitePopupDocument::from_read(file).unwrap();
+            doc.scrape_export_links().unwrap()
+        };
+
+        let formats = links.iter().map(|l| l.format.as_str()).collect::<Vec<_>>();
+        assert_eq!(formats, vec!["BibTeX", "EndNote", "RefMan", "RefWorks"]);
+        assert_eq!(
+            links[0].url,
+            "https://scholar.googleusercontent.com/scholar.bib\
+             ?q=info:5HbwekOPDqkJ:scholar.google.com/&output=citation\
+             &scisdr=CgXsOAkeEMeE&scisig=AAGBfm0AAAAA&scisf=4&ct=citation&cd=-1&hl=en"
+        );
 
-    let citation_node = footer_nodes
-        .filter(|n: &Node| {
-            if let Some(id_url) = n.attr("href") {
-                parse_cluster_id(id_url).is_ok()
-            } else {
-                false
-            }
-        })
-        .first();
-    let citation_node = try_html_bad!(citation_node);
+        let doc = CitePopupDocument::from("<div id=\"gs_citt\"></div>");
+        assert!(doc.scrape_export_links().unwrap().is_empty());
+    }
 
-    let cluster_id = {
-        let id_url = citation_node.attr("href").unwrap();
-        parse_cluster_id(id_url).unwrap()
-    };
-    let citation_count = parse_citation_count(&citation_node.text())?;
+    #[test]
+    fn cite_popup_document_formatted_citations_test() {
+        use std::fs;
 
-    Ok(ArticleFooter {
-        cluster_id,
-        citation_count,
-    })
-}
+        let citations = {
+            let file = fs::File::open("src/test_html/cite/aharonov_bohm_cite.html").unwrap();
+            let doc = CitePopupDocument::from_read(file).unwrap();
+            doc.scrape_formatted_citations().unwrap()
+        };
 
-fn parse_cluster_id(url: &str) -> Result<u64> {
-    use regex::Regex;
+        let styles = citations.iter().map(|c| c.style.as_str()).collect::<Vec<_>>();
+        assert_eq!(styles, vec!["MLA", "APA", "Chicago", "Harvard", "Vancouver"]);
+        assert_eq!(
+            citations[1],
+            FormattedCitation {
+                style: String::from("APA"),
+                text: String::from(
+                    "Aharonov, Y., & Bohm, D. (1959). Significance of electromagnetic potentials \
+                     in the quantum theory. Physical Review, 115(3), 485."
+                ),
+            }
+        );
 
-    lazy_static! {
-        static ref RE: Regex = Regex::new(r"(cluster|cites)=(\d+)").unwrap();
+        let doc = CitePopupDocument::from(
+            "<div id=\"gs_citt\"><table><tr><th class=\"gs_cith\">MLA</th></tr></table></div>",
+        );
+        assert!(doc.scrape_formatted_citations().is_err());
     }
 
-    let cluster_id = {
-        let caps = try_html_bad!(RE.captures(url));
-        let id = try_html_bad!(caps.get(2));
-        id.as_str().parse()?
-    };
+    #[test]
+    fn parse_citation_for_view_test() {
+        assert_eq!(
+            parse_citation_for_view("/citations?user=foo&citation_for_view=foo:bar-_1").unwrap(),
+            "foo:bar-_1"
+        );
+        assert!(parse_citation_for_view("/citations?user=foo").is_err());
+    }
 
-    Ok(cluster_id)
-}
+    #[test]
+    fn settings_document_scrape_test() {
+        use std::fs;
 
-fn parse_citation_count(text: &str) -> Result<u32> {
-    use regex::Regex;
+        let settings = {
+            let file = fs::File::open("src/test_html/settings/settings.html").unwrap();
+            let doc = SettingsDocument::from_read(file).unwrap();
+            doc.scrape_settings().unwrap()
+        };
 
-    lazy_static! {
-        static ref RE: Regex = Regex::new(r"[^\d]+(\d+)").unwrap();
+        assert_eq!(
+            settings,
+            Settings {
+                results_per_page: Some(20),
+                bibliography_manager: Some(BibliographyManager::BibTeX),
+                library_links: vec![LibraryLink {
+                    id: String::from("2948727814949403473"),
+                    name: String::from("The University of Tokyo - Find it@UTokyo"),
+                }],
+            }
+        );
+
+        let doc = SettingsDocument::from(
+            r#"<form id="gs_settings_form">
+                 <input type="radio" name="scis" value="no" checked>
+                 <input type="radio" name="scis" value="yes">
+                 <select name="scisf"><option value="4" selected>BibTeX</option></select>
+               </form>"#,
+        );
+        assert_eq!(
+            doc.scrape_settings().unwrap(),
+            Settings {
+                results_per_page: None,
+                bibliography_manager: None,
+                library_links: vec![],
+            }
+        );
     }
 
-    let count = {
-        let caps = try_html_bad!(RE.captures(text));
-        let count = try_html_bad!(caps.get(1));
-        count.as_str().parse().unwrap()
-    };
+    #[test]
+    fn search_document_tracking_title_test() {
+        use std::fs;
 
-    Ok(count)
-}
+        let papers = {
+            let file = fs::File::open("src/test_html/search/tracking_title.html").unwrap();
+            let doc = SearchDocument::from_read(file).unwrap();
+            doc.scrape_papers().unwrap()
+        };
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+        assert_eq!(papers.len(), 3);
+        assert_eq!(
+            papers[0].title,
+            "Topological insulators and superconductors"
+        );
+        assert_eq!(
+            papers[0].link,
+            Some(String::from("https://arxiv.org/abs/1008.2026"))
+        );
+        assert_eq!(papers[1].title, "Colloquium: topological insulators");
+        assert_eq!(
+            papers[1].link,
+            Some(String::from(
+                "https://journals.aps.org/rmp/abstract/10.1103/RevModPhys.82.3045"
+            ))
+        );
+        assert_eq!(papers[2].title, "Quantum spin Hall effect");
+    }
 
     #[test]
-    fn parse_year_pass() {
-        assert_eq!(parse_year("foo - journal, 2000 - bar").unwrap(), 2000);
-        assert_eq!(parse_year("foo bar - 1999 - baz, qrux").unwrap(), 1999);
-        assert_eq!(parse_year("foo - journal, 1998").unwrap(), 1998);
-        assert_eq!(parse_year("foo - 1899").unwrap(), 1899);
-        assert_eq!(parse_year(" - journal, 1898").unwrap(), 1898);
-        assert_eq!(parse_year(" - 1800").unwrap(), 1800);
+    fn search_document_article_type_test() {
+        use std::fs;
+
+        let papers = {
+            let file = fs::File::open("src/test_html/search/review_article.html").unwrap();
+            let doc = SearchDocument::from_read(file).unwrap();
+            doc.scrape_papers().unwrap()
+        };
+
+        assert_eq!(papers.len(), 2);
+        assert_eq!(papers[0].article_type, Some(String::from("Review article")));
+        assert_eq!(papers[1].article_type, None);
     }
 
     #[test]
-    fn parse_year_fail() {
-        assert!(parse_year("foo - journal - bar").is_err());
-        assert!(parse_year("foo - journal").is_err());
-        assert!(parse_year("- journal, 1898").is_err());
-        assert!(parse_year("- 1800").is_err());
+    fn search_document_search_scope_test() {
+        use std::fs;
+
+        let scope = |path| {
+            let file = fs::File::open(path).unwrap();
+            SearchDocument::from_read(file).unwrap().scrape_search_scope()
+        };
+
+        assert_eq!(scope("src/test_html/search/quantum_theory.html"), SearchScope::Articles);
+        assert_eq!(scope("src/test_html/case_law/case_law.html"), SearchScope::CaseLaw);
+        assert_eq!(scope("src/test_html/search/blocked.html"), SearchScope::Unknown);
     }
 
     #[test]
-    fn parse_cluster_id_pass() {
-        assert_eq!(parse_cluster_id("cluster=123456").unwrap(), 123456);
-        assert_eq!(parse_cluster_id("scholar?cluster=654321").unwrap(), 654321);
+    fn search_document_sorted_by_date_test() {
+        use std::fs;
+
+        let doc = {
+            let file = fs::File::open("src/test_html/search/sorted_by_date.html").unwrap();
+            SearchDocument::from_read(file).unwrap()
+        };
+        assert_eq!(doc.scrape_sort_order(), SortOrder::Date);
+        assert_eq!(doc.scrape_year_range(), (Some(2017), None));
+
+        let papers = doc.scrape_papers().unwrap();
+        assert_eq!(papers[0].age_days, Some(3));
         assert_eq!(
-            parse_cluster_id("scholar?cluster=222222&foo=bar").unwrap(),
-            222222
+            papers[0].snippet,
+            Some(String::from(
+                "We study the surface states of a topological insulator thin film …"
+            ))
+        );
+        // The footer has no link bearing the cluster ID
+        assert_eq!(papers[0].cluster_id, None);
+        assert_eq!(papers[0].related_url, None);
+        assert_eq!(papers[1].age_days, Some(1));
+        assert_eq!(
+            papers[1].cluster_id,
+            Some(ClusterId(3_183_435_922_263_403_281))
         );
+        assert_eq!(papers[1].citation_count, Some(2.into()));
+
+        let doc = {
+            let file = fs::File::open("src/test_html/search/quantum_theory.html").unwrap();
+            SearchDocument::from_read(file).unwrap()
+        };
+        assert_eq!(doc.scrape_sort_order(), SortOrder::Relevance);
+        assert_eq!(doc.scrape_year_range(), (None, None));
+        assert!(doc.scrape_papers().unwrap().iter().all(|p| p.age_days.is_none()));
+
+        assert_eq!(parse_age_days("3 days ago - "), Some(3));
+        assert_eq!(parse_age_days("1 day ago - "), Some(1));
+        assert_eq!(parse_age_days("New"), None);
     }
 
     #[test]
-    fn parse_cluster_id_fail() {
-        assert!(parse_cluster_id("foo").is_err());
-        assert!(parse_cluster_id("claster=000000").is_err());
-        assert!(parse_cluster_id("cluster=aaaaaa").is_err());
+    fn search_document_related_searches_test() {
+        use std::fs;
+
+        let doc = {
+            let file = fs::File::open("src/test_html/search/quantum_theory.html").unwrap();
+            SearchDocument::from_read(file).unwrap()
+        };
+        assert_eq!(
+            doc.scrape_related_searches(),
+            vec![
+                "radiation quantum theory",
+                "relativistic quantum theory",
+                "classical quantum theory",
+                "quantum theory consciousness",
+                "molecules quantum theory",
+                "quantum theory solids",
+                "quantum theory atoms",
+                "quantum theory physical principles",
+                "quantum theory einstein",
+                "quantum theory foundations",
+                "atoms in molecules quantum theory",
+                "quantum theory fields",
+                "planck's quantum theory",
+                "quantum theory many-particle systems",
+                "bohm quantum theory",
+                "relativity quantum theory",
+            ]
+        );
+
+        let doc = {
+            let file = fs::File::open("src/test_html/search/quantum_theory_page2.html").unwrap();
+            SearchDocument::from_read(file).unwrap()
+        };
+        assert!(doc.scrape_related_searches().is_empty());
     }
 
     #[test]
-    fn parse_citation_count_pass() {
-        assert_eq!(parse_citation_count("Cited by 111").unwrap(), 111);
-        assert_eq!(parse_citation_count("引用元 222").unwrap(), 222);
+    fn search_document_omitted_results_test() {
+        use std::fs;
+
+        let doc = {
+            let file = fs::File::open("src/test_html/search/omitted_results.html").unwrap();
+            SearchDocument::from_read(file).unwrap()
+        };
+        assert_eq!(
+            doc.scrape_omitted_results_notice(),
+            Some(String::from(
+                "In order to show you the most relevant results, \
+                 we have omitted some entries very similar to the 991 already displayed.\
+                 If you like, you can repeat the search with the omitted results included."
+            ))
+        );
+        assert_eq!(
+            doc.scrape_omitted_results_url(),
+            Some(String::from(
+                "https://scholar.google.co.jp/scholar?q=quantum+theory&hl=en&as_sdt=0,5&filter=0"
+            ))
+        );
+        assert_eq!(doc.scrape_papers().unwrap().len(), 1);
+
+        let doc = {
+            let file = fs::File::open("src/test_html/search/quantum_theory.html").unwrap();
+            SearchDocument::from_read(file).unwrap()
+        };
+        assert_eq!(doc.scrape_omitted_results_notice(), None);
+        assert_eq!(doc.scrape_omitted_results_url(), None);
     }
 
     #[test]
-    fn parse_citation_count_fail() {
-        assert!(parse_citation_count("foo").is_err());
+    fn search_document_result_count_test() {
+        use std::fs;
+
+        let doc = {
+            let file = fs::File::open("src/test_html/search/quantum_theory.html").unwrap();
+            SearchDocument::from_read(file).unwrap()
+        };
+        assert_eq!(doc.result_count().unwrap(), Some(1960000));
+        assert_eq!(
+            doc.scrape_result_stats().unwrap(),
+            Some(ResultStats {
+                total_count: 1960000,
+                approximate: true,
+                query_time: Some(Duration::from_millis(320)),
+            })
+        );
+
+        let doc = {
+            let file = fs::File::open("src/test_html/search/access.html").unwrap();
+            SearchDocument::from_read(file).unwrap()
+        };
+        assert_eq!(doc.result_count().unwrap(), None);
+        assert_eq!(doc.scrape_result_stats().unwrap(), None);
     }
 
     #[test]
-    fn is_blocked_test() {
+    fn search_document_next_page_url_test() {
         use std::fs;
 
-        let blocked_doc = {
-            let file = fs::File::open("src/test_html/blocked.html").unwrap();
-            Document::from_read(file).unwrap()
+        let next_page_url = |path| {
+            let file = fs::File::open(path).unwrap();
+            SearchDocument::from_read(file).unwrap().next_page_url()
         };
-        assert!(blocked_doc.is_blocked());
 
-        let unblocked_doc = {
-            let file = fs::File::open("src/test_html/quantum_theory.html").unwrap();
-            Document::from_read(file).unwrap()
-        };
+        assert_eq!(
+            next_page_url("src/test_html/search/quantum_theory_page2.html"),
+            Some(String::from(
+                "https://scholar.google.co.jp/scholar?start=20&q=quantum+theory&hl=en&as_sdt=0,5"
+            ))
+        );
+        assert_eq!(
+            next_page_url("src/test_html/search/omitted_results.html"),
+            None
+        );
+        assert_eq!(next_page_url("src/test_html/search/access.html"), None);
 
-        assert!(!unblocked_doc.is_blocked());
+        let doc = SearchDocument::from(
+            r#"<div id="gs_n">
+                 <a href="/scholar?start=10&amp;q=foo"><span class="gs_ico_nav_next"></span>Next</a>
+               </div>"#,
+        );
+        assert_eq!(
+            doc.next_page_url(),
+            Some(String::from("/scholar?start=10&q=foo"))
+        );
     }
 
     #[test]
-    fn search_document_scrape_test() {
+    fn search_document_pagination_offsets_test() {
         use std::fs;
 
-        let papers = {
-            let file = fs::File::open("src/test_html/quantum_theory.html").unwrap();
-            let doc = SearchDocument::from_read(file).unwrap();
-            doc.scrape_papers().unwrap()
+        let doc = {
+            let file = fs::File::open("src/test_html/search/quantum_theory.html").unwrap();
+            SearchDocument::from_read(file).unwrap()
         };
+        assert_eq!(
+            doc.scrape_pagination_offsets(),
+            vec![10, 20, 30, 40, 50, 60, 70, 80, 90]
+        );
 
-        assert_eq!(papers.len(), 10);
+        let doc = SearchDocument::from(
+            r#"<div id="gs_n">
+                 <a href="/scholar?q=foo"><b>Previous</b></a>
+                 <a href="/scholar?start=20&q=foo">3</a>
+                 <a href="/scholar?q=foo">1</a>
+                 <b>2</b>
+                 <a href="/scholar?start=20&q=foo"><b>Next</b></a>
+               </div>"#,
+        );
+        assert_eq!(doc.scrape_pagination_offsets(), vec![0, 20]);
 
-        assert_eq!(papers[0], {
-            let mut paper = Paper::new(
-                "Quantum field theory and critical phenomena",
-                16499695044466828447,
-            );
-            paper.link = Some(String::from("http://cds.cern.ch/record/2280881"));
-            paper.year = Some(1996);
-            paper.citation_count = Some(4821);
-            paper
-        });
+        let doc = SearchDocument::from("<div></div>");
+        assert!(doc.scrape_pagination_offsets().is_empty());
+    }
 
-        assert_eq!(papers[1], {
-            let mut paper = Paper::new("Quantum theory of solids", 8552492368061991976);
-            paper.year = Some(1963);
-            paper.citation_count = Some(4190);
-            paper
-        });
+    #[test]
+    fn search_document_query_test() {
+        use std::fs;
 
-        assert_eq!(papers[2], {
-            let mut paper = Paper::new(
-                "Significance of electromagnetic potentials in the quantum theory",
-                5545735591029960915,
-            );
-            paper.link = Some(String::from(
-                "https://journals.aps.org/pr/abstract/10.1103/PhysRev.115.485",
-            ));
-            paper.year = Some(1959);
-            paper.citation_count = Some(6961);
-            paper
-        });
+        let doc = {
+            let file = fs::File::open("src/test_html/search/quantum_theory.html").unwrap();
+            SearchDocument::from_read(file).unwrap()
+        };
+        assert_eq!(doc.scrape_query(), Some(String::from("quantum theory")));
+        assert!(doc.matches_query("Quantum Theory"));
+        assert!(!doc.matches_query("quantum field theory"));
+
+        let doc = {
+            let file = fs::File::open("src/test_html/search/blocked.html").unwrap();
+            SearchDocument::from_read(file).unwrap()
+        };
+        assert_eq!(doc.scrape_query(), None);
+        assert!(!doc.matches_query(""));
     }
 
     #[test]
@@ -485,13 +4903,20 @@ mod tests {
         use std::fs;
 
         let doc = {
-            let file = fs::File::open("src/test_html/quantum_theory_citations.html").unwrap();
+            let file =
+                fs::File::open("src/test_html/citations/quantum_theory_citations.html").unwrap();
             CitationDocument::from_read(file).unwrap()
         };
 
         let target_paper = doc.scrape_target_paper().unwrap();
         let citer_papers = doc.scrape_papers().unwrap();
 
+        assert_eq!(doc.next_page_start(), Some(10));
+        assert_eq!(
+            doc.scrape_pagination_offsets(),
+            (1..10).map(|i| i * 10).collect::<Vec<_>>()
+        );
+
         assert_eq!(
             target_paper,
             Paper::new(
@@ -507,11 +4932,30 @@ mod tests {
                 "Quantal phase factors accompanying adiabatic changes",
                 15570691018430890829,
             );
+            paper.snippet = Some(String::from(
+                "Abstract A quantal system in an eigenstate, slowly transported round a circuit C by varying parameters $\\mathbf {R} $ in its Hamiltonian $\\hat {H}(\\mathrm {R}) $, will acquire a geometrical phase factor $\\exp {i\\gamma (\\mathrm {C})} $ in addition to the familiar",
+            ));
+            paper.info_id = Some(String::from("TUPw9Ps0FtgJ"));
+            paper.related_url = Some(String::from(
+                "https://scholar.google.co.jp/scholar?q=related:TUPw9Ps0FtgJ:scholar.google.com/&hl=en&as_sdt=2005&sciodt=0,5",
+            ));
             paper.link = Some(String::from(
                 "http://rspa.royalsocietypublishing.org/content/royprsa/392/1802/45.full.pdf",
             ));
             paper.year = Some(1984);
-            paper.citation_count = Some(7813);
+            paper.citation_count = Some(7813.into());
+            paper.result_position = Some(0);
+            paper.versions_cluster_id = Some(ClusterId(15570691018430890829));
+            paper.versions_count = Some(9);
+            paper.versions_url = Some(String::from(
+                "https://scholar.google.co.jp/scholar?cluster=15570691018430890829&hl=en&as_sdt=2005&sciodt=0,5",
+            ));
+            paper.authors = Some(String::from("MV Berry"));
+            paper.venue = Some(String::from("Proceedings of the Royal Society of …"));
+            paper.access = Access::FullText;
+            paper.pdf_link = Some(String::from(
+                "http://www.academia.edu/download/34752305/berr83.pdf",
+            ));
             paper
         });
 
@@ -520,33 +4964,90 @@ mod tests {
                 "Multiferroics: a magnetic twist for ferroelectricity",
                 9328505180409005573,
             );
+            paper.snippet = Some(String::from(
+                "Abstract Magnetism and ferroelectricity are essential to many forms of current technology, and the quest for multiferroic materials, where these two phenomena are intimately coupled, is of great technological and fundamental importance. Ferroelectricity and magnetism tend",
+            ));
+            paper.info_id = Some(String::from("BYarewmCdYEJ"));
+            paper.related_url = Some(String::from(
+                "https://scholar.google.co.jp/scholar?q=related:BYarewmCdYEJ:scholar.google.com/&hl=en&as_sdt=2005&sciodt=0,5",
+            ));
             paper.link = Some(String::from(
                 "https://www.nature.com/nmat/journal/v6/n1/abs/nmat1804.html",
             ));
             paper.year = Some(2007);
-            paper.citation_count = Some(3232);
+            paper.citation_count = Some(3232.into());
+            paper.result_position = Some(1);
+            paper.versions_cluster_id = Some(ClusterId(9328505180409005573));
+            paper.versions_count = Some(16);
+            paper.versions_url = Some(String::from(
+                "https://scholar.google.co.jp/scholar?cluster=9328505180409005573&hl=en&as_sdt=2005&sciodt=0,5",
+            ));
+            paper.authors = Some(String::from("SW Cheong, M Mostovoy"));
+            paper.venue = Some(String::from("Nature materials"));
+            paper.access = Access::FullText;
+            paper.pdf_link = Some(String::from(
+                "https://pure.rug.nl/ws/files/6702041/2007NatureMaterCheong.pdf",
+            ));
             paper
         });
 
         assert_eq!(citer_papers[2], {
             let mut paper = Paper::new("Quantum field theory", 14398189842493937255);
+            paper.snippet = Some(String::from(
+                "This book is a modern introduction to the ideas and techniques of quantum field theory. After a brief overview of particle physics and a survey of relativistic wave equations and Lagrangian methods, the author develops the quantum theory of scalar and spinor fields,",
+            ));
+            paper.info_id = Some(String::from("ZxZ9emCl0McJ"));
+            paper.related_url = Some(String::from(
+                "https://scholar.google.co.jp/scholar?q=related:ZxZ9emCl0McJ:scholar.google.com/&hl=en&as_sdt=2005&sciodt=0,5",
+            ));
+            paper.doc_type = Some(DocType::Book);
             paper.link = Some(String::from(
                 "https://books.google.co.jp/books?\
                  hl=en&lr=&id=nnuW_kVJ500C&oi=fnd&pg=PR17\
                  &ots=vrupeDXT-V&sig=MofOsrk4Hh9qXjkS_WuQ7jHr2sY",
             ));
             paper.year = Some(1996);
-            paper.citation_count = Some(2911);
+            paper.citation_count = Some(2911.into());
+            paper.result_position = Some(2);
+            paper.versions_cluster_id = Some(ClusterId(14398189842493937255));
+            paper.versions_count = Some(11);
+            paper.versions_url = Some(String::from(
+                "https://scholar.google.co.jp/scholar?cluster=14398189842493937255&hl=en&as_sdt=2005&sciodt=0,5",
+            ));
+            paper.authors = Some(String::from("LH Ryder"));
             paper
         });
     }
 
+    #[test]
+    fn citation_document_target_header_test() {
+        use std::fs;
+
+        let doc = {
+            let file =
+                fs::File::open("src/test_html/citations/berry_phase_citations.html").unwrap();
+            CitationDocument::from_read(file).unwrap()
+        };
+
+        let paper = doc.scrape_target_paper_with_citers().unwrap();
+        assert_eq!(
+            paper.title,
+            "Quantal phase factors accompanying adiabatic changes"
+        );
+        assert_eq!(paper.cluster_id, Some(ClusterId(15570691018430890829)));
+        assert_eq!(paper.authors, Some(String::from("MV Berry")));
+        assert_eq!(paper.venue, Some(String::from("Proc. R. Soc. Lond. A")));
+        assert_eq!(paper.year, Some(1984));
+        assert_eq!(paper.citers.map(|c| c.len()), Some(1));
+    }
+
     #[test]
     fn cluster_document_scrape_test() {
         use std::fs;
 
         let doc = {
-            let file = fs::File::open("src/test_html/quantum_theory_cluster.html").unwrap();
+            let file =
+                fs::File::open("src/test_html/cluster/quantum_theory_cluster.html").unwrap();
             ClusterDocument::from_read(file).unwrap()
         };
 
@@ -557,10 +5058,207 @@ mod tests {
                 "Quantum field theory and critical phenomena",
                 6453279145216378381,
             );
+            paper.snippet = Some(String::from(
+                "Abstract Over the last twenty years quantum field theory has become not only the framework for the discussion of all fundamental interactions except gravity, but also for the understanding of second-order phase transitions in statistical mechanics. This advanced text",
+            ));
+            paper.info_id = Some(String::from("DQYPDzGnjlkJ"));
+            paper.related_url = Some(String::from(
+                "https://scholar.google.com/scholar?q=related:DQYPDzGnjlkJ:scholar.google.com/&hl=en&as_sdt=0,5",
+            ));
+            paper.doc_type = Some(DocType::Book);
             paper.year = Some(1996);
             paper.link = Some(String::from("http://cds.cern.ch/record/2280881"));
-            paper.citation_count = Some(4932);
+            paper.citation_count = Some(4932.into());
+            paper.result_position = Some(0);
+            paper.authors = Some(String::from("J Zinn-Justin"));
             paper
         });
     }
+
+    #[test]
+    fn cluster_document_scrape_versions_test() {
+        use std::fs;
+
+        let mut doc = {
+            let file =
+                fs::File::open("src/test_html/cluster/quantum_theory_cluster.html").unwrap();
+            ClusterDocument::from_read(file).unwrap()
+        };
+        doc.set_options(ScrapeOptions {
+            dedup_within_page: true,
+            ..Default::default()
+        });
+
+        let versions = doc.scrape_versions().unwrap();
+        assert_eq!(versions[0], doc.scrape_target_paper().unwrap());
+
+        let links = versions
+            .iter()
+            .map(|p| p.link.as_ref().unwrap().as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            links,
+            vec![
+                "http://cds.cern.ch/record/2280881",
+                "http://inspirehep.net/record/588675/",
+                "http://cds.cern.ch/record/572813",
+                "https://hal.archives-ouvertes.fr/hal-00120423/",
+                "https://hal.archives-ouvertes.fr/hal-00120759/",
+                "http://inspirehep.net/record/421719/",
+            ]
+        );
+        assert!(versions[1..]
+            .iter()
+            .all(|p| p.doc_type == Some(DocType::Citation)));
+    }
+
+    /// Outcome of scraping a fixture.
+    #[derive(Debug, PartialEq)]
+    enum Outcome {
+        /// Number of scraped items: papers, cases, or saved searches.
+        /// A cluster page counts its target paper only.
+        Scraped(usize),
+        Blocked,
+        BadHtml,
+        NoResultsContainer,
+        ProfilePrivate,
+    }
+
+    /// Every fixture under `src/test_html/`.
+    /// The directory of a fixture names its page type, which decides the scraper to run.
+    const FIXTURES: &[(&str, Outcome)] = &[
+        ("src/test_html/search/access.html", Outcome::Scraped(4)),
+        ("src/test_html/search/blocked.html", Outcome::Blocked),
+        ("src/test_html/search/captcha.html", Outcome::Blocked),
+        ("src/test_html/search/concatenated_pages.html", Outcome::Scraped(5)),
+        ("src/test_html/search/duplicate_cluster.html", Outcome::Scraped(3)),
+        ("src/test_html/search/garbage.html", Outcome::BadHtml),
+        ("src/test_html/search/mobile.html", Outcome::Scraped(2)),
+        ("src/test_html/search/no_results.html", Outcome::Scraped(0)),
+        (
+            "src/test_html/search/no_results_container.html",
+            Outcome::NoResultsContainer,
+        ),
+        ("src/test_html/search/omitted_results.html", Outcome::Scraped(1)),
+        ("src/test_html/search/quantum_theory.html", Outcome::Scraped(10)),
+        ("src/test_html/search/quantum_theory_page2.html", Outcome::Scraped(2)),
+        ("src/test_html/search/review_article.html", Outcome::Scraped(2)),
+        ("src/test_html/search/signed_in.html", Outcome::Scraped(2)),
+        ("src/test_html/search/sorted_by_date.html", Outcome::Scraped(2)),
+        ("src/test_html/search/tracking_title.html", Outcome::Scraped(3)),
+        ("src/test_html/search/truncated_authors.html", Outcome::Scraped(3)),
+        ("src/test_html/citations/berry_phase_citations.html", Outcome::Scraped(1)),
+        ("src/test_html/citations/quantum_theory_citations.html", Outcome::Scraped(10)),
+        ("src/test_html/cluster/quantum_theory_cluster.html", Outcome::Scraped(1)),
+        ("src/test_html/case_law/case_law.html", Outcome::Scraped(2)),
+        ("src/test_html/saved_searches/saved_searches.html", Outcome::Scraped(2)),
+        ("src/test_html/settings/settings.html", Outcome::Scraped(1)),
+        ("src/test_html/profile/private.html", Outcome::ProfilePrivate),
+        ("src/test_html/profile/profile.html", Outcome::Scraped(2)),
+        ("src/test_html/cite/aharonov_bohm_cite.html", Outcome::Scraped(4)),
+        ("src/test_html/author_search/berry.html", Outcome::Scraped(2)),
+        ("src/test_html/related/berry_phase_related.html", Outcome::Scraped(2)),
+        ("src/test_html/metrics/top_venues.html", Outcome::Scraped(3)),
+        ("src/test_html/library/library.html", Outcome::Scraped(2)),
+        ("src/test_html/citation_detail/berry_phase.html", Outcome::Scraped(7)),
+    ];
+
+    fn scrape_fixture(path: &str) -> Outcome {
+        use std::fs;
+
+        macro_rules! scrape {
+            ($doc: ident, $count: expr) => {{
+                let file = fs::File::open(path).unwrap();
+                let doc = match $doc::from_read(file) {
+                    Ok(doc) => doc,
+                    Err(Error(ErrorKind::BadHtml(..), _)) => return Outcome::BadHtml,
+                    Err(e) => panic!("{}: {}", path, e),
+                };
+                if doc.is_blocked() {
+                    return Outcome::Blocked;
+                }
+                match $count(&doc) {
+                    Ok(count) => Outcome::Scraped(count),
+                    Err(Error(ErrorKind::BadHtml(..), _)) => Outcome::BadHtml,
+                    Err(Error(ErrorKind::Blocked(_), _)) => Outcome::Blocked,
+                    Err(Error(ErrorKind::NoResultsContainer, _)) => Outcome::NoResultsContainer,
+                    Err(Error(ErrorKind::ProfilePrivate, _)) => Outcome::ProfilePrivate,
+                    Err(e) => panic!("{}: {}", path, e),
+                }
+            }}
+        }
+
+        let page_type = path.split('/').nth(2).unwrap();
+        match page_type {
+            "search" => scrape!(SearchDocument, |d: &SearchDocument| {
+                d.scrape_papers().map(|p| p.len())
+            }),
+            "citations" => scrape!(CitationDocument, |d: &CitationDocument| {
+                d.scrape_papers().map(|p| p.len())
+            }),
+            "cluster" => scrape!(ClusterDocument, |d: &ClusterDocument| {
+                d.scrape_target_paper().map(|_| 1)
+            }),
+            "case_law" => scrape!(CaseLawDocument, |d: &CaseLawDocument| {
+                d.scrape_cases().map(|c| c.len())
+            }),
+            "saved_searches" => scrape!(SavedSearchesDocument, |d: &SavedSearchesDocument| {
+                d.scrape_saved_searches().map(|s| s.len())
+            }),
+            "settings" => scrape!(SettingsDocument, |d: &SettingsDocument| {
+                d.scrape_settings().map(|_| 1)
+            }),
+            "profile" => scrape!(ProfileDocument, |d: &ProfileDocument| {
+                d.scrape_articles().map(|a| a.len())
+            }),
+            "related" => scrape!(RelatedDocument, |d: &RelatedDocument| {
+                d.scrape_papers().map(|p| p.len())
+            }),
+            "author_search" => scrape!(AuthorSearchDocument, |d: &AuthorSearchDocument| {
+                d.scrape_authors().map(|a| a.len())
+            }),
+            "metrics" => scrape!(MetricsDocument, |d: &MetricsDocument| {
+                d.scrape_venue_metrics().map(|v| v.len())
+            }),
+            "library" => scrape!(LibraryDocument, |d: &LibraryDocument| {
+                d.scrape_articles().map(|a| a.len())
+            }),
+            "citation_detail" => scrape!(CitationDetailDocument, |d: &CitationDetailDocument| {
+                d.scrape_citations_per_year().map(|c| c.len())
+            }),
+            "cite" => scrape!(CitePopupDocument, |d: &CitePopupDocument| {
+                d.scrape_export_links().map(|l| l.len())
+            }),
+            _ => panic!("{}: unknown page type {}", path, page_type),
+        }
+    }
+
+    #[test]
+    fn fixtures_regression_test() {
+        for &(path, ref expected) in FIXTURES {
+            assert_eq!(&scrape_fixture(path), expected, "{}", path);
+        }
+    }
+
+    #[test]
+    fn fixtures_listed_test() {
+        use std::fs;
+
+        let mut paths = Vec::new();
+        for dir in fs::read_dir("src/test_html").unwrap() {
+            let dir = dir.unwrap().path();
+            assert!(dir.is_dir(), "{} should be in a page type directory", dir.display());
+            for file in fs::read_dir(dir).unwrap() {
+                paths.push(file.unwrap().path().to_str().unwrap().to_owned());
+            }
+        }
+
+        for path in paths {
+            assert!(
+                FIXTURES.iter().any(|&(p, _)| p == path),
+                "{} should be listed in FIXTURES",
+                path
+            );
+        }
+    }
 }
@@ -0,0 +1,79 @@
+//! `SelectorProfile` struct.
+
+#[cfg(all(feature = "serde-derive", feature = "serde_json"))]
+use errors::*;
+
+/// IDs and class names of elements scraped in pages listing papers.
+///
+/// The default profile matches the layouts Google Scholar currently serves.
+/// When Google Scholar renames some of them, set a patched profile to `ScrapeOptions::selectors`
+/// to keep scraping without waiting for a release of this crate.
+/// Profiles can be loaded from JSON with `from_json()`, or from any format with `serde`,
+/// where missing fields are taken from the default profile.
+///
+/// # Example
+///
+/// ```
+/// use scholar::selector::SelectorProfile;
+///
+/// let mut selectors = SelectorProfile::default();
+/// selectors.title_class = String::from("gs_rt2");
+/// assert_eq!(selectors.paper_class_desktop, "gs_ri");
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-derive", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-derive", serde(default))]
+pub struct SelectorProfile {
+    /// ID of the container of results in the desktop layout, e.g. "gs_res_ccl_mid".
+    pub container_id_desktop: String,
+    /// Class of each paper in the container in the desktop layout, e.g. "gs_ri".
+    pub paper_class_desktop: String,
+    /// ID of the container of results in the mobile layout, e.g. "gs_ccl".
+    pub container_id_mobile: String,
+    /// Class of each paper in the container in the mobile layout, e.g. "gs_r".
+    pub paper_class_mobile: String,
+    /// Class of the title header of a paper, e.g. "gs_rt".
+    pub title_class: String,
+    /// Class of the byline listing authors, venue, and year, e.g. "gs_a".
+    pub byline_class: String,
+    /// Class of the snippet below the byline, e.g. "gs_rs".
+    pub snippet_class: String,
+    /// Class of the footer holding "Cited by" and other links, e.g. "gs_fl".
+    pub footer_class: String,
+    /// Class of the full text links beside a paper, e.g. "gs_ggs".
+    pub full_text_class: String,
+}
+
+impl Default for SelectorProfile {
+    fn default() -> Self {
+        Self {
+            container_id_desktop: String::from("gs_res_ccl_mid"),
+            paper_class_desktop: String::from("gs_ri"),
+            container_id_mobile: String::from("gs_ccl"),
+            paper_class_mobile: String::from("gs_r"),
+            title_class: String::from("gs_rt"),
+            byline_class: String::from("gs_a"),
+            snippet_class: String::from("gs_rs"),
+            footer_class: String::from("gs_fl"),
+            full_text_class: String::from("gs_ggs"),
+        }
+    }
+}
+
+impl SelectorProfile {
+    /// Parse a profile in JSON, taking missing fields from the default profile.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use scholar::selector::SelectorProfile;
+    ///
+    /// let selectors = SelectorProfile::from_json(r#"{ "title_class": "gs_rt2" }"#).unwrap();
+    /// assert_eq!(selectors.title_class, "gs_rt2");
+    /// assert_eq!(selectors.byline_class, "gs_a");
+    /// ```
+    #[cfg(all(feature = "serde-derive", feature = "serde_json"))]
+    pub fn from_json(json: &str) -> Result<Self> {
+        Ok(::serde_json::from_str(json)?)
+    }
+}
@@ -0,0 +1,387 @@
+//! Look papers up in Semantic Scholar via its Graph API, e.g. as a fallback source
+//! when Google Scholar blocks requests.
+//!
+//! Requires the `semantic_scholar` feature.
+
+use std::io::Read;
+
+use reqwest::{self, StatusCode};
+use reqwest::header::{Headers, UserAgent};
+use serde_json::{self, Value};
+use url::percent_encoding::{utf8_percent_encode, PATH_SEGMENT_ENCODE_SET};
+
+use matcher::{self, TITLE_MATCH_THRESHOLD};
+use paper::{Access, CitationCount, Paper};
+use request::form_urlencode;
+use source::PaperSource;
+use errors::*;
+
+const SEMANTIC_SCHOLAR_URL_BASE: &str = "https://api.semanticscholar.org/graph/v1";
+
+/// Fields of papers requested, each converted into a field of `Paper`.
+const PAPER_FIELDS: &str =
+    "title,authors,venue,year,abstract,url,citationCount,referenceCount,externalIds,openAccessPdf";
+
+/// Most papers the Graph API returns per request.
+const MAX_LIMIT: usize = 100;
+
+/// Number of papers requested when looking a paper up by title.
+const TITLE_SEARCH_LIMIT: usize = 5;
+
+/// Convert a paper object of the Graph API into a `Paper`.
+///
+/// The paper has no cluster ID, which only Google Scholar assigns.
+/// Returns `None` if the object has no title.
+///
+/// # Example
+///
+/// ```
+/// #[macro_use]
+/// extern crate serde_json;
+/// extern crate scholar;
+///
+/// use scholar::semantic_scholar::paper_from_json;
+///
+/// # fn main() {
+/// let paper = paper_from_json(&json!({
+///     "title": "Quantal phase factors accompanying adiabatic changes",
+///     "authors": [{ "name": "M. Berry" }],
+///     "year": 1984,
+///     "citationCount": 9876,
+///     "externalIds": { "DOI": "10.1098/rspa.1984.0023" },
+/// })).unwrap();
+///
+/// assert_eq!(paper.authors, Some(String::from("M. Berry")));
+/// assert_eq!(paper.cluster_id, None);
+/// assert_eq!(paper.doi, Some(String::from("10.1098/rspa.1984.0023")));
+/// # }
+/// ```
+pub fn paper_from_json(json: &Value) -> Option<Paper> {
+    let mut paper = Paper::new(json["title"].as_str()?, 0);
+    paper.set_cluster_id(None);
+
+    let authors = json["authors"]
+        .as_array()
+        .map(|authors| {
+            authors
+                .iter()
+                .filter_map(|a| a["name"].as_str())
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    if !authors.is_empty() {
+        paper.authors = Some(authors.join(", "));
+    }
+    paper.venue = non_empty_str(&json["venue"]);
+    paper.year = json["year"].as_u64().map(|year| year as u32);
+    paper.snippet = non_empty_str(&json["abstract"]);
+    paper.link = non_empty_str(&json["url"]);
+    paper.citation_count = json["citationCount"]
+        .as_u64()
+        .map(|count| CitationCount::from(count as u32));
+    paper.references_count = json["referenceCount"].as_u64().map(|count| count as u32);
+    paper.doi = non_empty_str(&json["externalIds"]["DOI"]);
+    paper.arxiv_id = non_empty_str(&json["externalIds"]["ArXiv"]);
+    paper.pdf_link = non_empty_str(&json["openAccessPdf"]["url"]);
+    if paper.pdf_link.is_some() {
+        paper.access = Access::FullText;
+    }
+
+    Some(paper)
+}
+
+/// URL searching papers matching `query`, up to `limit`.
+///
+/// # Example
+///
+/// ```
+/// use scholar::semantic_scholar::search_url;
+///
+/// assert!(search_url("berry phase", 10).starts_with(
+///     "https://api.semanticscholar.org/graph/v1/paper/search?query=berry+phase&limit=10&fields="
+/// ));
+/// ```
+pub fn search_url(query: &str, limit: usize) -> String {
+    format!(
+        "{}/paper/search?query={}&limit={}&fields={}",
+        SEMANTIC_SCHOLAR_URL_BASE,
+        form_urlencode(query),
+        limit,
+        PAPER_FIELDS
+    )
+}
+
+/// URL listing papers citing a paper of `paper_id`,
+/// which is a Semantic Scholar ID or an external ID like "DOI:10.1098/rspa.1984.0023".
+///
+/// # Example
+///
+/// ```
+/// use scholar::semantic_scholar::citations_url;
+///
+/// assert!(citations_url("DOI:10.1098/rspa.1984.0023").starts_with(
+///     "https://api.semanticscholar.org/graph/v1/paper/DOI:10.1098%2Frspa.1984.0023/citations?"
+/// ));
+/// ```
+pub fn citations_url(paper_id: &str) -> String {
+    format!(
+        "{}/paper/{}/citations?limit={}&fields={}",
+        SEMANTIC_SCHOLAR_URL_BASE,
+        utf8_percent_encode(paper_id, PATH_SEGMENT_ENCODE_SET),
+        MAX_LIMIT,
+        PAPER_FIELDS
+    )
+}
+
+/// External ID by which Semantic Scholar finds `paper`, if `paper` has a DOI or arXiv ID.
+///
+/// # Example
+///
+/// ```
+/// use scholar::paper::Paper;
+/// use scholar::semantic_scholar::external_id;
+///
+/// let mut paper = Paper::new("foo", 42);
+/// assert_eq!(external_id(&paper), None);
+///
+/// paper.arxiv_id = Some(String::from("2103.12345"));
+/// assert_eq!(external_id(&paper), Some(String::from("ARXIV:2103.12345")));
+/// ```
+pub fn external_id(paper: &Paper) -> Option<String> {
+    paper
+        .doi
+        .as_ref()
+        .map(|doi| format!("DOI:{}", doi))
+        .or_else(|| paper.arxiv_id.as_ref().map(|id| format!("ARXIV:{}", id)))
+}
+
+/// Client of the Semantic Scholar Graph API.
+///
+/// # Example
+///
+/// ```no_run
+/// use scholar::semantic_scholar::SemanticScholarClient;
+/// use scholar::source::PaperSource;
+///
+/// let client = SemanticScholarClient::new();
+/// for paper in client.search_papers("berry phase", 10).unwrap() {
+///     println!("{}", paper.title);
+/// }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct SemanticScholarClient {
+    api_key: Option<String>,
+}
+
+impl SemanticScholarClient {
+    /// Create a client without API key, which shares the public rate limit.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set an API key sent in `x-api-key`, with which Semantic Scholar allows more requests.
+    pub fn set_api_key(&mut self, api_key: &str) {
+        self.api_key = Some(api_key.to_owned());
+    }
+
+    pub fn get_api_key(&self) -> Option<&str> {
+        self.api_key.as_ref().map(|key| &key[..])
+    }
+
+    /// Paper best matching `title` among the search results,
+    /// or `None` if none scores `matcher::TITLE_MATCH_THRESHOLD` or more.
+    pub fn paper_by_title(&self, title: &str) -> Result<Option<Paper>> {
+        let papers = self.search_papers(title, TITLE_SEARCH_LIMIT)?;
+        Ok(best_paper(title, &papers).cloned())
+    }
+
+    /// ID by which Semantic Scholar finds `paper`: its DOI or arXiv ID,
+    /// or else the ID of the paper best matching its title.
+    fn paper_id(&self, paper: &Paper) -> Result<Option<String>> {
+        if let Some(id) = external_id(paper) {
+            return Ok(Some(id));
+        }
+
+        let res = match self.fetch_json(&search_url(&paper.title, TITLE_SEARCH_LIMIT))? {
+            Some(res) => res,
+            None => return Ok(None),
+        };
+        let (ids, papers): (Vec<_>, Vec<_>) = res["data"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|json| Some((json["paperId"].as_str()?, paper_from_json(json)?)))
+            .unzip();
+        let best = best_paper(&paper.title, &papers)
+            .and_then(|best| papers.iter().position(|p| p == best));
+        Ok(best.map(|i| ids[i].to_owned()))
+    }
+
+    /// Response of `url` in JSON, or `None` if not found.
+    fn fetch_json(&self, url: &str) -> Result<Option<Value>> {
+        let http_error = || ErrorKind::Http(url.to_owned());
+
+        let mut headers = Headers::new();
+        headers.set(UserAgent::new(concat!("scholar/", env!("CARGO_PKG_VERSION"))));
+        if let Some(ref api_key) = self.api_key {
+            headers.set_raw("x-api-key", api_key.clone());
+        }
+
+        debug!("Fetching {}", url);
+        let res = reqwest::Client::new()
+            .get(url)
+            .headers(headers)
+            .send()
+            .chain_err(&http_error)?;
+        if res.status() == StatusCode::NotFound {
+            return Ok(None);
+        }
+
+        let mut body = Vec::new();
+        res.error_for_status()
+            .chain_err(&http_error)?
+            .read_to_end(&mut body)?;
+        Ok(Some(serde_json::from_slice(&body)?))
+    }
+}
+
+impl PaperSource for SemanticScholarClient {
+    fn name(&self) -> &str {
+        "Semantic Scholar"
+    }
+
+    fn search_papers(&self, query: &str, count: usize) -> Result<Vec<Paper>> {
+        let limit = ::std::cmp::min(count, MAX_LIMIT);
+        let res = match self.fetch_json(&search_url(query, limit))? {
+            Some(res) => res,
+            None => return Ok(Vec::new()),
+        };
+        Ok(res["data"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(paper_from_json)
+            .collect())
+    }
+
+    /// Papers citing `paper`, found by its DOI, arXiv ID, or title in this order.
+    fn citing_papers(&self, paper: &Paper) -> Result<Vec<Paper>> {
+        let id = match self.paper_id(paper)? {
+            Some(id) => id,
+            None => return Err(ErrorKind::ResultNotFount.into()),
+        };
+        let res = match self.fetch_json(&citations_url(&id))? {
+            Some(res) => res,
+            None => return Err(ErrorKind::ResultNotFount.into()),
+        };
+        Ok(citers_from_json(&res))
+    }
+}
+
+/// Citing papers in a response of `/paper/{id}/citations`.
+fn citers_from_json(res: &Value) -> Vec<Paper> {
+    res["data"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|citation| paper_from_json(&citation["citingPaper"]))
+        .collect()
+}
+
+/// The paper among `papers` whose title best matches `title`, if not below the threshold.
+fn best_paper<'a>(title: &str, papers: &'a [Paper]) -> Option<&'a Paper> {
+    let best = matcher::best_match(title, papers)
+        .filter(|m| m.confidence >= TITLE_MATCH_THRESHOLD)?;
+    papers.iter().find(|&p| *p == best.paper)
+}
+
+/// String of `value`, or `None` if it is null or empty.
+fn non_empty_str(value: &Value) -> Option<String> {
+    value
+        .as_str()
+        .filter(|s| !s.is_empty())
+        .map(ToOwned::to_owned)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn berry_json() -> Value {
+        json!({
+            "paperId": "0e5d6d5ba5d4e5b4f12c9e2b7d8d8ddd9b3a7c1f",
+            "title": "Quantal phase factors accompanying adiabatic changes",
+            "authors": [{ "authorId": "1", "name": "M. Berry" }],
+            "venue": "Proceedings of the Royal Society of London. A.",
+            "year": 1984,
+            "abstract": null,
+            "url": "https://www.semanticscholar.org/paper/0e5d6d5ba5d4e5b4f12c9e2b7d8d8ddd9b3a7c1f",
+            "citationCount": 9876,
+            "referenceCount": 12,
+            "externalIds": { "DOI": "10.1098/rspa.1984.0023", "MAG": "123" },
+            "openAccessPdf": null,
+        })
+    }
+
+    #[test]
+    fn paper_from_json_test() {
+        let paper = paper_from_json(&berry_json()).unwrap();
+
+        let mut expected = Paper::new("Quantal phase factors accompanying adiabatic changes", 0);
+        expected.set_cluster_id(None);
+        expected.authors = Some(String::from("M. Berry"));
+        expected.venue = Some(String::from("Proceedings of the Royal Society of London. A."));
+        expected.year = Some(1984);
+        expected.link = Some(String::from(
+            "https://www.semanticscholar.org/paper/0e5d6d5ba5d4e5b4f12c9e2b7d8d8ddd9b3a7c1f",
+        ));
+        expected.citation_count = Some(9876.into());
+        expected.references_count = Some(12);
+        expected.doi = Some(String::from("10.1098/rspa.1984.0023"));
+        assert_eq!(paper, expected);
+
+        let paper = paper_from_json(&json!({
+            "title": "Berry phase effects on electronic properties",
+            "authors": [],
+            "venue": "",
+            "externalIds": { "ArXiv": "0907.2021" },
+            "openAccessPdf": { "url": "https://arxiv.org/pdf/0907.2021" },
+        })).unwrap();
+        assert_eq!(paper.authors, None);
+        assert_eq!(paper.venue, None);
+        assert_eq!(paper.arxiv_id, Some(String::from("0907.2021")));
+        assert_eq!(paper.pdf_link, Some(String::from("https://arxiv.org/pdf/0907.2021")));
+        assert_eq!(paper.access, Access::FullText);
+
+        assert_eq!(paper_from_json(&json!({ "paperId": "abc" })), None);
+    }
+
+    #[test]
+    fn citers_from_json_test() {
+        let res = json!({
+            "offset": 0,
+            "data": [
+                { "citingPaper": berry_json() },
+                { "citingPaper": { "paperId": "abc", "title": null } },
+            ],
+        });
+        let citers = citers_from_json(&res);
+        assert_eq!(citers.len(), 1);
+        assert_eq!(citers[0].year, Some(1984));
+
+        assert!(citers_from_json(&json!({})).is_empty());
+    }
+
+    #[test]
+    fn best_paper_test() {
+        let papers = vec![
+            paper_from_json(&json!({ "title": "Berry phase effects on electronic properties" }))
+                .unwrap(),
+            paper_from_json(&berry_json()).unwrap(),
+        ];
+
+        let paper = best_paper("Quantal Phase Factors Accompanying Adiabatic Changes.", &papers);
+        assert_eq!(paper.unwrap().year, Some(1984));
+        assert_eq!(best_paper("Geometric phases in physics", &papers), None);
+    }
+}
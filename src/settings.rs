@@ -0,0 +1,36 @@
+//! `Settings` struct.
+
+/// Preferences shown in the settings page of Google Scholar.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-derive", derive(Serialize))]
+pub struct Settings {
+    /// Number of results per page.
+    pub results_per_page: Option<u32>,
+    /// Bibliography manager to show citation import links for.
+    /// `None` if import links are disabled.
+    pub bibliography_manager: Option<BibliographyManager>,
+    /// Libraries whose links are shown with results.
+    pub library_links: Vec<LibraryLink>,
+}
+
+/// Bibliography manager to import citations into.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-derive", derive(Serialize))]
+pub enum BibliographyManager {
+    BibTeX,
+    EndNote,
+    RefMan,
+    RefWorks,
+    /// Not known to this crate. Holds the value of the `scisf` option.
+    Other(String),
+}
+
+/// Library link enabled by the user, e.g. "Find it@My Library".
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-derive", derive(Serialize))]
+pub struct LibraryLink {
+    /// ID of the library, given as the `inst` parameter.
+    pub id: String,
+    /// Name of the library shown in the settings page.
+    pub name: String,
+}
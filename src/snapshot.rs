@@ -0,0 +1,186 @@
+//! Record fetched pages as snapshots, and replay them later without network.
+
+use std::fs::{self, File};
+use std::io::{self, BufRead, Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use cache::fnv1a;
+use errors::*;
+
+/// Whether `Client::set_snapshots()` records pages it fetches, or replays recorded ones.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SnapshotMode {
+    /// Fetch pages as usual, and record every page fetched, including ones blocking requests.
+    Record,
+    /// Serve recorded pages without sending requests,
+    /// failing with `ErrorKind::SnapshotNotFound` for pages not recorded.
+    Replay,
+}
+
+/// Metadata recorded with a page.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SnapshotMetadata {
+    /// Absolute URL of the page.
+    pub url: String,
+    /// Seconds since the Unix epoch when the page was recorded.
+    pub recorded_at: u64,
+    /// Whether Google Scholar blocked the request, showing CAPTCHA etc.
+    pub blocked: bool,
+}
+
+/// Pages recorded in a directory, each in a `.html` file of the raw page
+/// and a `.meta` file of its `SnapshotMetadata`, named after a hash of the URL.
+///
+/// Unlike `cache::PageCache`, snapshots never expire,
+/// so that tests replaying them give the same results whenever they run.
+/// Recording a page again replaces the old snapshot.
+/// `Client::set_snapshots()` makes a client record pages here, or replay them.
+///
+/// # Example
+///
+/// ```no_run
+/// use scholar::snapshot::SnapshotStore;
+///
+/// let store = SnapshotStore::open("snapshots").unwrap();
+///
+/// let url = "https://scholar.google.com/scholar?q=quantum+theory";
+/// store.record(url, b"<html>...</html>", false).unwrap();
+/// assert_eq!(store.replay(url).unwrap(), Some(b"<html>...</html>".to_vec()));
+/// ```
+#[derive(Clone, Debug)]
+pub struct SnapshotStore {
+    dir: PathBuf,
+}
+
+impl SnapshotStore {
+    /// Open a store in `dir`, creating the directory if it does not exist.
+    pub fn open<P: AsRef<Path>>(dir: P) -> Result<Self> {
+        fs::create_dir_all(dir.as_ref())?;
+        Ok(Self {
+            dir: dir.as_ref().to_owned(),
+        })
+    }
+
+    pub fn get_dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Record `page` of `url` with its metadata, replacing the old snapshot if any.
+    pub fn record(&self, url: &str, page: &[u8], blocked: bool) -> Result<()> {
+        let recorded_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+
+        File::create(self.path(url, "html"))?.write_all(page)?;
+        // The metadata is written last, so that a snapshot without it is never replayed
+        let mut meta = File::create(self.path(url, "meta"))?;
+        writeln!(meta, "url: {}", url)?;
+        writeln!(meta, "recorded_at: {}", recorded_at)?;
+        writeln!(meta, "blocked: {}", blocked)?;
+        Ok(())
+    }
+
+    /// Recorded page of `url`, or `None` if not recorded.
+    pub fn replay(&self, url: &str) -> Result<Option<Vec<u8>>> {
+        if self.metadata(url)?.is_none() {
+            return Ok(None);
+        }
+
+        let mut page = Vec::new();
+        File::open(self.path(url, "html"))?.read_to_end(&mut page)?;
+        Ok(Some(page))
+    }
+
+    /// Metadata of the snapshot of `url`, or `None` if not recorded.
+    pub fn metadata(&self, url: &str) -> Result<Option<SnapshotMetadata>> {
+        match read_metadata(&self.path(url, "meta"))? {
+            // Another URL of the same hash
+            Some(ref meta) if meta.url != url => Ok(None),
+            meta => Ok(meta),
+        }
+    }
+
+    /// Metadata of every snapshot, in ascending order of URLs.
+    pub fn list(&self) -> Result<Vec<SnapshotMetadata>> {
+        let mut snapshots = Vec::new();
+        for entry in fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            if path.extension().map_or(false, |ext| ext == "meta") {
+                snapshots.extend(read_metadata(&path)?);
+            }
+        }
+        snapshots.sort_by(|a, b| a.url.cmp(&b.url));
+        Ok(snapshots)
+    }
+
+    fn path(&self, url: &str, extension: &str) -> PathBuf {
+        self.dir
+            .join(format!("{:016x}.{}", fnv1a(url.as_bytes()), extension))
+    }
+}
+
+/// Parse a `.meta` file of "key: value" lines, or `None` if it does not exist.
+fn read_metadata(path: &Path) -> Result<Option<SnapshotMetadata>> {
+    let file = match File::open(path) {
+        Ok(file) => file,
+        Err(ref e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+
+    let mut meta = SnapshotMetadata {
+        url: String::new(),
+        recorded_at: 0,
+        blocked: false,
+    };
+    for line in io::BufReader::new(file).lines() {
+        let line = line?;
+        let mut key_value = line.splitn(2, ": ");
+        match (key_value.next(), key_value.next()) {
+            (Some("url"), Some(url)) => meta.url = url.to_owned(),
+            (Some("recorded_at"), Some(secs)) => meta.recorded_at = secs.parse()?,
+            (Some("blocked"), Some(blocked)) => meta.blocked = blocked == "true",
+            _ => {}
+        }
+    }
+    Ok(Some(meta))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::env;
+    use std::process;
+
+    #[test]
+    fn snapshot_store_test() {
+        let dir = env::temp_dir().join(format!("scholar-snapshot-test-{}", process::id()));
+        let store = SnapshotStore::open(&dir).unwrap();
+
+        let foo = "https://scholar.google.com/scholar?q=foo";
+        let bar = "https://scholar.google.com/scholar?q=bar";
+        assert_eq!(store.replay(foo).unwrap(), None);
+        store.record(foo, b"<html>foo</html>", false).unwrap();
+        store.record(bar, b"<html>old</html>", false).unwrap();
+        store.record(bar, b"<html>captcha</html>", true).unwrap();
+
+        assert_eq!(store.replay(foo).unwrap(), Some(b"<html>foo</html>".to_vec()));
+        assert_eq!(store.replay(bar).unwrap(), Some(b"<html>captcha</html>".to_vec()));
+        let meta = store.metadata(bar).unwrap().unwrap();
+        assert_eq!(meta.url, bar);
+        assert!(meta.blocked);
+        assert!(meta.recorded_at > 0);
+
+        let urls = store
+            .list()
+            .unwrap()
+            .into_iter()
+            .map(|meta| meta.url)
+            .collect::<Vec<_>>();
+        assert_eq!(urls, vec![bar, foo]);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,239 @@
+//! `PaperSource` trait of services papers are looked up in, and falling back between them.
+
+#[cfg(feature = "client")]
+use client::is_rate_limited;
+use paper::Paper;
+use errors::*;
+
+/// A service to search papers and their citations in, e.g. Google Scholar via `Client`,
+/// or Semantic Scholar via `semantic_scholar::SemanticScholarClient`.
+///
+/// Every source scrapes or converts its results into `Paper`s,
+/// though fields only some sources know are left `None` by the others,
+/// e.g. `cluster_id` of papers found in Semantic Scholar.
+pub trait PaperSource {
+    /// Name of the source shown in logs, e.g. "Google Scholar".
+    fn name(&self) -> &str;
+
+    /// Search up to `count` papers matching free-text `query`, most relevant first.
+    fn search_papers(&self, query: &str, count: usize) -> Result<Vec<Paper>>;
+
+    /// Papers citing `paper`, as many as the source lists at once.
+    ///
+    /// Each source identifies `paper` by the IDs it knows, e.g. `cluster_id` or `doi`.
+    /// Returns `ErrorKind::ResultNotFount` if the source cannot identify `paper`.
+    fn citing_papers(&self, paper: &Paper) -> Result<Vec<Paper>>;
+}
+
+/// Source asking `primary` first, and `fallback` if `primary` is blocked,
+/// e.g. Semantic Scholar when Google Scholar shows CAPTCHA or answers 429 Too Many Requests.
+///
+/// `primary` is taken as blocked if it fails with `ErrorKind::Blocked`,
+/// or with an error `client::is_rate_limited()` reports.
+/// Its other errors are returned as is.
+///
+/// # Example
+///
+/// ```
+/// use scholar::errors::*;
+/// use scholar::paper::Paper;
+/// use scholar::scrape::BlockKind;
+/// use scholar::source::{FallbackSource, PaperSource};
+///
+/// struct Blocked;
+///
+/// impl PaperSource for Blocked {
+///     fn name(&self) -> &str {
+///         "blocked"
+///     }
+///
+///     fn search_papers(&self, _: &str, _: usize) -> Result<Vec<Paper>> {
+///         Err(ErrorKind::Blocked(BlockKind::Captcha).into())
+///     }
+///
+///     fn citing_papers(&self, _: &Paper) -> Result<Vec<Paper>> {
+///         Err(ErrorKind::Blocked(BlockKind::Captcha).into())
+///     }
+/// }
+///
+/// struct Echo;
+///
+/// impl PaperSource for Echo {
+///     fn name(&self) -> &str {
+///         "echo"
+///     }
+///
+///     fn search_papers(&self, query: &str, _: usize) -> Result<Vec<Paper>> {
+///         Ok(vec![Paper::new(query, 1)])
+///     }
+///
+///     fn citing_papers(&self, _: &Paper) -> Result<Vec<Paper>> {
+///         Ok(Vec::new())
+///     }
+/// }
+///
+/// let source = FallbackSource::new(Blocked, Echo);
+/// assert_eq!(source.search_papers("foo", 10).unwrap()[0].title, "foo");
+/// ```
+#[derive(Clone, Debug)]
+pub struct FallbackSource<P, F> {
+    primary: P,
+    fallback: F,
+}
+
+impl<P: PaperSource, F: PaperSource> FallbackSource<P, F> {
+    pub fn new(primary: P, fallback: F) -> Self {
+        Self { primary, fallback }
+    }
+
+    pub fn get_primary(&self) -> &P {
+        &self.primary
+    }
+
+    pub fn get_fallback(&self) -> &F {
+        &self.fallback
+    }
+
+    fn run<T, G>(&self, mut f: G) -> Result<T>
+    where
+        G: FnMut(&dyn PaperSource) -> Result<T>,
+    {
+        match f(&self.primary) {
+            Err(ref e) if is_blocked(e) => {
+                warn!(
+                    "{} is blocked, falling back to {}",
+                    self.primary.name(),
+                    self.fallback.name()
+                );
+                f(&self.fallback)
+            }
+            result => result,
+        }
+    }
+}
+
+fn is_blocked(err: &Error) -> bool {
+    match *err.kind() {
+        ErrorKind::Blocked(_) => true,
+        _ => is_rate_limited(err),
+    }
+}
+
+/// Without `client`, no response with a status code is ever received.
+#[cfg(not(feature = "client"))]
+fn is_rate_limited(_: &Error) -> bool {
+    false
+}
+
+impl<P: PaperSource, F: PaperSource> PaperSource for FallbackSource<P, F> {
+    fn name(&self) -> &str {
+        self.primary.name()
+    }
+
+    fn search_papers(&self, query: &str, count: usize) -> Result<Vec<Paper>> {
+        self.run(|source| source.search_papers(query, count))
+    }
+
+    fn citing_papers(&self, paper: &Paper) -> Result<Vec<Paper>> {
+        self.run(|source| source.citing_papers(paper))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::cell::Cell;
+
+    use scrape::BlockKind;
+
+    /// Source failing with `error` if any, counting calls.
+    struct MockSource {
+        error: Option<fn() -> Error>,
+        calls: Cell<u32>,
+    }
+
+    impl MockSource {
+        fn new(error: Option<fn() -> Error>) -> Self {
+            Self {
+                error,
+                calls: Cell::new(0),
+            }
+        }
+    }
+
+    impl PaperSource for MockSource {
+        fn name(&self) -> &str {
+            "mock"
+        }
+
+        fn search_papers(&self, query: &str, _count: usize) -> Result<Vec<Paper>> {
+            self.calls.set(self.calls.get() + 1);
+            match self.error {
+                Some(error) => Err(error()),
+                None => Ok(vec![Paper::new(query, 1)]),
+            }
+        }
+
+        fn citing_papers(&self, paper: &Paper) -> Result<Vec<Paper>> {
+            self.search_papers(&paper.title, 10)
+        }
+    }
+
+    #[test]
+    fn fallback_source_test() {
+        let source = FallbackSource::new(
+            MockSource::new(Some(|| ErrorKind::Blocked(BlockKind::Captcha).into())),
+            MockSource::new(None),
+        );
+        assert_eq!(source.search_papers("foo", 10).unwrap(), vec![Paper::new("foo", 1)]);
+        assert_eq!(
+            source.citing_papers(&Paper::new("bar", 2)).unwrap(),
+            vec![Paper::new("bar", 1)]
+        );
+        assert_eq!(source.get_primary().calls.get(), 2);
+        assert_eq!(source.get_fallback().calls.get(), 2);
+
+        // Errors other than blocking are not worth asking another source
+        let source = FallbackSource::new(
+            MockSource::new(Some(|| ErrorKind::Http(String::from("foo")).into())),
+            MockSource::new(None),
+        );
+        match source.search_papers("foo", 10) {
+            Err(Error(ErrorKind::Http(_), _)) => {}
+            result => panic!("unexpected result: {:?}", result),
+        }
+        assert_eq!(source.get_fallback().calls.get(), 0);
+
+        let source = FallbackSource::new(MockSource::new(None), MockSource::new(None));
+        assert!(source.search_papers("foo", 10).is_ok());
+        assert_eq!(source.get_fallback().calls.get(), 0);
+    }
+
+    #[cfg(feature = "client")]
+    #[test]
+    fn fallback_source_rate_limited_test() {
+        use client::HttpStatus;
+        use reqwest::StatusCode;
+
+        let source = FallbackSource::new(
+            MockSource::new(Some(|| {
+                let status = HttpStatus(StatusCode::TooManyRequests);
+                Error::with_chain(status, ErrorKind::Http(String::from("foo")))
+            })),
+            MockSource::new(None),
+        );
+        assert_eq!(source.search_papers("foo", 10).unwrap(), vec![Paper::new("foo", 1)]);
+        assert_eq!(source.get_fallback().calls.get(), 1);
+
+        let source = FallbackSource::new(
+            MockSource::new(Some(|| {
+                let status = HttpStatus(StatusCode::NotFound);
+                Error::with_chain(status, ErrorKind::Http(String::from("foo")))
+            })),
+            MockSource::new(None),
+        );
+        assert!(source.search_papers("foo", 10).is_err());
+        assert_eq!(source.get_fallback().calls.get(), 0);
+    }
+}
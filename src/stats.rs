@@ -0,0 +1,139 @@
+//! Aggregate statistics of scraped papers.
+
+use paper::{Access, Paper};
+
+/// Summary of papers listed in a page.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PageStats {
+    /// Number of papers.
+    pub count: usize,
+    /// Sum of citation counts. Papers without citation count are excluded,
+    /// and approximate counts are summed as is.
+    pub total_citations: u64,
+    /// Median of citation counts. Papers without citation count are excluded.
+    /// `None` if no paper has citation count.
+    pub median_citations: Option<f64>,
+    /// Oldest published year. Papers without published year are excluded.
+    pub oldest_year: Option<u32>,
+    /// Newest published year. Papers without published year are excluded.
+    pub newest_year: Option<u32>,
+    /// Number of papers with a link to full text, or a link to PDF.
+    pub with_pdf: usize,
+}
+
+/// Compute aggregate statistics of `papers`, typically scraped from one page.
+///
+/// # Example
+///
+/// ```
+/// use scholar::paper::Paper;
+/// use scholar::stats::page_stats;
+///
+/// let mut foo = Paper::new("foo", 0);
+/// foo.citation_count = Some(10.into());
+/// foo.year = Some(2000);
+/// let bar = Paper::new("bar", 1);
+///
+/// let stats = page_stats(&[foo, bar]);
+/// assert_eq!(stats.count, 2);
+/// assert_eq!(stats.total_citations, 10);
+/// assert_eq!(stats.median_citations, Some(10.0));
+/// assert_eq!(stats.oldest_year, Some(2000));
+/// ```
+pub fn page_stats(papers: &[Paper]) -> PageStats {
+    let mut citations = papers
+        .iter()
+        .filter_map(|p| p.citation_count.map(|c| c.value))
+        .collect::<Vec<_>>();
+    citations.sort();
+
+    let years = papers.iter().filter_map(|p| p.year);
+
+    PageStats {
+        count: papers.len(),
+        total_citations: citations.iter().map(|&c| u64::from(c)).sum(),
+        median_citations: median(&citations),
+        oldest_year: years.clone().min(),
+        newest_year: years.max(),
+        with_pdf: papers.iter().filter(|p| has_pdf(p)).count(),
+    }
+}
+
+fn median(sorted: &[u32]) -> Option<f64> {
+    let len = sorted.len();
+    if len == 0 {
+        None
+    } else if len % 2 == 1 {
+        Some(f64::from(sorted[len / 2]))
+    } else {
+        Some((f64::from(sorted[len / 2 - 1]) + f64::from(sorted[len / 2])) / 2.0)
+    }
+}
+
+fn has_pdf(paper: &Paper) -> bool {
+    if paper.pdf_link.is_some() || paper.access == Access::FullText {
+        return true;
+    }
+
+    match paper.link {
+        Some(ref link) => {
+            let path = link.split(&['?', '#'][..]).next().unwrap_or("");
+            path.to_lowercase().ends_with(".pdf")
+        }
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn paper(citation_count: Option<u32>, year: Option<u32>, link: Option<&str>) -> Paper {
+        let mut paper = Paper::new("foo", 0);
+        paper.citation_count = citation_count.map(Into::into);
+        paper.year = year;
+        paper.link = link.map(ToOwned::to_owned);
+        paper
+    }
+
+    #[test]
+    fn page_stats_test() {
+        let mut full_text = paper(Some(7), None, None);
+        full_text.access = Access::FullText;
+
+        let papers = vec![
+            paper(Some(30), Some(1984), Some("http://example.com/paper.PDF?dl=1")),
+            paper(None, Some(1959), Some("https://example.com/abstract")),
+            paper(Some(10), None, None),
+            paper(Some(20), Some(2007), None),
+            full_text,
+        ];
+
+        assert_eq!(
+            page_stats(&papers),
+            PageStats {
+                count: 5,
+                total_citations: 67,
+                median_citations: Some(15.0),
+                oldest_year: Some(1959),
+                newest_year: Some(2007),
+                with_pdf: 2,
+            }
+        );
+    }
+
+    #[test]
+    fn page_stats_empty_test() {
+        assert_eq!(
+            page_stats(&[paper(None, None, None)]),
+            PageStats {
+                count: 1,
+                total_citations: 0,
+                median_citations: None,
+                oldest_year: None,
+                newest_year: None,
+                with_pdf: 0,
+            }
+        );
+    }
+}
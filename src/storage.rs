@@ -0,0 +1,312 @@
+//! Store papers and citations in a SQLite database, to resume long-running crawls.
+//!
+//! Requires the `sqlite` feature.
+
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use rusqlite::Connection;
+
+use graph::CitationGraph;
+use id::ClusterId;
+use paper::{CitationCount, Paper};
+use errors::*;
+
+/// Tables created by `Storage::open()` if missing.
+///
+/// Cluster IDs are stored as decimal text, since they may not fit in SQLite's signed integers.
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS papers (
+    cluster_id TEXT PRIMARY KEY NOT NULL,
+    title TEXT NOT NULL,
+    authors TEXT,
+    venue TEXT,
+    year INTEGER,
+    citation_count INTEGER,
+    citation_count_approximate INTEGER,
+    link TEXT,
+    pdf_link TEXT,
+    updated_at INTEGER NOT NULL
+);
+CREATE TABLE IF NOT EXISTS citations (
+    citer_id TEXT NOT NULL,
+    cited_id TEXT NOT NULL,
+    PRIMARY KEY (citer_id, cited_id)
+);
+CREATE INDEX IF NOT EXISTS citations_cited_id ON citations (cited_id);
+";
+
+/// SQLite database of papers keyed by cluster ID, and citations among them.
+///
+/// Storing a paper already stored updates the fields known in the new one,
+/// keeping the stored values of the others, like `Paper::merge()`.
+/// Papers without cluster ID cannot be told apart, so they are not stored.
+///
+/// # Example
+///
+/// ```
+/// use scholar::id::ClusterId;
+/// use scholar::paper::Paper;
+/// use scholar::storage::Storage;
+///
+/// let mut storage = Storage::open_in_memory().unwrap();
+///
+/// let mut paper = Paper::new("foo", 1);
+/// paper.citers = Some(vec![Paper::new("bar", 2)]);
+/// storage.upsert_paper(&paper).unwrap();
+///
+/// assert_eq!(storage.get_paper(ClusterId(2)).unwrap().unwrap().title, "bar");
+/// assert_eq!(storage.citers_of(ClusterId(1)).unwrap(), vec![ClusterId(2)]);
+/// ```
+#[derive(Debug)]
+pub struct Storage {
+    conn: Connection,
+}
+
+impl Storage {
+    /// Open a database file at `path`, creating it and the tables if missing.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::with_connection(Connection::open(path)?)
+    }
+
+    /// Open a database in memory, which is lost when dropped.
+    pub fn open_in_memory() -> Result<Self> {
+        Self::with_connection(Connection::open_in_memory()?)
+    }
+
+    fn with_connection(conn: Connection) -> Result<Self> {
+        conn.execute_batch(SCHEMA)?;
+        Ok(Self { conn })
+    }
+
+    /// Store `paper`, its `citers` recursively, and citations from the citers to it,
+    /// in a transaction.
+    ///
+    /// Returns whether `paper` itself is stored, i.e. it has a cluster ID.
+    pub fn upsert_paper(&mut self, paper: &Paper) -> Result<bool> {
+        let tx = self.conn.transaction()?;
+        let stored = upsert_paper_tree(&tx, paper, now())?;
+        tx.commit()?;
+        Ok(stored)
+    }
+
+    /// Store papers and citations of `graph` in a transaction.
+    pub fn upsert_graph(&mut self, graph: &CitationGraph) -> Result<()> {
+        let tx = self.conn.transaction()?;
+        let now = now();
+        for paper in graph.papers.values() {
+            upsert_paper_row(&tx, paper, now)?;
+        }
+        for &(citer, cited) in &graph.edges {
+            insert_citation(&tx, citer, cited)?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Store a citation from a paper of `citer` to a paper of `cited`.
+    ///
+    /// The papers need not be stored, so that citations can be recorded before their details.
+    pub fn insert_citation(&self, citer: ClusterId, cited: ClusterId) -> Result<()> {
+        insert_citation(&self.conn, citer, cited)
+    }
+
+    /// Stored paper of `cluster_id`, with fields stored in the database only.
+    /// `citers` are left `None`; see `citers_of()` instead.
+    pub fn get_paper(&self, cluster_id: ClusterId) -> Result<Option<Paper>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT title, authors, venue, year, citation_count, citation_count_approximate,
+                    link, pdf_link
+             FROM papers WHERE cluster_id = ?",
+        )?;
+        let mut papers = stmt.query_map(&[&cluster_id.to_string()], |row| {
+            let mut paper = Paper::new(&row.get::<_, String>(0), cluster_id.as_u64());
+            paper.authors = row.get(1);
+            paper.venue = row.get(2);
+            paper.year = row.get(3);
+            paper.citation_count = row.get::<_, Option<u32>>(4).map(|value| CitationCount {
+                value,
+                approximate: row.get::<_, Option<bool>>(5).unwrap_or(false),
+            });
+            paper.link = row.get(6);
+            paper.pdf_link = row.get(7);
+            paper
+        })?;
+
+        match papers.next() {
+            Some(paper) => Ok(Some(paper?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Whether a paper of `cluster_id` is stored, e.g. to skip fetching it again.
+    pub fn contains(&self, cluster_id: ClusterId) -> Result<bool> {
+        let count: i64 = self.conn.query_row(
+            "SELECT COUNT(*) FROM papers WHERE cluster_id = ?",
+            &[&cluster_id.to_string()],
+            |row| row.get(0),
+        )?;
+        Ok(count > 0)
+    }
+
+    /// Cluster IDs of papers citing a paper of `cited`, in ascending order.
+    pub fn citers_of(&self, cited: ClusterId) -> Result<Vec<ClusterId>> {
+        let mut stmt = self.conn
+            .prepare("SELECT citer_id FROM citations WHERE cited_id = ?")?;
+        let ids = stmt.query_map(&[&cited.to_string()], |row| row.get::<_, String>(0))?;
+
+        let mut citers = Vec::new();
+        for id in ids {
+            citers.push(id?.parse()?);
+        }
+        citers.sort();
+        Ok(citers)
+    }
+
+    /// Number of stored papers.
+    pub fn paper_count(&self) -> Result<u64> {
+        let count: i64 = self.conn
+            .query_row("SELECT COUNT(*) FROM papers", &[], |row| row.get(0))?;
+        Ok(count as u64)
+    }
+}
+
+fn upsert_paper_tree(conn: &Connection, paper: &Paper, now: i64) -> Result<bool> {
+    let stored = upsert_paper_row(conn, paper, now)?;
+    for citer in paper.citers.iter().flatten() {
+        upsert_paper_tree(conn, citer, now)?;
+        if let (Some(citer_id), Some(cited_id)) = (citer.cluster_id, paper.cluster_id) {
+            insert_citation(conn, citer_id, cited_id)?;
+        }
+    }
+    Ok(stored)
+}
+
+fn upsert_paper_row(conn: &Connection, paper: &Paper, now: i64) -> Result<bool> {
+    let cluster_id = match paper.cluster_id {
+        Some(id) => id.to_string(),
+        None => return Ok(false),
+    };
+    let citation_count = paper.citation_count.map(|c| c.value);
+    let approximate = paper.citation_count.map(|c| c.approximate);
+
+    conn.execute(
+        "INSERT OR IGNORE INTO papers (cluster_id, title, updated_at) VALUES (?, ?, ?)",
+        &[&cluster_id, &paper.title, &now],
+    )?;
+    conn.execute(
+        "UPDATE papers SET
+             title = ?,
+             authors = COALESCE(?, authors),
+             venue = COALESCE(?, venue),
+             year = COALESCE(?, year),
+             citation_count = COALESCE(?, citation_count),
+             citation_count_approximate = COALESCE(?, citation_count_approximate),
+             link = COALESCE(?, link),
+             pdf_link = COALESCE(?, pdf_link),
+             updated_at = ?
+         WHERE cluster_id = ?",
+        &[
+            &paper.title,
+            &paper.authors,
+            &paper.venue,
+            &paper.year,
+            &citation_count,
+            &approximate,
+            &paper.link,
+            &paper.pdf_link,
+            &now,
+            &cluster_id,
+        ],
+    )?;
+
+    Ok(true)
+}
+
+fn insert_citation(conn: &Connection, citer: ClusterId, cited: ClusterId) -> Result<()> {
+    conn.execute(
+        "INSERT OR IGNORE INTO citations (citer_id, cited_id) VALUES (?, ?)",
+        &[&citer.to_string(), &cited.to_string()],
+    )?;
+    Ok(())
+}
+
+/// Seconds since the Unix epoch.
+fn now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn storage_upsert_test() {
+        let mut storage = Storage::open_in_memory().unwrap();
+
+        let mut result = Paper::new("Quantal phase factors", 15_570_691_018_430_890_829);
+        result.authors = Some(String::from("MV Berry"));
+        result.year = Some(1984);
+        result.citation_count = Some(10.into());
+        assert!(storage.upsert_paper(&result).unwrap());
+
+        // Known fields are updated, and unknown ones are kept
+        let mut target = Paper::new("Quantal phase factors", 15_570_691_018_430_890_829);
+        target.citation_count = Some(CitationCount {
+            value: 12,
+            approximate: true,
+        });
+        target.link = Some(String::from("https://example.com/berry"));
+        target.citers = Some(vec![Paper::new("citer", 2), Paper::new("citer", 3)]);
+        assert!(storage.upsert_paper(&target).unwrap());
+
+        let mut no_id = Paper::new("no ID", 0);
+        no_id.set_cluster_id(None);
+        assert!(!storage.upsert_paper(&no_id).unwrap());
+
+        let stored = storage
+            .get_paper(ClusterId(15_570_691_018_430_890_829))
+            .unwrap()
+            .unwrap();
+        assert_eq!(stored.authors, Some(String::from("MV Berry")));
+        assert_eq!(stored.year, Some(1984));
+        assert_eq!(stored.citation_count, target.citation_count);
+        assert_eq!(stored.link, target.link);
+
+        assert_eq!(storage.paper_count().unwrap(), 3);
+        assert!(storage.contains(ClusterId(3)).unwrap());
+        assert!(!storage.contains(ClusterId(4)).unwrap());
+        assert_eq!(storage.get_paper(ClusterId(4)).unwrap(), None);
+        assert_eq!(
+            storage
+                .citers_of(ClusterId(15_570_691_018_430_890_829))
+                .unwrap(),
+            vec![ClusterId(2), ClusterId(3)]
+        );
+    }
+
+    #[test]
+    fn storage_graph_test() {
+        use graph::{crawl_citations, CrawlOptions};
+
+        let graph = crawl_citations(ClusterId(1), &CrawlOptions::default(), |id| {
+            let mut paper = Paper::new("foo", id.as_u64());
+            paper.citers = Some(vec![Paper::new("bar", 2)]);
+            Ok(paper)
+        }).unwrap();
+
+        let mut storage = Storage::open_in_memory().unwrap();
+        storage.upsert_graph(&graph).unwrap();
+        storage.upsert_graph(&graph).unwrap();
+        storage.insert_citation(ClusterId(5), ClusterId(1)).unwrap();
+
+        assert_eq!(storage.paper_count().unwrap(), 2);
+        assert_eq!(
+            storage.citers_of(ClusterId(1)).unwrap(),
+            vec![ClusterId(2), ClusterId(5)]
+        );
+    }
+}
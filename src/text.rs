@@ -0,0 +1,334 @@
+//! Clean up text scraped from HTML.
+
+use select::node::Node;
+use unicode_normalization::UnicodeNormalization;
+
+/// Configuration of how to clean up text scraped from HTML, like titles.
+///
+/// The default configuration decodes entities, strips invisible characters,
+/// collapses whitespaces, and strips highlights, but does not normalize Unicode,
+/// quotes, or ligatures, which may change text differing per locale.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TextCleaner {
+    /// Decode HTML entities left in text, like `&amp;` or `&#39;`.
+    pub decode_entities: bool,
+    /// Remove soft hyphens and zero-width spaces, which Google Scholar leaves in long words.
+    pub strip_invisible: bool,
+    /// Replace typographic quotes like `‘’` and `“”` with ASCII `'` and `"`.
+    pub normalize_quotes: bool,
+    /// Expand ligatures like `ﬁ` into their letters, as in PDF-derived titles.
+    pub expand_ligatures: bool,
+    /// Collapse each run of whitespaces, including no-break spaces, into one space,
+    /// and trim both ends.
+    pub collapse_whitespace: bool,
+    /// Normalize text into Unicode Normalization Form C.
+    pub normalize_unicode: bool,
+    /// Strip `<b>` tags with which Google Scholar highlights matched words.
+    /// If disabled, the tags are kept in the text.
+    pub strip_highlights: bool,
+}
+
+impl Default for TextCleaner {
+    fn default() -> Self {
+        Self {
+            decode_entities: true,
+            strip_invisible: true,
+            normalize_quotes: false,
+            expand_ligatures: false,
+            collapse_whitespace: true,
+            normalize_unicode: false,
+            strip_highlights: true,
+        }
+    }
+}
+
+impl TextCleaner {
+    /// Create a configuration enabling every cleanup, so that text scraped in any locale
+    /// compares equal as far as possible.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use scholar::text::TextCleaner;
+    ///
+    /// let cleaner = TextCleaner::thorough();
+    /// assert_eq!(
+    ///     cleaner.clean("\u{201c}E\u{fb03}cient\u{201d} adia\u{ad}batic\u{a0}changes"),
+    ///     "\"Efficient\" adiabatic changes"
+    /// );
+    /// ```
+    pub fn thorough() -> Self {
+        Self {
+            decode_entities: true,
+            strip_invisible: true,
+            normalize_quotes: true,
+            expand_ligatures: true,
+            collapse_whitespace: true,
+            normalize_unicode: true,
+            strip_highlights: true,
+        }
+    }
+
+    /// Get text of `node` and its descendants, cleaned up.
+    pub fn node_text(&self, node: &Node) -> String {
+        self.clean(&self.raw_text(node))
+    }
+
+    /// Get text of `node` and its descendants as is,
+    /// except that highlights are kept unless `strip_highlights` is enabled.
+    pub(crate) fn raw_text(&self, node: &Node) -> String {
+        if self.strip_highlights {
+            node.text()
+        } else {
+            highlighted_text(node)
+        }
+    }
+
+    /// Clean up `text` according to this configuration.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use scholar::text::TextCleaner;
+    ///
+    /// let cleaner = TextCleaner::default();
+    /// assert_eq!(
+    ///     cleaner.clean("  Quantum field theory\n    &amp; critical phenomena "),
+    ///     "Quantum field theory & critical phenomena"
+    /// );
+    /// ```
+    pub fn clean(&self, text: &str) -> String {
+        let mut text = if self.decode_entities {
+            decode_entities(text)
+        } else {
+            text.to_owned()
+        };
+
+        if self.strip_invisible || self.normalize_quotes || self.expand_ligatures {
+            let mut mapped = String::with_capacity(text.len());
+            for c in text.chars() {
+                self.push_mapped(&mut mapped, c);
+            }
+            text = mapped;
+        }
+
+        if self.normalize_unicode {
+            text = text.nfc().collect();
+        }
+
+        if self.collapse_whitespace {
+            text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+        }
+
+        text
+    }
+
+    fn push_mapped(&self, text: &mut String, c: char) {
+        match c {
+            '\u{ad}' | '\u{200b}' | '\u{2060}' | '\u{feff}' if self.strip_invisible => {}
+            '\u{2018}' | '\u{2019}' | '\u{201a}' | '\u{201b}' | '\u{2032}'
+                if self.normalize_quotes =>
+            {
+                text.push('\'')
+            }
+            '\u{201c}' | '\u{201d}' | '\u{201e}' | '\u{201f}' | '\u{2033}'
+                if self.normalize_quotes =>
+            {
+                text.push('"')
+            }
+            c => match ligature_letters(c) {
+                Some(letters) if self.expand_ligatures => text.push_str(letters),
+                _ => text.push(c),
+            },
+        }
+    }
+}
+
+/// Fold `text` into a form for loose comparison:
+/// lowercased, with diacritics and punctuation removed, and whitespaces collapsed.
+///
+/// # Example
+///
+/// ```
+/// use scholar::text::normalize_for_matching;
+///
+/// assert_eq!(normalize_for_matching("  Schrödinger's   Équation! "), "schrodingers equation");
+/// ```
+pub fn normalize_for_matching(text: &str) -> String {
+    use unicode_normalization::char::is_combining_mark;
+
+    let folded = text.nfd()
+        .filter(|&c| !is_combining_mark(c))
+        .filter(|&c| c.is_alphanumeric() || c.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .collect::<String>();
+    folded.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn ligature_letters(c: char) -> Option<&'static str> {
+    match c {
+        '\u{fb00}' => Some("ff"),
+        '\u{fb01}' => Some("fi"),
+        '\u{fb02}' => Some("fl"),
+        '\u{fb03}' => Some("ffi"),
+        '\u{fb04}' => Some("ffl"),
+        '\u{fb05}' | '\u{fb06}' => Some("st"),
+        _ => None,
+    }
+}
+
+fn highlighted_text(node: &Node) -> String {
+    if let Some(t) = node.as_text() {
+        return t.to_owned();
+    }
+
+    let inner = node.children()
+        .map(|n| highlighted_text(&n))
+        .collect::<String>();
+    if node.name() == Some("b") {
+        format!("<b>{}</b>", inner)
+    } else {
+        inner
+    }
+}
+
+fn decode_entities(text: &str) -> String {
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(amp) = rest.find('&') {
+        decoded.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+
+        let entity = rest.find(';')
+            .and_then(|semi| decode_entity(&rest[1..semi]).map(|c| (c, semi)));
+        match entity {
+            Some((c, semi)) => {
+                decoded.push(c);
+                rest = &rest[semi + 1..];
+            }
+            None => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+
+    decoded
+}
+
+fn decode_entity(name: &str) -> Option<char> {
+    use std::char;
+
+    match name {
+        "amp" => Some('&'),
+        "lt" => Some('<'),
+        "gt" => Some('>'),
+        "quot" => Some('"'),
+        "apos" => Some('\''),
+        "nbsp" => Some('\u{a0}'),
+        _ if name.starts_with("#x") || name.starts_with("#X") => u32::from_str_radix(&name[2..], 16)
+            .ok()
+            .and_then(char::from_u32),
+        _ if name.starts_with('#') => name[1..].parse().ok().and_then(char::from_u32),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_entities_test() {
+        assert_eq!(decode_entities("foo &amp; bar"), "foo & bar");
+        assert_eq!(
+            decode_entities("&lt;&gt;&quot;&#39;&#x41;&apos;"),
+            r#"<>"'A'"#
+        );
+        assert_eq!(decode_entities("AT&T; &foo; & &"), "AT&T; &foo; & &");
+    }
+
+    #[test]
+    fn clean_test() {
+        const TEXT: &str = " Schro\u{308}dinger  &amp;\n Dirac ";
+
+        let mut cleaner = TextCleaner::default();
+        assert_eq!(cleaner.clean(TEXT), "Schro\u{308}dinger & Dirac");
+
+        cleaner.normalize_unicode = true;
+        assert_eq!(cleaner.clean(TEXT), "Schr\u{f6}dinger & Dirac");
+
+        cleaner.collapse_whitespace = false;
+        assert_eq!(cleaner.clean(TEXT), " Schr\u{f6}dinger  &\n Dirac ");
+
+        cleaner.decode_entities = false;
+        assert_eq!(cleaner.clean(TEXT), " Schr\u{f6}dinger  &amp;\n Dirac ");
+    }
+
+    #[test]
+    fn clean_characters_test() {
+        const TEXT: &str = "\u{2018}Ge\u{ad}o\u{200b}metric\u{2019} \u{fb01}elds \u{201e}Feld\u{201c}";
+
+        let mut cleaner = TextCleaner::default();
+        assert_eq!(
+            cleaner.clean(TEXT),
+            "\u{2018}Geometric\u{2019} \u{fb01}elds \u{201e}Feld\u{201c}"
+        );
+
+        cleaner.normalize_quotes = true;
+        assert_eq!(cleaner.clean(TEXT), "'Geometric' \u{fb01}elds \"Feld\"");
+
+        cleaner.expand_ligatures = true;
+        assert_eq!(cleaner.clean(TEXT), "'Geometric' fields \"Feld\"");
+
+        cleaner.strip_invisible = false;
+        assert_eq!(cleaner.clean(TEXT), "'Ge\u{ad}o\u{200b}metric' fields \"Feld\"");
+
+        assert_eq!(
+            TextCleaner::default().clean("adiabatic\u{a0}\u{a0}changes&nbsp;"),
+            "adiabatic changes"
+        );
+    }
+
+    #[test]
+    fn normalize_for_matching_test() {
+        assert_eq!(
+            normalize_for_matching("Quantum Field Theory!"),
+            normalize_for_matching("quantum field theory")
+        );
+        // Both precomposed and decomposed forms
+        assert_eq!(normalize_for_matching("Schr\u{f6}dinger"), "schrodinger");
+        assert_eq!(normalize_for_matching("Schro\u{308}dinger"), "schrodinger");
+        assert_eq!(
+            normalize_for_matching("Berry\u{2019}s phase,\n  revisited"),
+            "berrys phase revisited"
+        );
+        assert_eq!(normalize_for_matching("量子 力学"), "量子 力学");
+        assert_eq!(normalize_for_matching(" ?! "), "");
+    }
+
+    #[test]
+    fn node_text_test() {
+        use select::document::Document;
+        use select::predicate::Name;
+
+        let doc = Document::from("<h3><b>Quantum </b>field <b>theory </b>and critical phenomena</h3>");
+        let node = doc.find(Name("h3")).nth(0).unwrap();
+        let highlight = doc.find(Name("b")).nth(0).unwrap();
+
+        let mut cleaner = TextCleaner::default();
+        assert_eq!(
+            cleaner.node_text(&node),
+            "Quantum field theory and critical phenomena"
+        );
+
+        cleaner.strip_highlights = false;
+        assert_eq!(
+            cleaner.node_text(&node),
+            "<b>Quantum </b>field <b>theory </b>and critical phenomena"
+        );
+        assert_eq!(cleaner.node_text(&highlight), "<b>Quantum </b>");
+    }
+}
@@ -0,0 +1,77 @@
+//! Utilities to handle papers scraped from several pages.
+
+use std::collections::HashMap;
+
+use id::ClusterId;
+use paper::Paper;
+
+/// Merge papers sharing a cluster ID, e.g. found in several pages of results.
+///
+/// The first occurrence of each cluster ID is kept in place,
+/// unless it lacks `citation_count` and a later duplicate has one,
+/// in which case the later one takes its place.
+/// Papers without cluster ID are all kept since they cannot be told apart.
+///
+/// # Example
+///
+/// ```
+/// use scholar::paper::Paper;
+/// use scholar::util::dedup_papers;
+///
+/// let papers = vec![Paper::new("foo", 1), Paper::new("bar", 2), Paper::new("foo", 1)];
+/// let papers = dedup_papers(papers);
+/// assert_eq!(papers, vec![Paper::new("foo", 1), Paper::new("bar", 2)]);
+/// ```
+pub fn dedup_papers(papers: Vec<Paper>) -> Vec<Paper> {
+    let mut deduped: Vec<Paper> = Vec::with_capacity(papers.len());
+    let mut positions: HashMap<ClusterId, usize> = HashMap::new();
+
+    for paper in papers {
+        let id = match paper.cluster_id {
+            Some(id) => id,
+            None => {
+                deduped.push(paper);
+                continue;
+            }
+        };
+
+        match positions.get(&id) {
+            Some(&i) => {
+                if deduped[i].citation_count.is_none() && paper.citation_count.is_some() {
+                    deduped[i] = paper;
+                }
+            }
+            None => {
+                positions.insert(id, deduped.len());
+                deduped.push(paper);
+            }
+        }
+    }
+
+    deduped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dedup_papers_test() {
+        let first = Paper::new("foo", 1);
+        let mut duplicate = Paper::new("foo (version)", 1);
+        duplicate.citation_count = Some(42.into());
+        let other = Paper::new("bar", 2);
+
+        let mut no_id = Paper::new("baz", 0);
+        no_id.set_cluster_id(None);
+
+        let papers = dedup_papers(vec![
+            first,
+            other.clone(),
+            no_id.clone(),
+            duplicate.clone(),
+            no_id.clone(),
+        ]);
+        assert_eq!(papers, vec![duplicate, other, no_id.clone(), no_id]);
+    }
+}
@@ -0,0 +1,262 @@
+//! Watch papers for citations added since the last run, e.g. to alert on new citations.
+
+use std::collections::HashSet;
+
+use id::ClusterId;
+use paper::Paper;
+#[cfg(feature = "sqlite")]
+use storage::Storage;
+use errors::*;
+
+/// A paper checked for new citations, and its citers known as of the last run.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WatchedPaper {
+    /// Paper as of the last run. `citation_count` is the baseline of the delta.
+    /// `citers` are ignored; see `known_citers` instead.
+    pub paper: Paper,
+    /// Cluster IDs of papers known to cite `paper`.
+    pub known_citers: Vec<ClusterId>,
+}
+
+impl WatchedPaper {
+    /// Watch `paper` stored as is, e.g. loaded from JSON,
+    /// taking its `citers` with cluster ID as the known citers.
+    pub fn from_paper(paper: Paper) -> Self {
+        let known_citers = paper
+            .citers
+            .iter()
+            .flatten()
+            .filter_map(|citer| citer.cluster_id)
+            .collect();
+        Self {
+            paper,
+            known_citers,
+        }
+    }
+
+    /// Watch a paper of `cluster_id` stored in `storage`, with citers stored for it.
+    ///
+    /// Returns `None` if the paper is not stored.
+    #[cfg(feature = "sqlite")]
+    pub fn from_storage(storage: &Storage, cluster_id: ClusterId) -> Result<Option<Self>> {
+        let paper = match storage.get_paper(cluster_id)? {
+            Some(paper) => paper,
+            None => return Ok(None),
+        };
+        Ok(Some(Self {
+            paper,
+            known_citers: storage.citers_of(cluster_id)?,
+        }))
+    }
+}
+
+/// Changes in citations of a watched paper since the last run.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CitationUpdate {
+    /// Paper as fetched now, with `citers` listed in its citation page.
+    /// Store it to make the next run start from here.
+    pub paper: Paper,
+    /// Citation count as of the last run, if known.
+    pub previous_count: Option<u32>,
+    /// Citers not known as of the last run, in the order listed.
+    pub new_citers: Vec<Paper>,
+}
+
+impl CitationUpdate {
+    /// Citation count as fetched now, if shown.
+    pub fn current_count(&self) -> Option<u32> {
+        self.paper.citation_count.map(|c| c.value)
+    }
+
+    /// Change of the citation count since the last run, negative if Google Scholar merged
+    /// or dropped citers. An unknown count is taken as 0.
+    pub fn count_delta(&self) -> i64 {
+        i64::from(self.current_count().unwrap_or(0)) - i64::from(self.previous_count.unwrap_or(0))
+    }
+
+    /// Whether the paper gained citers or its citation count changed.
+    pub fn has_changes(&self) -> bool {
+        !self.new_citers.is_empty() || self.count_delta() != 0
+    }
+}
+
+/// Compare `current`, the watched paper as fetched now with `citers`, against `watched`.
+///
+/// Citers without cluster ID cannot be told apart, so they are never reported as new.
+///
+/// # Example
+///
+/// ```
+/// use scholar::paper::Paper;
+/// use scholar::watch::{diff_citations, WatchedPaper};
+///
+/// let mut previous = Paper::new("foo", 1);
+/// previous.citation_count = Some(1.into());
+/// previous.citers = Some(vec![Paper::new("bar", 2)]);
+/// let watched = WatchedPaper::from_paper(previous);
+///
+/// let mut current = Paper::new("foo", 1);
+/// current.citation_count = Some(2.into());
+/// current.citers = Some(vec![Paper::new("baz", 3), Paper::new("bar", 2)]);
+///
+/// let update = diff_citations(&watched, current);
+/// assert_eq!(update.count_delta(), 1);
+/// assert_eq!(update.new_citers, vec![Paper::new("baz", 3)]);
+/// ```
+pub fn diff_citations(watched: &WatchedPaper, current: Paper) -> CitationUpdate {
+    let known = watched.known_citers.iter().collect::<HashSet<_>>();
+    let new_citers = current
+        .citers
+        .iter()
+        .flatten()
+        .filter(|citer| match citer.cluster_id {
+            Some(ref id) => !known.contains(id),
+            None => false,
+        })
+        .cloned()
+        .collect();
+
+    CitationUpdate {
+        paper: current,
+        previous_count: watched.paper.citation_count.map(|c| c.value),
+        new_citers,
+    }
+}
+
+/// Fetch each of `watched` papers with `fetch` and compare it with `diff_citations()`,
+/// in the order of `watched`, paired with the cluster ID of the paper.
+///
+/// `fetch` takes a cluster ID and returns the paper with `citers` populated,
+/// e.g. by `CitationDocument::scrape_target_paper_with_citers()`.
+/// `citers` should be as many as `known_citers` were collected, or else citers known but
+/// not fetched are missed and ones on later pages are reported as new next time.
+/// Papers without cluster ID have no citation page, so they are skipped.
+/// An error of `fetch` is reported for its paper only, and the others are still checked.
+///
+/// # Example
+///
+/// ```
+/// use scholar::paper::Paper;
+/// use scholar::watch::{check_citations, WatchedPaper};
+///
+/// let watched = vec![WatchedPaper::from_paper(Paper::new("foo", 1))];
+/// let updates = check_citations(&watched, |id| {
+///     let mut paper = Paper::new("foo", id.as_u64());
+///     paper.citers = Some(vec![Paper::new("bar", 2)]);
+///     Ok(paper)
+/// });
+/// let (id, ref update) = updates[0];
+/// assert_eq!(id.as_u64(), 1);
+/// assert_eq!(update.as_ref().unwrap().new_citers, vec![Paper::new("bar", 2)]);
+/// ```
+pub fn check_citations<F>(
+    watched: &[WatchedPaper],
+    mut fetch: F,
+) -> Vec<(ClusterId, Result<CitationUpdate>)>
+where
+    F: FnMut(ClusterId) -> Result<Paper>,
+{
+    watched
+        .iter()
+        .filter_map(|w| w.paper.cluster_id.map(|id| (w, id)))
+        .map(|(w, id)| {
+            let update = fetch(id).map(|current| diff_citations(w, current));
+            if let Ok(ref update) = update {
+                if update.has_changes() {
+                    info!(
+                        "{}: {} new citers, citation count {:+}",
+                        id,
+                        update.new_citers.len(),
+                        update.count_delta()
+                    );
+                }
+            }
+            (id, update)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use scrape::BlockKind;
+
+    #[test]
+    fn check_citations_test() {
+        let mut foo = Paper::new("foo", 1);
+        foo.citation_count = Some(3.into());
+        foo.citers = Some(vec![Paper::new("bar", 2), Paper::new("baz", 3)]);
+        let mut no_id = Paper::new("no ID", 0);
+        no_id.set_cluster_id(None);
+        let watched = vec![
+            WatchedPaper::from_paper(foo),
+            WatchedPaper::from_paper(no_id),
+            WatchedPaper::from_paper(Paper::new("qux", 4)),
+            WatchedPaper::from_paper(Paper::new("quux", 5)),
+        ];
+        assert_eq!(watched[0].known_citers, vec![ClusterId(2), ClusterId(3)]);
+
+        let mut fetched = Vec::new();
+        let updates = check_citations(&watched, |id| {
+            fetched.push(id);
+            let mut paper = Paper::new("fetched", id.as_u64());
+            match id.as_u64() {
+                1 => {
+                    let mut citer_without_id = Paper::new("anonymous", 0);
+                    citer_without_id.set_cluster_id(None);
+                    paper.citation_count = Some(2.into());
+                    paper.citers = Some(vec![
+                        Paper::new("baz", 3),
+                        citer_without_id,
+                        Paper::new("corge", 6),
+                    ]);
+                }
+                4 => return Err(ErrorKind::Blocked(BlockKind::Captcha).into()),
+                _ => {}
+            }
+            Ok(paper)
+        });
+        assert_eq!(fetched, vec![ClusterId(1), ClusterId(4), ClusterId(5)]);
+        assert_eq!(
+            updates.iter().map(|&(id, _)| id).collect::<Vec<_>>(),
+            vec![ClusterId(1), ClusterId(4), ClusterId(5)]
+        );
+
+        let update = updates[0].1.as_ref().unwrap();
+        assert_eq!(update.previous_count, Some(3));
+        assert_eq!(update.current_count(), Some(2));
+        assert_eq!(update.count_delta(), -1);
+        assert_eq!(update.new_citers, vec![Paper::new("corge", 6)]);
+        assert!(update.has_changes());
+
+        match updates[1].1 {
+            Err(Error(ErrorKind::Blocked(_), _)) => {}
+            ref result => panic!("unexpected result: {:?}", result),
+        }
+
+        let update = updates[2].1.as_ref().unwrap();
+        assert_eq!(update.count_delta(), 0);
+        assert!(!update.has_changes());
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[test]
+    fn watched_paper_from_storage_test() {
+        let mut storage = Storage::open_in_memory().unwrap();
+        let mut paper = Paper::new("foo", 1);
+        paper.citation_count = Some(1.into());
+        paper.citers = Some(vec![Paper::new("bar", 2)]);
+        storage.upsert_paper(&paper).unwrap();
+
+        let watched = WatchedPaper::from_storage(&storage, ClusterId(1))
+            .unwrap()
+            .unwrap();
+        assert_eq!(watched.paper.citation_count, Some(1.into()));
+        assert_eq!(watched.known_citers, vec![ClusterId(2)]);
+        assert_eq!(
+            WatchedPaper::from_storage(&storage, ClusterId(3)).unwrap(),
+            None
+        );
+    }
+}